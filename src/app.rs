@@ -3,8 +3,13 @@
 use std::io;
 
 use crate::event::{EventHandler, TerminalEvent};
-use crate::network::handler::NetworkHandler;
-use crate::store::{get_world_size, reset, save_world};
+use crate::network::config::NetworkConfig;
+use crate::network::handler::{NetworkEvent, NetworkHandler};
+use crate::store::{
+    get_world_size, load_or_generate_keypair, newest_valid_world_backup, reset,
+    restore_world_backup, save_to_json, save_world, PERSISTED_DESYNC_PREFIX,
+    PERSISTED_KEYPAIR_FILENAME,
+};
 use crate::tui::Tui;
 use crate::types::{AppResult, SystemTimeTick, Tick};
 use crate::ui::ui::Ui;
@@ -12,11 +17,9 @@ use crate::ui::utils::SwarmPanelEvent;
 use crate::world::world::World;
 use crossterm::event::{KeyCode, KeyModifiers};
 use futures::StreamExt;
-use libp2p::{gossipsub, swarm::SwarmEvent};
 use ratatui::backend::CrosstermBackend;
 use ratatui::Terminal;
 use tokio::select;
-use void::Void;
 
 pub struct App {
     pub world: World,
@@ -24,7 +27,8 @@ pub struct App {
     pub ui: Ui,
     generate_local_world: bool,
     pub network_handler: Option<NetworkHandler>,
-    seed_ip: Option<String>,
+    network_config: NetworkConfig,
+    rotate_keypair: bool,
 }
 
 impl App {
@@ -34,7 +38,8 @@ impl App {
         disable_audio: bool,
         generate_local_world: bool,
         reset_world: bool,
-        seed_ip: Option<String>,
+        network_config: NetworkConfig,
+        rotate_keypair: bool,
     ) -> Self {
         // If the reset_world flag is set, reset the world.
         if reset_world {
@@ -47,7 +52,8 @@ impl App {
             ui,
             generate_local_world,
             network_handler: None,
-            seed_ip,
+            network_config,
+            rotate_keypair,
         }
     }
 
@@ -60,7 +66,7 @@ impl App {
         ratatui.init()?;
         while self.running {
             if self.network_handler.is_none() && (self.world.has_own_team()) {
-                self.initialize_network_handler(self.seed_ip.clone());
+                self.initialize_network_handler();
             }
             //FIXME consolidate this into a single select! macro
             if self.network_handler.is_some() {
@@ -95,8 +101,15 @@ impl App {
         Ok(())
     }
 
-    pub fn initialize_network_handler(&mut self, seed_ip: Option<String>) {
-        let handler = NetworkHandler::new(seed_ip);
+    pub fn initialize_network_handler(&mut self) {
+        let local_key = load_or_generate_keypair(PERSISTED_KEYPAIR_FILENAME, self.rotate_keypair);
+        if local_key.is_err() {
+            eprintln!("Failed to load network keypair");
+            return;
+        }
+        // Only rotate once, reconnections keep the new identity.
+        self.rotate_keypair = false;
+        let handler = NetworkHandler::new(local_key.unwrap(), &self.network_config);
         if handler.is_err() {
             eprintln!("Failed to initialize network handler");
         } else {
@@ -111,31 +124,45 @@ impl App {
         }
     }
 
-    pub fn load_world(&mut self) {
-        // Try to load an existing world.
-        let try_load = World::load();
-        if let Ok(loaded_world) = try_load {
-            self.world = loaded_world;
-        } else {
-            panic!("Failed to load world: {}", try_load.err().unwrap());
+    pub fn load_world(&mut self) -> AppResult<()> {
+        // Try to load an existing world, migrating saves from older versions.
+        match World::load() {
+            Ok(world) => self.start_world(world),
+            Err(err) => {
+                // Offer to recover from the newest backup that can still be loaded.
+                if let Some((timestamp, filename)) = newest_valid_world_backup() {
+                    self.ui.splash_screen.set_backup(timestamp, filename);
+                    return Err(format!(
+                        "Failed to load world: {}\nA backup from {} can be recovered",
+                        err,
+                        timestamp.formatted_as_date()
+                    )
+                    .into());
+                }
+                Err(format!("Failed to load world: {}", err).into())
+            }
         }
+    }
 
-        let simulation = self.world.simulate_until_now();
+    pub fn recover_world_backup(&mut self, filename: &str) -> AppResult<()> {
+        let world = restore_world_backup(filename)?;
+        self.start_world(world)
+    }
 
-        match simulation {
-            Ok(messages) => {
-                for message in messages.iter() {
-                    self.ui.set_popup(crate::ui::ui::PopupMessage::Ok(
-                        message.clone(),
-                        Tick::now(),
-                    ));
-                }
-            }
-            Err(_) => {
-                panic!("Failed to simulate world");
-            }
+    fn start_world(&mut self, world: World) -> AppResult<()> {
+        self.world = world;
+        let messages = self
+            .world
+            .simulate_until_now()
+            .map_err(|e| format!("Failed to simulate world: {}", e))?;
+        for message in messages.iter() {
+            self.ui.set_popup(crate::ui::ui::PopupMessage::Ok(
+                message.clone(),
+                Tick::now(),
+            ));
         }
-        self.world.serialized_size = get_world_size().expect("Failed to get world size");
+        self.world.serialized_size = get_world_size()?;
+        Ok(())
     }
 
     /// Set running to false to quit the application.
@@ -201,7 +228,16 @@ impl App {
             });
         }
 
-        // Send own team to peers if dirty
+        self.verify_network_games();
+        self.cleanup_challenges(current_timestamp);
+
+        self.send_own_team_if_dirty();
+
+        Ok(())
+    }
+
+    /// Sends the own team to peers after it changed.
+    pub fn send_own_team_if_dirty(&mut self) {
         if self.world.dirty_network && self.world.has_own_team() {
             self.world.dirty_network = false;
             if let Some(network_handler) = &mut self.network_handler {
@@ -220,8 +256,56 @@ impl App {
                 }
             }
         }
+    }
 
-        Ok(())
+    /// Exchanges checksums of network games with peers and reports desyncs.
+    pub fn verify_network_games(&mut self) {
+        let Some(network_handler) = &mut self.network_handler else {
+            return;
+        };
+        if self.world.has_own_team() {
+            if let Err(err) = network_handler.send_game_checksum(&self.world) {
+                self.ui.swarm_panel.push_log_event(SwarmPanelEvent {
+                    timestamp: Tick::now(),
+                    peer_id: None,
+                    text: format!("Failed to send game checksum: {}", err),
+                });
+            }
+        }
+
+        for desync in network_handler.verify_game_checksums(&self.world) {
+            let filename = format!("{}{}.json", PERSISTED_DESYNC_PREFIX, desync.game_id);
+            let text = match save_to_json(&filename, &desync) {
+                Ok(_) => format!("{}. Diagnostics saved to {}", desync.description(), filename),
+                Err(err) => format!(
+                    "{}. Failed to save diagnostics: {}",
+                    desync.description(),
+                    err
+                ),
+            };
+            self.ui.swarm_panel.push_log_event(SwarmPanelEvent {
+                timestamp: Tick::now(),
+                peer_id: Some(desync.peer_id),
+                text: text.clone(),
+            });
+            self.ui
+                .set_popup(crate::ui::ui::PopupMessage::Error(text, Tick::now()));
+        }
+    }
+
+    /// Drops challenges that were not answered in time.
+    fn cleanup_challenges(&mut self, now: Tick) {
+        let Some(network_handler) = &mut self.network_handler else {
+            return;
+        };
+        for challenge in network_handler.cleanup_challenges(now) {
+            self.ui.swarm_panel.remove_challenge(&challenge.home_peer_id);
+            self.ui.swarm_panel.push_log_event(SwarmPanelEvent {
+                timestamp: now,
+                peer_id: None,
+                text: format!("Challenge expired: {}", challenge.format()),
+            });
+        }
     }
 
     pub fn handle_key_events(&mut self, key_event: crossterm::event::KeyEvent) -> AppResult<()> {
@@ -280,7 +364,7 @@ impl App {
 
     pub fn handle_network_events(
         &mut self,
-        network_event: SwarmEvent<gossipsub::Event, Void>,
+        network_event: NetworkEvent,
     ) -> AppResult<()> {
         if let Some(network_handler) = &mut self.network_handler {
             if let Some(callback) = network_handler.handle_network_events(network_event) {
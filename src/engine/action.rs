@@ -1,5 +1,7 @@
 use super::{
+    brawl::Brawl,
     end_of_quarter::EndOfQuarter,
+    free_throw::FreeThrow,
     game::Game,
     isolation::Isolation,
     jump_ball::JumpBall,
@@ -11,9 +13,11 @@ use super::{
     start_of_quarter::StartOfQuarter,
     substitution::Substitution,
     timer::Timer,
-    types::{GameStatsMap, Possession},
+    types::{GameStats, GameStatsMap, Possession},
 };
+use crate::types::GameId;
 use core::fmt::Debug;
+use itertools::Itertools;
 use rand_chacha::ChaCha8Rng;
 use serde::{Deserialize, Serialize};
 use serde_repr::{Deserialize_repr, Serialize_repr};
@@ -59,12 +63,58 @@ pub struct ActionOutput {
     pub defense_stats_update: Option<GameStatsMap>,
     pub foul_from: Option<usize>,
     pub foul_on: Option<usize>,
+    #[serde(default)]
+    pub free_throws: u8,
     pub home_score: u16,
     pub away_score: u16,
     pub score_change: u8,
     pub possession: Possession,
 }
 
+const FNV_OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+const FNV_PRIME: u64 = 0x100000001b3;
+
+impl ActionOutput {
+    /// Returns a checksum that is stable across platforms and builds,
+    /// so that peers can check that they simulated the same action.
+    pub fn checksum(&self) -> u64 {
+        // Stats maps are sorted since the iteration order of a HashMap is random.
+        fn sorted_stats(stats: &Option<GameStatsMap>) -> Option<Vec<(&GameId, &GameStats)>> {
+            stats
+                .as_ref()
+                .map(|s| s.iter().sorted_by_key(|(id, _)| **id).collect_vec())
+        }
+        let data = serde_json::to_vec(&(
+            (
+                &self.random_seed,
+                self.advantage,
+                &self.attackers,
+                &self.defenders,
+                self.assist_from,
+                &self.situation,
+                &self.description,
+                &self.start_at,
+                &self.end_at,
+            ),
+            (
+                sorted_stats(&self.attack_stats_update),
+                sorted_stats(&self.defense_stats_update),
+                self.foul_from,
+                self.foul_on,
+                self.free_throws,
+                self.home_score,
+                self.away_score,
+                self.score_change,
+                &self.possession,
+            ),
+        ))
+        .unwrap_or_default();
+        data.iter().fold(FNV_OFFSET_BASIS, |hash, &byte| {
+            (hash ^ byte as u64).wrapping_mul(FNV_PRIME)
+        })
+    }
+}
+
 #[derive(Debug, Serialize_repr, Deserialize_repr, Clone, Default)]
 #[repr(u8)]
 pub enum Action {
@@ -81,6 +131,8 @@ pub enum Action {
     MediumShot,
     LongShot,
     Substitution,
+    FreeThrow,
+    Brawl,
 }
 
 impl Action {
@@ -98,6 +150,8 @@ impl Action {
             Action::MediumShot => "Medium Shot".into(),
             Action::LongShot => "Long Shot".into(),
             Action::Substitution => "Substitution".into(),
+            Action::FreeThrow => "Free Throw".into(),
+            Action::Brawl => "Brawl".into(),
         }
     }
     pub fn execute(
@@ -119,6 +173,8 @@ impl Action {
             Action::MediumShot => MediumShot.execute(input, game, rng),
             Action::LongShot => LongShot.execute(input, game, rng),
             Action::Substitution => Substitution.execute(input, game, rng),
+            Action::FreeThrow => FreeThrow.execute(input, game, rng),
+            Action::Brawl => Brawl.execute(input, game, rng),
         };
         if output.is_some() {
             output.as_mut().unwrap().random_seed = rng.get_seed();
@@ -0,0 +1,306 @@
+use super::{
+    action::{ActionOutput, ActionSituation},
+    constants::{
+        TirednessCost, BRAWL_BASE_PROBABILITY, BRAWL_CALM_DOWN_DIFFICULTY, BRAWL_INJURY_LIMIT,
+        BRAWL_INJURY_SEVERITY, BRAWL_INJURY_TIREDNESS, BRAWL_KILL_LIMIT, BRAWL_KNOCKOUT_LIMIT,
+        MAX_TIREDNESS,
+    },
+    game::Game,
+    types::{GameStats, GameStatsMap},
+    utils::roll,
+};
+use crate::world::{
+    constants::MAX_INJURY_SEVERITY,
+    player::Player,
+    skill::{GameSkill, MAX_SKILL},
+};
+use rand::{seq::SliceRandom, Rng};
+use rand_chacha::ChaCha8Rng;
+use std::collections::HashMap;
+
+#[derive(Debug, Default)]
+pub struct Brawl;
+
+/// How a fight ends, depending on how much stronger the winner was.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum BrawlOutcome {
+    TradePunches,
+    Injury,
+    Knockout,
+    Kill,
+}
+
+impl BrawlOutcome {
+    fn from_fight(fight: i16) -> Self {
+        match fight.abs() {
+            x if x > BRAWL_KILL_LIMIT => Self::Kill,
+            x if x > BRAWL_KNOCKOUT_LIMIT => Self::Knockout,
+            x if x > BRAWL_INJURY_LIMIT => Self::Injury,
+            _ => Self::TradePunches,
+        }
+    }
+
+    fn apply(&self, winner: &Player, loser_update: &mut GameStats) {
+        match self {
+            Self::Kill => {
+                // Together with the knockout injury this is beyond recovery.
+                loser_update.knocked_out_by = Some(winner.id);
+                loser_update.tiredness = MAX_TIREDNESS;
+                loser_update.injury_severity += MAX_INJURY_SEVERITY;
+            }
+            Self::Knockout => {
+                loser_update.knocked_out_by = Some(winner.id);
+                loser_update.tiredness = MAX_TIREDNESS;
+            }
+            Self::Injury => {
+                loser_update.tiredness += BRAWL_INJURY_TIREDNESS;
+                loser_update.injury_severity += BRAWL_INJURY_SEVERITY;
+            }
+            Self::TradePunches => {}
+        }
+    }
+}
+
+pub fn is_brawl(rng: &mut ChaCha8Rng, fouled: &Player, fouler: &Player) -> bool {
+    // Pirates with low charisma are more hot-headed.
+    let average_charisma = (fouled.mental.charisma + fouler.mental.charisma) as f64 / 2.0;
+    let hotheadedness = 2.0 - average_charisma / MAX_SKILL as f64;
+    rng.gen_bool((BRAWL_BASE_PROBABILITY * hotheadedness).min(1.0))
+}
+
+impl Brawl {
+    pub fn execute(
+        &self,
+        input: &ActionOutput,
+        game: &Game,
+        rng: &mut ChaCha8Rng,
+    ) -> Option<ActionOutput> {
+        let attacking_players = game.attacking_players();
+        let defending_players = game.defending_players();
+        let attacking_stats = game.attacking_stats();
+        let defending_stats = game.defending_stats();
+
+        let fouled_idx = input.foul_on?;
+        let fouler_idx = input.foul_from?;
+        let fouled = *attacking_players.get(fouled_idx)?;
+        let fouler = *defending_players.get(fouler_idx)?;
+        let fouled_stats = attacking_stats.get(&fouled.id)?;
+        let fouler_stats = defending_stats.get(&fouler.id)?;
+
+        let mut fouled_update = GameStats::default();
+        fouled_update.add_tiredness(TirednessCost::MEDIUM, fouled.athleticism.stamina);
+        fouled_update.technical_fouls = 1;
+
+        let mut fouler_update = GameStats::default();
+        fouler_update.add_tiredness(TirednessCost::MEDIUM, fouler.athleticism.stamina);
+        fouler_update.technical_fouls = 1;
+
+        // The game resumes as it would have after the foul.
+        let mut result = ActionOutput {
+            possession: input.possession.clone(),
+            attackers: input.attackers.clone(),
+            situation: input.situation.clone(),
+            foul_on: input.foul_on,
+            free_throws: input.free_throws,
+            start_at: input.end_at,
+            end_at: input.end_at.plus(10 + rng.gen_range(0..=10)),
+            home_score: input.home_score,
+            away_score: input.away_score,
+            ..Default::default()
+        };
+
+        let calm_down = roll(rng, 0.0)
+            + ((fouled.mental.charisma.value() + fouler.mental.charisma.value()) / 2);
+
+        if calm_down > BRAWL_CALM_DOWN_DIFFICULTY {
+            result.description = [
+                format!(
+                    "{} and {} exchange some harsh words. Technical foul on both.",
+                    fouled.info.last_name, fouler.info.last_name
+                ),
+                format!(
+                    "{} shoves {} after the foul, teammates step in before it gets worse. Double technical.",
+                    fouled.info.last_name, fouler.info.last_name
+                ),
+            ]
+            .choose(rng)?
+            .clone();
+        } else {
+            let atk_result = roll(rng, fouled_stats.tiredness) + fouled.athleticism.strength.value();
+            let def_result = roll(rng, fouler_stats.tiredness) + fouler.athleticism.strength.value();
+            let fight = atk_result as i16 - def_result as i16;
+
+            let (winner, loser, loser_update) = if fight >= 0 {
+                (fouled, fouler, &mut fouler_update)
+            } else {
+                (fouler, fouled, &mut fouled_update)
+            };
+
+            let outcome = BrawlOutcome::from_fight(fight);
+            outcome.apply(winner, loser_update);
+            result.description = match outcome {
+                BrawlOutcome::Kill => format!(
+                    "A brawl breaks out! {} beats {} to death in front of the crowd.",
+                    winner.info.last_name, loser.info.last_name
+                ),
+                BrawlOutcome::Knockout => [
+                    format!(
+                        "A brawl breaks out! {} lands a terrible punch and {} is knocked out cold.",
+                        winner.info.last_name, loser.info.last_name
+                    ),
+                    format!(
+                        "{} and {} start fighting! {} hits the floor and does not get up.",
+                        winner.info.last_name, loser.info.last_name, loser.info.last_name
+                    ),
+                ]
+                .choose(rng)?
+                .clone(),
+                BrawlOutcome::Injury => format!(
+                    "A brawl breaks out! {} gets the better of {}, who is left limping.",
+                    winner.info.last_name, loser.info.last_name
+                ),
+                BrawlOutcome::TradePunches => format!(
+                    "{} and {} trade punches before being separated. Technical foul on both.",
+                    fouled.info.last_name, fouler.info.last_name
+                ),
+            };
+        }
+
+        // Free throws are lost if the fouled player cannot shoot them.
+        if fouled_update.knocked_out_by.is_some() {
+            result.situation = ActionSituation::BallInBackcourt;
+            result.attackers = vec![];
+            result.foul_on = None;
+            result.free_throws = 0;
+        }
+
+        let mut attack_stats_update: GameStatsMap = HashMap::new();
+        attack_stats_update.insert(fouled.id, fouled_update);
+        let mut defense_stats_update: GameStatsMap = HashMap::new();
+        defense_stats_update.insert(fouler.id, fouler_update);
+        result.attack_stats_update = Some(attack_stats_update);
+        result.defense_stats_update = Some(defense_stats_update);
+        Some(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Brawl, BrawlOutcome};
+    use crate::engine::{
+        action::ActionOutput,
+        constants::{
+            BRAWL_INJURY_LIMIT, BRAWL_INJURY_SEVERITY, BRAWL_KILL_LIMIT, BRAWL_KNOCKOUT_LIMIT,
+        },
+        types::{GameStats, Possession},
+    };
+    use crate::test_utils::test_world_and_game;
+    use crate::world::skill::MAX_SKILL;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    #[test]
+    fn test_brawl_outcome_thresholds() {
+        let cases = [
+            (0, BrawlOutcome::TradePunches),
+            (BRAWL_INJURY_LIMIT, BrawlOutcome::TradePunches),
+            (BRAWL_INJURY_LIMIT + 1, BrawlOutcome::Injury),
+            (BRAWL_KNOCKOUT_LIMIT, BrawlOutcome::Injury),
+            (BRAWL_KNOCKOUT_LIMIT + 1, BrawlOutcome::Knockout),
+            (BRAWL_KILL_LIMIT, BrawlOutcome::Knockout),
+            (BRAWL_KILL_LIMIT + 1, BrawlOutcome::Kill),
+        ];
+        for (fight, outcome) in cases {
+            // The outcome only depends on the gap, not on who won.
+            assert_eq!(BrawlOutcome::from_fight(fight), outcome);
+            assert_eq!(BrawlOutcome::from_fight(-fight), outcome);
+        }
+    }
+
+    #[test]
+    fn test_brawl_outcome_effects() {
+        let (_, game) = test_world_and_game();
+        let winner = game.attacking_players()[0];
+        let loser = game.defending_players()[0];
+        for outcome in [
+            BrawlOutcome::TradePunches,
+            BrawlOutcome::Injury,
+            BrawlOutcome::Knockout,
+            BrawlOutcome::Kill,
+        ] {
+            let mut stats = GameStats::default();
+            outcome.apply(winner, &mut stats);
+            let mut loser = loser.clone();
+            loser.apply_end_of_game_logic(&stats);
+
+            assert_eq!(
+                stats.is_knocked_out(),
+                matches!(outcome, BrawlOutcome::Knockout | BrawlOutcome::Kill)
+            );
+            assert_eq!(loser.is_injured(), outcome != BrawlOutcome::TradePunches);
+            assert_eq!(loser.is_dead(), outcome == BrawlOutcome::Kill);
+            if outcome == BrawlOutcome::Injury {
+                assert_eq!(stats.injury_severity, BRAWL_INJURY_SEVERITY);
+            }
+        }
+    }
+
+    #[test]
+    fn test_brawl_skill_gap() {
+        let (_, mut game) = test_world_and_game();
+        game.possession = Possession::Home;
+        // Hot-headed pirates, with the fouled team much stronger than the foulers.
+        for player in game.home_team_in_game.players.values_mut() {
+            player.mental.charisma = 0.0;
+            player.athleticism.strength = MAX_SKILL;
+        }
+        for player in game.away_team_in_game.players.values_mut() {
+            player.mental.charisma = 0.0;
+            player.athleticism.strength = 0.0;
+        }
+        let input = ActionOutput {
+            foul_from: Some(0),
+            foul_on: Some(0),
+            ..Default::default()
+        };
+        let fouled = game.attacking_players()[0].id;
+        let fouler = game.defending_players()[0].id;
+
+        let is_hurt = |stats: &GameStats| stats.injury_severity > 0.0 || stats.is_knocked_out();
+        let mut fouler_hurt = 0;
+        let mut fouled_hurt = 0;
+        for seed in 0..100 {
+            let rng = &mut ChaCha8Rng::seed_from_u64(seed);
+            let output = Brawl.execute(&input, &game, rng).unwrap();
+            let fouled_stats = &output.attack_stats_update.unwrap()[&fouled];
+            let fouler_stats = &output.defense_stats_update.unwrap()[&fouler];
+            assert_eq!(fouled_stats.technical_fouls, 1);
+            assert_eq!(fouler_stats.technical_fouls, 1);
+            // Knocked out players cannot shoot their free throws.
+            if fouled_stats.is_knocked_out() {
+                assert_eq!(output.free_throws, 0);
+                assert!(output.foul_on.is_none());
+            }
+            if is_hurt(fouler_stats) {
+                fouler_hurt += 1;
+            }
+            if is_hurt(fouled_stats) {
+                fouled_hurt += 1;
+            }
+        }
+        // The stronger side wins most fights.
+        assert!(fouler_hurt > fouled_hurt);
+    }
+
+    #[test]
+    fn test_brawl_out_of_court_players() {
+        let (_, game) = test_world_and_game();
+        let input = ActionOutput {
+            foul_from: Some(5),
+            foul_on: Some(0),
+            ..Default::default()
+        };
+        let rng = &mut ChaCha8Rng::seed_from_u64(0);
+        assert!(Brawl.execute(&input, &game, rng).is_none());
+    }
+}
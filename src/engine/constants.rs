@@ -22,3 +22,27 @@ pub const RECOVERING_TIREDNESS_PER_SHORT_TICK: f32 = 0.05;
 pub const MIN_TIREDNESS_FOR_SUB: f32 = 50.0;
 
 pub const BASE_ATTENDANCE: u32 = 60;
+
+pub const MAX_PERSONAL_FOULS: u8 = 5;
+pub const TEAM_FOULS_FOR_BONUS: usize = 5;
+pub const DRIVE_FOUL_PROBABILITY: f64 = 0.2;
+pub const SHOT_FOUL_PROBABILITY: f64 = 0.08;
+pub const FREE_THROW_DIFFICULTY: u8 = 22;
+
+pub const STANDARD_GRAVITY: usize = 10;
+pub const MAX_GRAVITY_BONUS: i16 = 8;
+pub const MIN_GRAVITY_TIREDNESS_FACTOR: f32 = 0.8;
+pub const MAX_GRAVITY_TIREDNESS_FACTOR: f32 = 1.75;
+
+pub const MAX_MOMENTUM: u8 = 10;
+pub const MOMENTUM_PER_SCORE: u8 = 1;
+pub const MOMENTUM_PER_DEFENSIVE_PLAY: u8 = 2;
+pub const MORALE_PER_STARTING_MOMENTUM: u8 = 20;
+
+pub const BRAWL_BASE_PROBABILITY: f64 = 0.02;
+pub const BRAWL_CALM_DOWN_DIFFICULTY: u8 = 30;
+pub const BRAWL_KILL_LIMIT: i16 = 45;
+pub const BRAWL_KNOCKOUT_LIMIT: i16 = 25;
+pub const BRAWL_INJURY_LIMIT: i16 = 12;
+pub const BRAWL_INJURY_TIREDNESS: f32 = 40.0;
+pub const BRAWL_INJURY_SEVERITY: f32 = 20.0;
@@ -0,0 +1,160 @@
+use super::{
+    action::{ActionOutput, ActionSituation, Advantage},
+    constants::{TirednessCost, FREE_THROW_DIFFICULTY, TEAM_FOULS_FOR_BONUS},
+    game::Game,
+    types::{GameStats, Possession},
+    utils::roll,
+};
+use crate::world::{player::Player, skill::GameSkill};
+use rand::Rng;
+use rand_chacha::ChaCha8Rng;
+use std::collections::HashMap;
+
+#[derive(Debug, Default)]
+pub struct FreeThrow;
+
+pub fn foul_on_drive(
+    input: &ActionOutput,
+    game: &Game,
+    attacker_idx: usize,
+    attacker: &Player,
+    defender_idx: usize,
+    defender: &Player,
+    rng: &mut ChaCha8Rng,
+) -> ActionOutput {
+    // The current foul is not yet in the action results, hence the +1.
+    let in_bonus = game.defending_team_fouls_in_period() + 1 >= TEAM_FOULS_FOR_BONUS;
+
+    if in_bonus {
+        ActionOutput {
+            possession: input.possession.clone(),
+            attackers: vec![attacker_idx],
+            situation: ActionSituation::FreeThrow,
+            description: format!(
+                "{} fouls {} on the drive. The team is in the bonus, {} goes to the line.",
+                defender.info.last_name,
+                attacker.info.last_name,
+                attacker.info.pronouns.as_subject()
+            ),
+            foul_from: Some(defender_idx),
+            foul_on: Some(attacker_idx),
+            free_throws: 2,
+            start_at: input.end_at,
+            end_at: input.end_at.plus(rng.gen_range(2..=4)),
+            home_score: input.home_score,
+            away_score: input.away_score,
+            ..Default::default()
+        }
+    } else {
+        ActionOutput {
+            possession: input.possession.clone(),
+            situation: ActionSituation::BallInBackcourt,
+            description: format!(
+                "{} fouls {} on the drive. The ball will be inbounded.",
+                defender.info.last_name, attacker.info.last_name,
+            ),
+            foul_from: Some(defender_idx),
+            foul_on: Some(attacker_idx),
+            start_at: input.end_at,
+            end_at: input.end_at.plus(rng.gen_range(2..=4)),
+            home_score: input.home_score,
+            away_score: input.away_score,
+            ..Default::default()
+        }
+    }
+}
+
+impl FreeThrow {
+    pub fn execute(
+        &self,
+        input: &ActionOutput,
+        game: &Game,
+        rng: &mut ChaCha8Rng,
+    ) -> Option<ActionOutput> {
+        let attacking_players = game.attacking_players();
+        let attacking_stats = game.attacking_stats();
+
+        let shooter_idx = input.foul_on.or(input.attackers.first().copied())?;
+        let shooter = *attacking_players.get(shooter_idx)?;
+        let shooter_stats = attacking_stats.get(&shooter.id)?;
+
+        let attempts = input.free_throws.max(1);
+        let mut made = 0;
+        let mut last_made = false;
+        for _ in 0..attempts {
+            last_made = roll(rng, shooter_stats.tiredness) + shooter.offense.medium_range.value()
+                > FREE_THROW_DIFFICULTY;
+            if last_made {
+                made += 1;
+            }
+        }
+
+        let mut shooter_update = GameStats {
+            attempted_ft: attempts,
+            made_ft: made,
+            points: made,
+            ..Default::default()
+        };
+        shooter_update.add_tiredness(TirednessCost::LOW, shooter.athleticism.stamina);
+
+        let description = match (attempts, made) {
+            (1, 1) => format!("{} makes the free throw.", shooter.info.last_name),
+            (1, 0) => format!("{} misses the free throw.", shooter.info.last_name),
+            (a, m) if a == m => format!(
+                "{} makes all {} free throws.",
+                shooter.info.last_name, attempts
+            ),
+            (_, 0) => format!(
+                "{} misses all {} free throws.",
+                shooter.info.last_name, attempts
+            ),
+            _ => format!(
+                "{} makes {} out of {} free throws.",
+                shooter.info.last_name, made, attempts
+            ),
+        };
+
+        let home_score = match input.possession {
+            Possession::Home => input.home_score + made as u16,
+            Possession::Away => input.home_score,
+        };
+        let away_score = match input.possession {
+            Possession::Home => input.away_score,
+            Possession::Away => input.away_score + made as u16,
+        };
+
+        let mut result = if last_made {
+            ActionOutput {
+                score_change: made,
+                possession: !input.possession.clone(),
+                situation: ActionSituation::BallInBackcourt,
+                description,
+                start_at: input.end_at,
+                end_at: input.end_at.plus(4 + rng.gen_range(0..=4)),
+                home_score,
+                away_score,
+                ..Default::default()
+            }
+        } else {
+            // The last free throw was missed, the ball is up for the rebound.
+            ActionOutput {
+                score_change: made,
+                advantage: Advantage::Defense,
+                possession: input.possession.clone(),
+                attackers: vec![shooter_idx],
+                situation: ActionSituation::MissedShot,
+                description,
+                start_at: input.end_at,
+                end_at: input.end_at.plus(1),
+                home_score,
+                away_score,
+                ..Default::default()
+            }
+        };
+
+        let mut attack_stats_update = HashMap::new();
+        attack_stats_update.insert(shooter.id, shooter_update);
+        result.attack_stats_update = Some(attack_stats_update);
+        Some(result)
+    }
+}
@@ -1,14 +1,16 @@
 use super::{
     action::{Action, ActionOutput, ActionSituation},
+    brawl::is_brawl,
     constants::*,
     end_of_quarter::EndOfQuarter,
     substitution::Substitution,
+    tactic::DefenseTactic,
     timer::Timer,
     types::{GameStatsMap, Possession, TeamInGame},
 };
 use crate::{
     types::{GameId, PlanetId, SortablePlayerMap, TeamId, Tick, SECONDS},
-    world::{planet::Planet, player::Player, position::MAX_POSITION},
+    world::{planet::Planet, player::Player, position::MAX_POSITION, skill::MAX_SKILL},
 };
 use rand::{Rng, SeedableRng};
 use rand_chacha::ChaCha8Rng;
@@ -26,6 +28,8 @@ pub struct GameSummary {
     pub away_score: u16,
     pub location: PlanetId,
     pub attendance: u32,
+    #[serde(default)]
+    pub starting_at: Tick,
 }
 
 impl GameSummary {
@@ -51,6 +55,7 @@ impl GameSummary {
             away_score,
             location: game.location,
             attendance: game.attendance,
+            starting_at: game.starting_at,
         }
     }
 }
@@ -61,6 +66,8 @@ pub struct Game {
     pub home_team_in_game: TeamInGame,
     pub away_team_in_game: TeamInGame,
     pub location: PlanetId,
+    #[serde(default = "default_gravity")]
+    pub gravity: usize,
     pub attendance: u32,
     pub action_results: Vec<ActionOutput>,
     pub won_jump_ball: Possession,
@@ -72,11 +79,15 @@ pub struct Game {
     pub current_action: Action,
 }
 
+fn default_gravity() -> usize {
+    STANDARD_GRAVITY
+}
+
 impl<'game> Game {
     pub fn new(
         id: GameId,
-        home_team_in_game: TeamInGame,
-        away_team_in_game: TeamInGame,
+        mut home_team_in_game: TeamInGame,
+        mut away_team_in_game: TeamInGame,
         starting_at: Tick,
         planet: &Planet,
     ) -> Self {
@@ -90,11 +101,18 @@ impl<'game> Game {
         let home_name = home_team_in_game.name.clone();
         let away_name = away_team_in_game.name.clone();
 
+        for team in [&mut home_team_in_game, &mut away_team_in_game] {
+            let momentum = team.starting_momentum();
+            team.offensive_momentum = momentum;
+            team.defensive_momentum = momentum;
+        }
+
         let mut game = Self {
             id,
             home_team_in_game,
             away_team_in_game,
             location: planet.id,
+            gravity: planet.gravity,
             attendance: 0,
             starting_at,
             ended_at: None,
@@ -122,9 +140,22 @@ impl<'game> Game {
 
     fn pick_action(&self, rng: &mut ChaCha8Rng) -> Action {
         //FIXME: Actions should be picked based on the team tactic/players
-        let situation = self.action_results[self.action_results.len() - 1]
-            .situation
-            .clone();
+        let last_result = &self.action_results[self.action_results.len() - 1];
+
+        // Hard fouls can degenerate into a brawl.
+        if let (Some(foul_from), Some(foul_on)) = (last_result.foul_from, last_result.foul_on) {
+            let attacking_players = self.attacking_players();
+            let defending_players = self.defending_players();
+            if let (Some(&fouled), Some(&fouler)) =
+                (attacking_players.get(foul_on), defending_players.get(foul_from))
+            {
+                if is_brawl(rng, fouled, fouler) {
+                    return Action::Brawl;
+                }
+            }
+        }
+
+        let situation = last_result.situation.clone();
 
         match situation {
             ActionSituation::JumpBall => Action::JumpBall,
@@ -134,8 +165,10 @@ impl<'game> Game {
             ActionSituation::LongShot => Action::LongShot,
             ActionSituation::MissedShot => Action::Rebound,
             ActionSituation::EndOfQuarter => Action::StartOfQuarter,
+            ActionSituation::FreeThrow => Action::FreeThrow,
             ActionSituation::BallInBackcourt
             | ActionSituation::BallInMidcourt
+            | ActionSituation::BallInFrontcourt
             | ActionSituation::AfterDefensiveRebound
             | ActionSituation::Turnover => match self.possession {
                 Possession::Home => self
@@ -149,7 +182,6 @@ impl<'game> Game {
                     .pick_action(rng)
                     .unwrap_or(Action::Isolation),
             },
-            _ => panic!("Unknown situation: {:?}", situation),
         }
     }
 
@@ -207,14 +239,45 @@ impl<'game> Game {
         assert!(self.home_team_in_game.stats.len() == self.home_team_in_game.players.len());
     }
 
+    fn apply_momentum_update(&mut self, result: &ActionOutput) {
+        let (attacking, defending) = match self.possession {
+            Possession::Home => (&mut self.home_team_in_game, &mut self.away_team_in_game),
+            Possession::Away => (&mut self.away_team_in_game, &mut self.home_team_in_game),
+        };
+
+        // Scoring builds the attack run and breaks the one of the other team.
+        if result.score_change > 0 {
+            attacking.offensive_momentum =
+                (attacking.offensive_momentum + MOMENTUM_PER_SCORE).min(MAX_MOMENTUM);
+            defending.offensive_momentum /= 2;
+            defending.defensive_momentum = defending.defensive_momentum.saturating_sub(1);
+        }
+
+        let defensive_plays = if let Some(updates) = &result.defense_stats_update {
+            updates
+                .values()
+                .map(|stats| stats.blocks + stats.steals)
+                .sum::<u8>()
+        } else {
+            0
+        };
+        if defensive_plays > 0 {
+            defending.defensive_momentum = (defending.defensive_momentum
+                + MOMENTUM_PER_DEFENSIVE_PLAY * defensive_plays)
+                .min(MAX_MOMENTUM);
+            attacking.offensive_momentum = attacking.offensive_momentum.saturating_sub(1);
+        }
+    }
+
     fn apply_tiredness_recovery(&mut self) {
+        let tiredness_factor = self.gravity_tiredness_factor();
         for team in [&mut self.home_team_in_game, &mut self.away_team_in_game] {
             for (id, stats) in team.stats.iter_mut() {
                 if stats.is_playing() && !stats.is_knocked_out() && !self.timer.is_break() {
                     stats.seconds_played += 1;
                     stats.experience_at_position[stats.position.unwrap() as usize] += 1;
                     let stamina = team.players.get(&id).unwrap().athleticism.stamina;
-                    stats.add_tiredness(TirednessCost::LOW, stamina);
+                    stats.add_tiredness(TirednessCost::LOW * tiredness_factor, stamina);
                 } else if stats.tiredness > RECOVERING_TIREDNESS_PER_SHORT_TICK
                     && !stats.is_knocked_out()
                 {
@@ -280,6 +343,56 @@ impl<'game> Game {
         }
     }
 
+    /// Bonus to jumping players due to the planet gravity:
+    /// positive on low gravity planets, negative on high gravity ones.
+    pub fn gravity_bonus(&self) -> i16 {
+        (STANDARD_GRAVITY as i16 - self.gravity.min(i16::MAX as usize) as i16)
+            .clamp(-MAX_GRAVITY_BONUS, MAX_GRAVITY_BONUS)
+    }
+
+    pub fn jump_bonus(&self, player: &Player) -> i16 {
+        (self.gravity_bonus() as f32 * player.athleticism.vertical / MAX_SKILL) as i16
+    }
+
+    /// Long range shots are harder to calibrate the further gravity is from the standard.
+    pub fn long_range_malus(&self) -> i16 {
+        self.gravity_bonus().abs() / 2
+    }
+
+    pub fn gravity_tiredness_factor(&self) -> f32 {
+        (1.0 + (self.gravity as f32 - STANDARD_GRAVITY as f32) / 20.0)
+            .clamp(MIN_GRAVITY_TIREDNESS_FACTOR, MAX_GRAVITY_TIREDNESS_FACTOR)
+    }
+
+    /// Bonus to the attack advantage rolls, positive if the attacking team has the momentum.
+    pub fn momentum_bonus(&self) -> i16 {
+        let (attacking, defending) = match self.possession {
+            Possession::Home => (&self.home_team_in_game, &self.away_team_in_game),
+            Possession::Away => (&self.away_team_in_game, &self.home_team_in_game),
+        };
+        (attacking.offensive_momentum as i16 - defending.defensive_momentum as i16) / 2
+    }
+
+    pub fn defending_tactic(&self) -> DefenseTactic {
+        match self.possession {
+            Possession::Home => self.away_team_in_game.defense_tactic,
+            Possession::Away => self.home_team_in_game.defense_tactic,
+        }
+    }
+
+    pub fn defending_team_fouls_in_period(&self) -> usize {
+        // Foul outputs always keep the possession of the fouled team.
+        let period = self.timer.period();
+        self.action_results
+            .iter()
+            .filter(|result| {
+                result.foul_from.is_some()
+                    && result.possession == self.possession
+                    && result.start_at.period() == period
+            })
+            .count()
+    }
+
     fn get_rng_seed(&self) -> [u8; 32] {
         let mut seed = [0; 32];
         seed[0..16].copy_from_slice(self.id.as_bytes());
@@ -290,6 +403,56 @@ impl<'game> Game {
         seed
     }
 
+    /// Returns a copy of the game rewound to tip-off. Since the game is fully
+    /// determined by its id and starting time, ticking the copy replays it exactly.
+    pub fn rewound(&self) -> Self {
+        Self {
+            home_team_in_game: self.home_team_in_game.rewound(),
+            away_team_in_game: self.away_team_in_game.rewound(),
+            action_results: self.action_results.iter().take(1).cloned().collect(),
+            won_jump_ball: Possession::default(),
+            ended_at: None,
+            possession: Possession::default(),
+            timer: Timer::default(),
+            next_step: 0,
+            current_action: Action::JumpBall,
+            ..self.clone()
+        }
+    }
+
+    /// Returns a copy of the game for saving, without the actions played so far.
+    /// Only the timer is kept, so that `expanded` can replay the game up to it.
+    pub fn compacted(&self) -> Self {
+        Self {
+            timer: self.timer,
+            ..self.rewound()
+        }
+    }
+
+    /// Replays a compacted game up to its timer. Full games are returned as they are.
+    pub fn expanded(self) -> Self {
+        if !self.timer.has_started() || self.action_results.len() > 1 {
+            return self;
+        }
+        let target = self.timer.value;
+        let mut game = Self {
+            timer: Timer::default(),
+            ..self
+        };
+        while game.timer.value < target && !game.timer.has_ended() {
+            game.tick();
+        }
+        game
+    }
+
+    pub fn action_checksums(&self, from: usize) -> Vec<u64> {
+        self.action_results
+            .iter()
+            .skip(from)
+            .map(|action| action.checksum())
+            .collect()
+    }
+
     pub fn get_score(&self) -> (u16, u16) {
         if let Some(result) = self.action_results.last() {
             (result.home_score, result.away_score)
@@ -331,6 +494,7 @@ impl<'game> Game {
                 result.attack_stats_update.clone(),
                 result.defense_stats_update.clone(),
             );
+            self.apply_momentum_update(&result);
 
             if result.score_change > 0 {
                 let home_plus_minus: i16 = if self.possession == Possession::Home {
@@ -392,9 +556,15 @@ impl<'game> Game {
 #[cfg(test)]
 mod tests {
     use super::Game;
+    use crate::engine::action::ActionSituation;
+    use crate::engine::constants::{
+        MAX_GRAVITY_BONUS, MAX_GRAVITY_TIREDNESS_FACTOR, MIN_GRAVITY_TIREDNESS_FACTOR,
+        STANDARD_GRAVITY,
+    };
     use crate::engine::types::TeamInGame;
+    use crate::test_utils::test_world_and_game;
     use crate::types::{GameId, IdSystem};
-    use crate::types::{SystemTimeTick, Tick};
+    use crate::types::{SystemTimeTick, Tick, HOURS};
     use crate::world::constants::DEFAULT_PLANET_ID;
     use crate::world::world::World;
     use rand::SeedableRng;
@@ -438,13 +608,24 @@ mod tests {
         let home_team_in_game = TeamInGame::from_team_id(id0, &world.teams, &world.players);
         let away_team_in_game = TeamInGame::from_team_id(id1, &world.teams, &world.players);
 
-        let mut game = Game::new(
-            GameId::new(),
-            home_team_in_game.unwrap(),
-            away_team_in_game.unwrap(),
-            Tick::now(),
-            &world.get_planet(DEFAULT_PLANET_ID.clone()).unwrap(),
-        );
+        // Playing teams are busy, so no random games are generated for them,
+        // and the own team is never picked for one.
+        world.own_team_id = id0;
+        // The game started an hour ago, so the world catches up with it
+        // instead of ticking it in real time.
+        let starting_at = Tick::now() - HOURS;
+        world.last_tick_short_interval = starting_at;
+        world.last_tick_medium_interval = starting_at;
+        let game_id = GameId::new();
+        world
+            .generate_game(
+                game_id,
+                home_team_in_game.unwrap(),
+                away_team_in_game.unwrap(),
+                starting_at,
+            )
+            .unwrap();
+        let game = world.games.get_mut(&game_id).unwrap();
 
         game.home_team_in_game
             .players
@@ -468,7 +649,6 @@ mod tests {
 
         println!("{:?}", game.home_team_in_game.players.len());
 
-        world.games.insert(game.id, game);
         while world.games.len() > 0 {
             let _ = world.handle_tick_events(Tick::now(), false);
         }
@@ -480,4 +660,211 @@ mod tests {
             .clone();
         println!("{} {}", quickness_before, quickness_after);
     }
+
+    #[test]
+    fn test_game_free_throws_are_scored() {
+        let (_, mut game) = test_world_and_game();
+        while !game.timer.has_ended() {
+            game.tick();
+        }
+
+        for result in game.action_results.iter() {
+            if result.situation == ActionSituation::FreeThrow {
+                assert!(result.foul_on.is_some());
+                assert!(result.free_throws >= 1 && result.free_throws <= 3);
+            }
+        }
+
+        let (home_score, away_score) = game.get_score();
+        let home_points = game
+            .home_team_in_game
+            .stats
+            .values()
+            .map(|s| s.points as u16)
+            .sum::<u16>();
+        let away_points = game
+            .away_team_in_game
+            .stats
+            .values()
+            .map(|s| s.points as u16)
+            .sum::<u16>();
+        assert_eq!(home_score, home_points);
+        assert_eq!(away_score, away_points);
+
+        for stats in game
+            .home_team_in_game
+            .stats
+            .values()
+            .chain(game.away_team_in_game.stats.values())
+        {
+            assert!(stats.made_ft <= stats.attempted_ft);
+        }
+    }
+
+    #[test]
+    fn test_game_replay() {
+        let mut world = World::new(None);
+        let rng = &mut ChaCha8Rng::seed_from_u64(world.seed);
+
+        let id0 = world
+            .generate_random_team(
+                rng,
+                DEFAULT_PLANET_ID.clone(),
+                "Testen".to_string(),
+                "Tosten".to_string(),
+            )
+            .unwrap();
+        let id1 = world
+            .generate_random_team(
+                rng,
+                DEFAULT_PLANET_ID.clone(),
+                "Holalo".to_string(),
+                "Halley".to_string(),
+            )
+            .unwrap();
+
+        let mut game = Game::new(
+            GameId::new(),
+            TeamInGame::from_team_id(id0, &world.teams, &world.players).unwrap(),
+            TeamInGame::from_team_id(id1, &world.teams, &world.players).unwrap(),
+            Tick::now(),
+            &world.get_planet(DEFAULT_PLANET_ID.clone()).unwrap(),
+        );
+        while !game.timer.has_ended() {
+            game.tick();
+        }
+
+        // Replays are loaded from the persisted game.
+        let persisted: Game = serde_json::from_str(&serde_json::to_string(&game).unwrap()).unwrap();
+        let mut replay = persisted.rewound();
+        assert_eq!(replay.action_results.len(), 1);
+        while !replay.timer.has_ended() {
+            replay.tick();
+        }
+
+        assert_eq!(replay.get_score(), game.get_score());
+        assert_eq!(replay.action_results.len(), game.action_results.len());
+        for (a, b) in replay.action_results.iter().zip(game.action_results.iter()) {
+            assert_eq!(a.description, b.description);
+        }
+        assert_eq!(replay.home_team_in_game.stats, game.home_team_in_game.stats);
+        assert_eq!(replay.away_team_in_game.stats, game.away_team_in_game.stats);
+    }
+
+    #[test]
+    fn test_compacted_game() {
+        let mut world = World::new(None);
+        let rng = &mut ChaCha8Rng::seed_from_u64(world.seed);
+        let mut team_ids = vec![];
+        for (team_name, ship_name) in [("Testen", "Tosten"), ("Holalo", "Halley")] {
+            team_ids.push(
+                world
+                    .generate_random_team(
+                        rng,
+                        DEFAULT_PLANET_ID.clone(),
+                        team_name.to_string(),
+                        ship_name.to_string(),
+                    )
+                    .unwrap(),
+            );
+        }
+
+        let mut game = Game::new(
+            GameId::new(),
+            TeamInGame::from_team_id(team_ids[0], &world.teams, &world.players).unwrap(),
+            TeamInGame::from_team_id(team_ids[1], &world.teams, &world.players).unwrap(),
+            Tick::now(),
+            &world.get_planet(DEFAULT_PLANET_ID.clone()).unwrap(),
+        );
+        // Games are saved both while in progress and once they have ended.
+        for ticks in [1000, u16::MAX] {
+            while game.timer.value < ticks && !game.timer.has_ended() {
+                game.tick();
+            }
+            let compacted = game.compacted();
+            assert_eq!(compacted.action_results.len(), 1);
+            let persisted: Game =
+                serde_json::from_str(&serde_json::to_string(&compacted).unwrap()).unwrap();
+            // Players with a team never compare equal, so compare the serialized games.
+            assert_eq!(
+                serde_json::to_value(&persisted.expanded()).unwrap(),
+                serde_json::to_value(&game).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn test_game_checksums() {
+        let mut world = World::new(None);
+        let rng = &mut ChaCha8Rng::seed_from_u64(world.seed);
+        let mut team_ids = vec![];
+        for (team_name, ship_name) in [("Testen", "Tosten"), ("Holalo", "Halley")] {
+            team_ids.push(
+                world
+                    .generate_random_team(
+                        rng,
+                        DEFAULT_PLANET_ID.clone(),
+                        team_name.to_string(),
+                        ship_name.to_string(),
+                    )
+                    .unwrap(),
+            );
+        }
+
+        // Both peers simulate the game from the same inputs.
+        let game_id = GameId::new();
+        let starting_at = Tick::now();
+        let planet = world.get_planet(DEFAULT_PLANET_ID.clone()).unwrap();
+        let new_game = || {
+            Game::new(
+                game_id,
+                TeamInGame::from_team_id(team_ids[0], &world.teams, &world.players).unwrap(),
+                TeamInGame::from_team_id(team_ids[1], &world.teams, &world.players).unwrap(),
+                starting_at,
+                planet,
+            )
+        };
+        let mut home_game = new_game();
+        let mut away_game = new_game();
+        for _ in 0..200 {
+            home_game.tick();
+            away_game.tick();
+        }
+        assert!(home_game.action_results.len() > 1);
+        assert_eq!(home_game.action_checksums(0), away_game.action_checksums(0));
+
+        let idx = away_game.action_results.len() - 1;
+        away_game.action_results[idx].home_score += 1;
+        assert_ne!(
+            home_game.action_checksums(idx),
+            away_game.action_checksums(idx)
+        );
+    }
+
+    #[test]
+    fn test_gravity_modifiers() {
+        let mut game = Game::default();
+
+        game.gravity = STANDARD_GRAVITY;
+        assert_eq!(game.gravity_bonus(), 0);
+        assert_eq!(game.long_range_malus(), 0);
+        assert_eq!(game.gravity_tiredness_factor(), 1.0);
+
+        // Moon
+        game.gravity = 2;
+        assert_eq!(game.gravity_bonus(), MAX_GRAVITY_BONUS);
+        assert_eq!(game.long_range_malus(), MAX_GRAVITY_BONUS / 2);
+        assert_eq!(
+            game.gravity_tiredness_factor(),
+            MIN_GRAVITY_TIREDNESS_FACTOR
+        );
+
+        // Sagittarius
+        game.gravity = 1000;
+        assert_eq!(game.gravity_bonus(), -MAX_GRAVITY_BONUS);
+        assert_eq!(
+            game.gravity_tiredness_factor(),
+            MAX_GRAVITY_TIREDNESS_FACTOR
+        );
+    }
 }
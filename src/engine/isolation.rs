@@ -1,9 +1,13 @@
 use super::{
     action::{ActionOutput, ActionSituation, Advantage},
-    constants::{TirednessCost, ADV_ATTACK_LIMIT, ADV_DEFENSE_LIMIT, ADV_NEUTRAL_LIMIT},
+    constants::{
+        TirednessCost, ADV_ATTACK_LIMIT, ADV_DEFENSE_LIMIT, ADV_NEUTRAL_LIMIT,
+        DRIVE_FOUL_PROBABILITY,
+    },
+    free_throw::foul_on_drive,
     game::Game,
     types::GameStats,
-    utils::roll,
+    utils::{is_foul, roll},
 };
 use crate::world::{player::Player, skill::GameSkill};
 use rand::Rng;
@@ -36,9 +40,11 @@ impl Isolation {
             }
         }
 
-        let iso: &Player = attacking_players[iso_idx];
+        let iso: &Player = *attacking_players.get(iso_idx)?;
         let iso_stats = attacking_stats.get(&iso.id)?;
-        let defender = defending_players[iso_idx];
+        let defense_tactic = game.defending_tactic();
+        let defender_idx = defense_tactic.pick_defender(iso_idx, defending_players.len(), rng);
+        let defender = *defending_players.get(defender_idx)?;
         let defender_stats = defending_stats.get(&defender.id)?;
 
         let timer_increase = 2 + rng.gen_range(0..=3);
@@ -61,12 +67,16 @@ impl Isolation {
             + defender.defense.perimeter_defense.value()
             + defender.athleticism.quickness.value();
 
-        let mut result = match atk_result as i16 - def_result as i16 {
+        let mut result = match atk_result as i16
+            - def_result as i16
+            - defense_tactic.perimeter_bonus()
+            + game.momentum_bonus()
+        {
             x if x > ADV_ATTACK_LIMIT => ActionOutput {
                 possession: input.possession.clone(),
                 advantage: Advantage::Attack,
                 attackers: vec![iso_idx],
-                defenders: vec![iso_idx],
+                defenders: vec![defender_idx],
                 situation: ActionSituation::CloseShot,
                 description: format!(
                     "{} breaks {}'s ankles and is now at the basket.",
@@ -82,7 +92,7 @@ impl Isolation {
                 possession: input.possession.clone(),
                 advantage: Advantage::Neutral,
                 attackers: vec![iso_idx],
-                defenders: vec![iso_idx], //got the switch
+                defenders: vec![defender_idx], //got the switch
                 situation: ActionSituation::CloseShot,
                 description: format!(
                     "{} gets through {} and gathers the ball to shoot.",
@@ -98,7 +108,7 @@ impl Isolation {
                 possession: input.possession.clone(),
                 advantage: Advantage::Defense,
                 attackers: vec![iso_idx],
-                defenders: vec![iso_idx], //no switch
+                defenders: vec![defender_idx], //no switch
                 situation: ActionSituation::MediumShot,
                 description: format!(
                     "{} tries to dribble past {} but {} is all over him.",
@@ -129,6 +139,15 @@ impl Isolation {
                 }
             }
         };
+
+        // The defender was beaten on the drive and may foul to stop it.
+        if result.situation == ActionSituation::CloseShot
+            && is_foul(rng, defender, defender_stats, DRIVE_FOUL_PROBABILITY)
+        {
+            defender_update.fouls = 1;
+            result = foul_on_drive(input, game, iso_idx, iso, defender_idx, defender, rng);
+        }
+
         attack_stats_update.insert(iso.id, iso_update);
         defense_stats_update.insert(defender.id, defender_update);
         result.attack_stats_update = Some(attack_stats_update);
@@ -1,6 +1,8 @@
 pub mod action;
+mod brawl;
 pub mod constants;
 mod end_of_quarter;
+mod free_throw;
 pub mod game;
 mod isolation;
 mod jump_ball;
@@ -45,13 +45,17 @@ impl OffTheScreen {
             }
         }
 
-        let playmaker: &Player = attacking_players[play_idx];
-        let playmaker_defender = defending_players[play_idx];
+        let playmaker: &Player = *attacking_players.get(play_idx)?;
+        let defense_tactic = game.defending_tactic();
+        let playmaker_defender = *defending_players
+            .get(defense_tactic.pick_defender(play_idx, defending_players.len(), rng))?;
         let play_stats = attacking_stats.get(&playmaker.id)?;
         let defender_stats = defending_stats.get(&playmaker_defender.id)?;
 
-        let target = attacking_players[target_idx];
-        let target_defender = defending_players[target_idx];
+        let target = *attacking_players.get(target_idx)?;
+        let target_defender_idx =
+            defense_tactic.pick_defender(target_idx, defending_players.len(), rng);
+        let target_defender = *defending_players.get(target_defender_idx)?;
 
         let mut attack_stats_update: GameStatsMap = HashMap::new();
         let mut playmaker_update = GameStats::default();
@@ -74,12 +78,16 @@ impl OffTheScreen {
             + target_defender.defense.perimeter_defense.value()
             + target_defender.athleticism.quickness.value();
 
-        result = match atk_result as i16 - def_result as i16 {
+        result = match atk_result as i16
+            - def_result as i16
+            - defense_tactic.perimeter_bonus()
+            + game.momentum_bonus()
+        {
             x if x > ADV_ATTACK_LIMIT => ActionOutput {
                 possession: input.possession.clone(),
                 advantage: Advantage::Attack,
                 attackers: vec![target_idx],
-                defenders: vec![target_idx],
+                defenders: vec![target_defender_idx],
                 situation: ActionSituation::LongShot,
                 description: format!(
                     "{} gets the pass from {} and is now open for the shot.",
@@ -96,7 +104,7 @@ impl OffTheScreen {
                 possession: input.possession.clone(),
                 advantage: Advantage::Neutral,
                 attackers: vec![target_idx],
-                defenders: vec![target_idx],
+                defenders: vec![target_defender_idx],
                 situation: ActionSituation::LongShot,
                 description: format!(
                     "{} passes to {} after the screen.",
@@ -113,7 +121,7 @@ impl OffTheScreen {
                 possession: input.possession.clone(),
                 advantage: Advantage::Defense,
                 attackers: vec![target_idx],
-                defenders: vec![target_idx],
+                defenders: vec![target_defender_idx],
                 situation: ActionSituation::MediumShot,
                 description: format!(
                     "{} passes to {} who tried to get free using the screen, but {} is all over him.",
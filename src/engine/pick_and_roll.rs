@@ -43,13 +43,18 @@ impl PickAndRoll {
             }
         }
 
-        let playmaker: &Player = attacking_players[play_idx];
-        let playmaker_defender = defending_players[play_idx];
+        let playmaker: &Player = *attacking_players.get(play_idx)?;
+        let defense_tactic = game.defending_tactic();
+        let play_defender_idx =
+            defense_tactic.pick_defender(play_idx, defending_players.len(), rng);
+        let playmaker_defender = *defending_players.get(play_defender_idx)?;
         let play_stats = attacking_stats.get(&playmaker.id)?;
         let defender_stats = defending_stats.get(&playmaker_defender.id)?;
 
-        let target = attacking_players[target_idx];
-        let target_defender = defending_players[target_idx];
+        let target = *attacking_players.get(target_idx)?;
+        let target_defender_idx =
+            defense_tactic.pick_defender(target_idx, defending_players.len(), rng);
+        let target_defender = *defending_players.get(target_defender_idx)?;
 
         let mut attack_stats_update: GameStatsMap = HashMap::new();
         let mut playmaker_update = GameStats::default();
@@ -79,12 +84,16 @@ impl PickAndRoll {
                 + playmaker_defender.defense.perimeter_defense.value()
                 + playmaker_defender.mental.vision.value();
 
-            result = match atk_result as i16 - def_result as i16 {
+            result = match atk_result as i16
+                - def_result as i16
+                - defense_tactic.perimeter_bonus()
+                + game.momentum_bonus()
+            {
                 x if x > ADV_ATTACK_LIMIT => ActionOutput {
                     possession: input.possession.clone(),
                     advantage: Advantage::Attack,
                     attackers: vec![play_idx],
-                    defenders: vec![play_idx],
+                    defenders: vec![play_defender_idx],
                     situation: ActionSituation::LongShot,
                     description: format!(
                         "{} uses the screen perfectly and is now open for the shot.",
@@ -100,7 +109,7 @@ impl PickAndRoll {
                     possession: input.possession.clone(),
                     advantage: Advantage::Neutral,
                     attackers: vec![play_idx],
-                    defenders: vec![play_idx],
+                    defenders: vec![play_defender_idx],
                     situation: ActionSituation::LongShot,
                     description: format!(
                         "They go for the pick'n'roll. {} goes through the screen and manages to get a bit of space to shot.",
@@ -116,7 +125,7 @@ impl PickAndRoll {
                     possession: input.possession.clone(),
                     advantage: Advantage::Defense,
                     attackers: vec![play_idx],
-                    defenders: vec![play_idx],
+                    defenders: vec![play_defender_idx],
                     situation: ActionSituation::LongShot,
                     description: format!(
                         "{} tries to use the screen but {} slides nicely to cover.",
@@ -156,12 +165,16 @@ impl PickAndRoll {
                 + playmaker_defender.defense.perimeter_defense.value()
                 + target_defender.athleticism.quickness.value();
 
-            result = match atk_result as i16 - def_result as i16 {
+            result = match atk_result as i16
+                - def_result as i16
+                - defense_tactic.interior_bonus()
+                + game.momentum_bonus()
+            {
             x if x > ADV_ATTACK_LIMIT => ActionOutput {
                 possession: input.possession.clone(),
                 advantage: Advantage::Attack,
                 attackers: vec![target_idx],
-                defenders: vec![play_idx], //got the switch
+                defenders: vec![play_defender_idx], //got the switch
                 situation: ActionSituation::CloseShot,
                 description: format!(
                     "{} and {} execute the pick'n'roll perfectly! {} is now open for the shot.",
@@ -178,7 +191,7 @@ impl PickAndRoll {
                 possession: input.possession.clone(),
                 advantage: Advantage::Neutral,
                 attackers: vec![target_idx],
-                defenders: vec![play_idx], //got the switch
+                defenders: vec![play_defender_idx], //got the switch
                 situation: ActionSituation::CloseShot,
                 description:format!(
                     "They go for the pick'n'roll, nice move. {} passes to {} and is now ready to shoot.",
@@ -195,7 +208,7 @@ impl PickAndRoll {
                 possession: input.possession.clone(),
                 advantage: Advantage::Defense,
                 attackers: vec![target_idx],
-                defenders: vec![target_idx], //no switch
+                defenders: vec![target_defender_idx], //no switch
                 situation: ActionSituation::MediumShot,
                 description:format!(
                     "They go for the pick'n'roll. {} passes to {} but {} is all over him.",
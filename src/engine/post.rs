@@ -1,9 +1,13 @@
 use super::{
     action::{ActionOutput, ActionSituation, Advantage},
-    constants::{TirednessCost, ADV_ATTACK_LIMIT, ADV_DEFENSE_LIMIT, ADV_NEUTRAL_LIMIT},
+    constants::{
+        TirednessCost, ADV_ATTACK_LIMIT, ADV_DEFENSE_LIMIT, ADV_NEUTRAL_LIMIT,
+        DRIVE_FOUL_PROBABILITY,
+    },
+    free_throw::foul_on_drive,
     game::Game,
     types::GameStats,
-    utils::roll,
+    utils::{is_foul, roll},
 };
 use crate::world::{player::Player, skill::GameSkill};
 use rand::Rng;
@@ -36,9 +40,11 @@ impl Post {
             }
         }
 
-        let poster: &Player = attacking_players[post_idx];
+        let poster: &Player = *attacking_players.get(post_idx)?;
         let post_stats = attacking_stats.get(&poster.id)?;
-        let defender = defending_players[post_idx];
+        let defense_tactic = game.defending_tactic();
+        let defender_idx = defense_tactic.pick_defender(post_idx, defending_players.len(), rng);
+        let defender = *defending_players.get(defender_idx)?;
         let defender_stats = defending_stats.get(&defender.id)?;
 
         let timer_increase = 4 + rng.gen_range(0..=5);
@@ -61,12 +67,16 @@ impl Post {
             + defender.defense.interior_defense.value()
             + defender.athleticism.strength.value();
 
-        let mut result = match atk_result as i16 - def_result as i16 {
+        let mut result = match atk_result as i16
+            - def_result as i16
+            - defense_tactic.interior_bonus()
+            + game.momentum_bonus()
+        {
             x if x > ADV_ATTACK_LIMIT => ActionOutput {
                 possession: input.possession.clone(),
                 advantage: Advantage::Attack,
                 attackers: vec![post_idx],
-                defenders: vec![post_idx],
+                defenders: vec![defender_idx],
                 situation: ActionSituation::CloseShot,
                 description: format!(
                     "{} worked {}'s perfectly and got to the basket.",
@@ -82,7 +92,7 @@ impl Post {
                 possession: input.possession.clone(),
                 advantage: Advantage::Neutral,
                 attackers: vec![post_idx],
-                defenders: vec![post_idx],
+                defenders: vec![defender_idx],
                 situation: ActionSituation::CloseShot,
                 description: format!(
                     "{} bumped on {} and gathers the ball to shoot.",
@@ -99,7 +109,7 @@ impl Post {
                     let mut weights = [3, 3, 2, 2, 1];
                     weights[post_idx] = 0;
                     let target_idx = WeightedIndex::new(&weights).ok()?.sample(rng);
-                    let target: &Player = attacking_players[target_idx];
+                    let target: &Player = *attacking_players.get(target_idx)?;
                     ActionOutput {
                         possession: input.possession.clone(),
                         advantage: Advantage::Neutral,
@@ -121,7 +131,7 @@ impl Post {
                         possession: input.possession.clone(),
                         advantage: Advantage::Defense,
                         attackers: vec![post_idx],
-                        defenders: vec![post_idx],
+                        defenders: vec![defender_idx],
                         situation: ActionSituation::MediumShot,
                         description: format!(
                         "{} tries to make the post moves work against {} but {} is all over him.",
@@ -154,6 +164,15 @@ impl Post {
                 }
             }
         };
+
+        // The defender was beaten in the post and may foul to stop it.
+        if result.situation == ActionSituation::CloseShot
+            && is_foul(rng, defender, defender_stats, DRIVE_FOUL_PROBABILITY)
+        {
+            defender_update.fouls = 1;
+            result = foul_on_drive(input, game, post_idx, poster, defender_idx, defender, rng);
+        }
+
         attack_stats_update.insert(poster.id, post_update);
         defense_stats_update.insert(defender.id, defender_update);
         result.attack_stats_update = Some(attack_stats_update);
@@ -5,10 +5,10 @@ use crate::{
 
 use super::{
     action::{ActionOutput, ActionSituation, Advantage},
-    constants::ShotDifficulty,
+    constants::{ShotDifficulty, SHOT_FOUL_PROBABILITY},
     game::Game,
     types::GameStats,
-    utils::roll,
+    utils::{is_foul, roll},
 };
 use rand::{seq::SliceRandom, Rng};
 use rand_chacha::ChaCha8Rng;
@@ -202,21 +202,57 @@ fn execute_shot(
         })
         .sum::<u8>();
 
+    let defense_tactic = game.defending_tactic();
+    let tactic_bonus = match shot {
+        ShotDifficulty::Close => defense_tactic.interior_bonus(),
+        ShotDifficulty::Medium => 0,
+        ShotDifficulty::Long => defense_tactic.perimeter_bonus(),
+    };
+
+    // Gravity helps or hinders dunks and blocks, and long range accuracy.
+    let gravity_bonus = match shot {
+        ShotDifficulty::Close => game.jump_bonus(shooter),
+        ShotDifficulty::Medium => 0,
+        ShotDifficulty::Long => -game.long_range_malus(),
+    };
+    let defenders_jump_bonus = defenders
+        .iter()
+        .map(|&p| game.jump_bonus(p))
+        .sum::<i16>();
+
     let roll = match input.advantage {
         Advantage::Attack => {
             (roll(rng, shooter_stats.tiredness) + atk_skill) as i16 - (shot as u8) as i16
+                + gravity_bonus
         }
         Advantage::Neutral => {
             (roll(rng, shooter_stats.tiredness) + atk_skill) as i16
                 - (shot as u8 + def_skill / 2) as i16
+                - tactic_bonus / 2
+                + gravity_bonus
+                - defenders_jump_bonus / 2
         }
         Advantage::Defense => {
             (roll(rng, shooter_stats.tiredness) + atk_skill) as i16
                 - (shot as u8 + def_skill) as i16
+                - tactic_bonus
+                + gravity_bonus
+                - defenders_jump_bonus
         }
     };
 
     let success = roll > 0;
+
+    // Contested shots can draw a foul on the first defender.
+    let foul = input.advantage != Advantage::Attack
+        && defenders.len() > 0
+        && is_foul(
+            rng,
+            defenders[0],
+            defending_stats.get(&defenders[0].id)?,
+            SHOT_FOUL_PROBABILITY,
+        );
+
     let mut result = match success {
         false => {
             // Attackers and defenders will get a malus in the rebound action.
@@ -285,6 +321,33 @@ fn execute_shot(
         }
     };
 
+    if foul {
+        result.free_throws = match (success, shot) {
+            (true, _) => 1,
+            (false, ShotDifficulty::Long) => 3,
+            (false, _) => 2,
+        };
+        result.description = if success {
+            format!(
+                "{} And one! Foul by {}.",
+                result.description, defenders[0].info.last_name
+            )
+        } else {
+            format!(
+                "{} is fouled by {} on the shot.",
+                shooter.info.last_name, defenders[0].info.last_name
+            )
+        };
+        result.possession = input.possession.clone();
+        result.advantage = Advantage::Neutral;
+        result.attackers = vec![shooter_idx];
+        result.defenders = vec![];
+        result.situation = ActionSituation::FreeThrow;
+        result.foul_from = Some(input.defenders[0]);
+        result.foul_on = Some(shooter_idx);
+        result.end_at = input.end_at.plus(2);
+    }
+
     // Update stats
     let mut attack_stats_update = HashMap::new();
     let mut shooter_update = GameStats::default();
@@ -347,6 +410,13 @@ fn execute_shot(
         }
     };
 
+    // A missed shot drawing a foul does not count as an attempt.
+    if foul && !success {
+        shooter_update.attempted_2pt = 0;
+        shooter_update.attempted_3pt = 0;
+        shooter_update.shot_positions = vec![];
+    }
+
     if result.score_change > 0 {
         shooter_update.points = result.score_change;
         match shot {
@@ -372,7 +442,7 @@ fn execute_shot(
             Advantage::Defense => {
                 defender_update.add_tiredness(TirednessCost::MEDIUM, defender.athleticism.stamina);
                 // Only the first defender gets the block
-                if !success && idx == 0 {
+                if !success && !foul && idx == 0 {
                     defender_update.blocks = 1;
                 }
             }
@@ -381,6 +451,9 @@ fn execute_shot(
             }
             _ => {}
         }
+        if foul && idx == 0 {
+            defender_update.fouls = 1;
+        }
         defense_stats_update.insert(defender.id, defender_update);
     }
     result.attack_stats_update = Some(attack_stats_update);
@@ -28,6 +28,7 @@ fn get_subs<'a>(players: Vec<&'a Player>, team_stats: &GameStatsMap) -> Vec<&'a
             let stats = team_stats.get(&p.id).unwrap();
             return stats.is_playing() == false
                 && !stats.is_knocked_out()
+                && !stats.has_fouled_out()
                 && stats.tiredness <= MIN_TIREDNESS_FOR_SUB;
         })
         //Sort from most to less skilled*tired
@@ -50,20 +51,25 @@ fn get_subs<'a>(players: Vec<&'a Player>, team_stats: &GameStatsMap) -> Vec<&'a
         .take(5)
         .filter(|&p| {
             let stats = team_stats.get(&p.id).unwrap();
-            return stats.is_playing() == true && stats.tiredness > MIN_TIREDNESS_FOR_SUB;
+            return stats.is_playing() == true
+                && (stats.tiredness > MIN_TIREDNESS_FOR_SUB
+                    || stats.is_knocked_out()
+                    || stats.has_fouled_out());
         })
         //Sort from less to most skilled*tired
         .sorted_by(|&a, &b| {
-            let v1 = if team_stats.get(&a.id).unwrap().is_knocked_out() {
+            let stats1 = team_stats.get(&a.id).unwrap();
+            let v1 = if stats1.is_knocked_out() || stats1.has_fouled_out() {
                 0
             } else {
-                let t1 = team_stats.get(&a.id).unwrap().tiredness;
+                let t1 = stats1.tiredness;
                 a.total_skills() * (MAX_TIREDNESS - t1 / 2.0) as u16
             };
-            let v2 = if team_stats.get(&b.id).unwrap().is_knocked_out() {
+            let stats2 = team_stats.get(&b.id).unwrap();
+            let v2 = if stats2.is_knocked_out() || stats2.has_fouled_out() {
                 0
             } else {
-                let t2 = team_stats.get(&b.id).unwrap().tiredness;
+                let t2 = stats2.tiredness;
                 b.total_skills() * (MAX_TIREDNESS - t2 / 2.0) as u16
             };
             v1.cmp(&v2)
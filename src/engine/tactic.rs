@@ -30,6 +30,56 @@ impl DefenseTactic {
         let mut rng = rand::thread_rng();
         Self::iter().choose(&mut rng).unwrap()
     }
+
+    pub fn next(&self) -> Self {
+        match self {
+            Self::PirateToPirate => Self::Zone,
+            Self::Zone => Self::PirateToPirate,
+        }
+    }
+
+    pub fn pick_defender(
+        &self,
+        attacker_idx: usize,
+        defenders: usize,
+        rng: &mut ChaCha8Rng,
+    ) -> usize {
+        // In pirate-to-pirate every attacker is guarded by the defender in the same position.
+        // In zone the attacker is picked up by whoever covers the area he is playing in.
+        // Short-handed defenses only pick among the defenders actually on court.
+        let fallback = attacker_idx.min(defenders.saturating_sub(1));
+        match self {
+            Self::PirateToPirate => fallback,
+            Self::Zone => {
+                let weights = match attacker_idx {
+                    0 => [3, 2, 1, 0, 0],
+                    1 => [2, 3, 1, 0, 0],
+                    2 => [1, 1, 3, 1, 0],
+                    3 => [0, 0, 1, 3, 2],
+                    _ => [0, 0, 1, 2, 3],
+                };
+                WeightedIndex::new(&weights[..defenders.min(weights.len())])
+                    .map(|w| w.sample(rng))
+                    .unwrap_or(fallback)
+            }
+        }
+    }
+
+    pub fn perimeter_bonus(&self) -> i16 {
+        // Bonus to the defense roll for actions happening away from the basket.
+        match self {
+            Self::PirateToPirate => 2,
+            Self::Zone => -4,
+        }
+    }
+
+    pub fn interior_bonus(&self) -> i16 {
+        // Bonus to the defense roll for actions happening close to the basket.
+        match self {
+            Self::PirateToPirate => -2,
+            Self::Zone => 4,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy, Default, Serialize_repr, Deserialize_repr, PartialEq, EnumIter)]
@@ -81,3 +131,43 @@ impl OffenseTactic {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::DefenseTactic;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    #[test]
+    fn test_pick_defender() {
+        let rng = &mut ChaCha8Rng::seed_from_u64(0);
+        for attacker_idx in 0..5 {
+            assert_eq!(
+                DefenseTactic::PirateToPirate.pick_defender(attacker_idx, 5, rng),
+                attacker_idx
+            );
+            for _ in 0..100 {
+                assert!(DefenseTactic::Zone.pick_defender(attacker_idx, 5, rng) < 5);
+            }
+        }
+    }
+
+    #[test]
+    fn test_pick_defender_short_handed() {
+        let rng = &mut ChaCha8Rng::seed_from_u64(0);
+        for defenders in 1..5 {
+            for attacker_idx in 0..5 {
+                assert!(
+                    DefenseTactic::PirateToPirate.pick_defender(attacker_idx, defenders, rng)
+                        < defenders
+                );
+                for _ in 0..100 {
+                    assert!(
+                        DefenseTactic::Zone.pick_defender(attacker_idx, defenders, rng) < defenders
+                    );
+                }
+            }
+        }
+        assert_eq!(DefenseTactic::Zone.pick_defender(4, 0, rng), 0);
+    }
+}
@@ -1,12 +1,13 @@
 use super::{
-    constants::MAX_TIREDNESS,
+    constants::{MAX_MOMENTUM, MAX_PERSONAL_FOULS, MAX_TIREDNESS, MORALE_PER_STARTING_MOMENTUM},
     tactic::{DefenseTactic, OffenseTactic},
 };
 use crate::{
     image::pitch::PitchStyle,
-    types::{GameId, PlayerId, PlayerMap, TeamId, TeamMap},
+    types::{AppResult, GameId, PlayerId, PlayerMap, TeamId, TeamMap},
     world::{
         player::{InfoStats, Player},
+        constants::MIN_PLAYERS_PER_TEAM,
         position::{Position, MAX_POSITION},
         skill::{Athleticism, Defense, Mental, Offense, Technical},
         team::Team,
@@ -95,6 +96,9 @@ pub struct GameStats {
     #[serde(skip_serializing_if = "is_default")]
     #[serde(default)]
     pub knocked_out_by: Option<PlayerId>,
+    #[serde(skip_serializing_if = "is_default")]
+    #[serde(default)]
+    pub injury_severity: f32,
 }
 
 impl GameStats {
@@ -124,7 +128,10 @@ impl GameStats {
         for (idx, exp) in stats.experience_at_position.iter().enumerate() {
             self.experience_at_position[idx] += exp;
         }
-        self.knocked_out_by = stats.knocked_out_by.clone();
+        if stats.knocked_out_by.is_some() {
+            self.knocked_out_by = stats.knocked_out_by.clone();
+        }
+        self.injury_severity += stats.injury_severity;
     }
 
     pub fn is_playing(&self) -> bool {
@@ -135,6 +142,10 @@ impl GameStats {
         self.knocked_out_by.is_some()
     }
 
+    pub fn has_fouled_out(&self) -> bool {
+        self.fouls >= MAX_PERSONAL_FOULS
+    }
+
     pub fn add_tiredness(&mut self, tiredness: f32, stamina: f32) {
         self.tiredness = (self.tiredness + tiredness / (1.0 + stamina / 20.0)).min(MAX_TIREDNESS);
     }
@@ -158,14 +169,27 @@ pub struct TeamInGame {
 
 impl<'game> TeamInGame {
     pub fn new(team: &Team, players: PlayerMap) -> Self {
+        // Injured players cannot be fielded.
+        let initial_positions = team
+            .player_ids
+            .iter()
+            .filter(|id| players.contains_key(id) && !players[id].is_injured())
+            .cloned()
+            .collect::<Vec<PlayerId>>();
+        let players = players
+            .into_iter()
+            .filter(|(id, _)| initial_positions.contains(id))
+            .collect::<PlayerMap>();
+
         let mut stats = HashMap::new();
-        for (idx, player_id) in team.player_ids.iter().enumerate() {
+        for (idx, player_id) in initial_positions.iter().enumerate() {
             let mut player_stats = GameStats::default();
             if (idx as Position) < MAX_POSITION {
                 player_stats.position = Some(idx as Position);
             }
             player_stats.initial_tiredness = players[player_id].tiredness;
             player_stats.tiredness = players[player_id].tiredness;
+            player_stats.morale = players[player_id].morale as u8;
             stats.insert(player_id.clone(), player_stats.clone());
         }
         Self {
@@ -173,7 +197,7 @@ impl<'game> TeamInGame {
             peer_id: team.peer_id,
             reputation: team.reputation,
             name: team.name.clone(),
-            initial_positions: team.player_ids.clone(),
+            initial_positions,
             version: team.version,
             players,
             stats,
@@ -183,15 +207,62 @@ impl<'game> TeamInGame {
         }
     }
 
-    pub fn from_team_id(team_id: TeamId, teams: &TeamMap, players: &PlayerMap) -> Option<Self> {
-        let team = teams.get(&team_id)?;
+    pub fn from_team_id(team_id: TeamId, teams: &TeamMap, players: &PlayerMap) -> AppResult<Self> {
+        let team = teams.get(&team_id).ok_or(format!("Team {:?} not found", team_id))?;
         let mut team_players = PlayerMap::new();
         for player_id in team.player_ids.iter() {
-            let player = players.get(player_id)?;
+            let player = players
+                .get(player_id)
+                .ok_or(format!("Player {:?} not found", player_id))?;
             team_players.insert(player_id.clone(), player.clone());
         }
 
-        Some(TeamInGame::new(team, team_players))
+        let team_in_game = TeamInGame::new(team, team_players);
+        if team_in_game.initial_positions.len() < MIN_PLAYERS_PER_TEAM {
+            return Err(format!("{} does not have enough healthy players", team.name).into());
+        }
+        Ok(team_in_game)
+    }
+
+    /// Returns a copy of the team as it was at tip-off, before any stats were recorded.
+    pub fn rewound(&self) -> Self {
+        let mut stats = HashMap::new();
+        for (idx, player_id) in self.initial_positions.iter().enumerate() {
+            let mut player_stats = GameStats::default();
+            if (idx as Position) < MAX_POSITION {
+                player_stats.position = Some(idx as Position);
+            }
+            if let Some(current) = self.stats.get(player_id) {
+                player_stats.initial_tiredness = current.initial_tiredness;
+                player_stats.tiredness = current.initial_tiredness;
+                player_stats.morale = current.morale;
+            }
+            stats.insert(player_id.clone(), player_stats);
+        }
+        let mut team = Self {
+            stats,
+            ..self.clone()
+        };
+        let momentum = team.starting_momentum();
+        team.offensive_momentum = momentum;
+        team.defensive_momentum = momentum;
+        team
+    }
+
+    /// Teams with high morale start the game with some momentum.
+    pub fn starting_momentum(&self) -> u8 {
+        let starters = self
+            .initial_positions
+            .iter()
+            .take(MAX_POSITION as usize)
+            .filter_map(|id| self.stats.get(id))
+            .map(|stats| stats.morale as u16)
+            .collect::<Vec<u16>>();
+        if starters.len() == 0 {
+            return 0;
+        }
+        let average_morale = starters.iter().sum::<u16>() / starters.len() as u16;
+        (average_morale / MORALE_PER_STARTING_MOMENTUM as u16).min(MAX_MOMENTUM as u16) as u8
     }
 }
 
@@ -1,6 +1,7 @@
 use std::cmp::min;
 
-use super::constants::MAX_TIREDNESS;
+use super::{constants::MAX_TIREDNESS, types::GameStats};
+use crate::world::{player::Player, skill::MAX_SKILL};
 use rand::Rng;
 use rand_chacha::ChaCha8Rng;
 
@@ -11,6 +12,18 @@ pub fn roll(rng: &mut ChaCha8Rng, tiredness: f32) -> u8 {
     )
 }
 
+pub fn is_foul(
+    rng: &mut ChaCha8Rng,
+    defender: &Player,
+    defender_stats: &GameStats,
+    base_probability: f64,
+) -> bool {
+    // Tired defenders foul more, well positioned ones foul less.
+    let tiredness_factor = 1.0 + defender_stats.tiredness as f64 / MAX_TIREDNESS as f64;
+    let positioning_factor = 1.5 - defender.mental.positioning as f64 / MAX_SKILL as f64;
+    rng.gen_bool((base_probability * tiredness_factor * positioning_factor).min(1.0))
+}
+
 #[cfg(test)]
 mod tests {
     use super::roll;
@@ -22,17 +22,28 @@ pub struct PlayerImage {
     head: HeadImage,
     body: BodyImage,
     legs: LegsImage,
+    // Missing parts are left out of saves to keep them small.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     hair: Option<HairImage>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     beard: Option<BeardImage>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     shirt: Option<ShirtImage>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     shorts: Option<ShortsImage>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     shoes: Option<ShoesImage>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub hat: Option<HatImage>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub wooden_leg: Option<WoodenLegImage>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub eye_patch: Option<EyePatchImage>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub hook: Option<HookImage>,
     skin_color_map: SkinColorMap,
     hair_color_map: HairColorMap,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     jersey_color_map: Option<ColorMap>,
     pub blinking_bitmap: u16,
 }
@@ -213,6 +224,12 @@ impl PlayerImage {
         self.jersey_color_map = Some(jersey.color);
     }
 
+    /// Removes the parts that `set_jersey` derives from the team jersey and the crew role.
+    pub fn remove_outfit(&mut self) {
+        self.remove_jersey();
+        self.hat = None;
+    }
+
     pub fn remove_jersey(&mut self) {
         self.shirt = None;
         self.shorts = None;
@@ -5,7 +5,10 @@ pub mod event;
 pub mod image;
 pub mod network;
 pub mod relayer;
+pub mod simulator;
 pub mod store;
+#[cfg(test)]
+mod test_utils;
 pub mod tui;
 pub mod types;
 pub mod ui;
@@ -1,7 +1,18 @@
-use clap::{ArgAction, Parser};
+use clap::{ArgAction, Parser, Subcommand};
+use log::LevelFilter;
 use rebels::app::App;
+use rebels::network::config::NetworkConfig;
+use rebels::network::types::NetworkTeam;
 use rebels::relayer::Relayer;
+use rebels::simulator::{OutputFormat, Simulator};
+use rebels::store::{
+    load_team_file, load_world, save_team_file, save_world, set_compression, set_profile,
+    store_stats, world_exists,
+};
 use rebels::types::AppResult;
+use rebels::world::world::World;
+use std::net::IpAddr;
+use std::path::{Path, PathBuf};
 
 #[derive(Parser, Debug)]
 #[clap(name="Rebels in the Sky", about = "P(lanet)2P(lanet) basketball", author, version, long_about = None)]
@@ -12,21 +23,121 @@ struct Args {
     disable_network: bool,
     #[clap(long, short='a', action=ArgAction::SetTrue, help = "Disable audio")]
     disable_audio: bool,
-    #[clap(long, short='r', action=ArgAction::SetTrue, help = "Reset all save files")]
+    #[clap(long, short='r', action=ArgAction::SetTrue, help = "Reset all save files of the profile")]
     reset_world: bool,
+    #[clap(long, action=ArgAction::Set, help = "Use a named profile, with its own world and network identity")]
+    profile: Option<String>,
+    #[clap(long, short='z', action=ArgAction::SetTrue, help = "Compress save files")]
+    compress_saves: bool,
     #[clap(long, short='f', action=ArgAction::SetFalse, help = "Disable generating local teams")]
     generate_local_world: bool,
     #[clap(long, short='n', action=ArgAction::SetTrue, help = "Run in network relayer mode (no game)")]
     relayer_mode: bool,
-    #[clap(long, short = 'i', action=ArgAction::Set, help = "Set ip of seed node")]
+    #[clap(long, short = 'i', action=ArgAction::Set, help = "Set ip or host name of seed node")]
     seed_ip: Option<String>,
+    #[clap(long, short = 'b', action=ArgAction::Append, help = "Add a bootstrap peer (multiaddr, ip or host name, with optional port)")]
+    bootstrap: Vec<String>,
+    #[clap(long, action=ArgAction::Set, help = "Set ip to listen on (IPv4 or IPv6)")]
+    listen_ip: Option<IpAddr>,
+    #[clap(long, action=ArgAction::Set, help = "Set port to listen on")]
+    port: Option<u16>,
+    #[clap(long, short='m', action=ArgAction::SetTrue, help = "Discover peers on the local network with mDNS")]
+    mdns: bool,
+    #[clap(long, action=ArgAction::SetTrue, help = "Generate a new network identity (peer id)")]
+    rotate_keypair: bool,
+    #[clap(long, default_value_t = LevelFilter::Info, help = "Relayer log level (off, error, warn, info, debug, trace)")]
+    log_level: LevelFilter,
+    #[clap(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    #[clap(about = "Play games between two teams without a terminal and print aggregate stats")]
+    Simulate {
+        #[clap(
+            long,
+            short = 'g',
+            default_value_t = 100,
+            help = "Number of games to play"
+        )]
+        games: usize,
+        #[clap(long, short = 't', action=ArgAction::Append, help = "Load a team from a JSON file (up to two, missing teams are generated)")]
+        team: Vec<PathBuf>,
+        #[clap(long, short = 'p', action=ArgAction::Set, help = "Name of the planet where games are played")]
+        planet: Option<String>,
+        #[clap(long, short = 'o', value_enum, default_value_t = OutputFormat::Json, help = "Output format")]
+        format: OutputFormat,
+    },
+    #[clap(about = "Export a team of the profile world to a file, to share it or move it to another machine")]
+    ExportTeam {
+        #[clap(help = "File to write the team to")]
+        path: PathBuf,
+        #[clap(long, short = 't', action=ArgAction::Set, help = "Name of the team to export (defaults to the own team)")]
+        team: Option<String>,
+    },
+    #[clap(about = "Import a team from a file into the profile world as a local team (close the game first)")]
+    ImportTeam {
+        #[clap(help = "File to read the team from")]
+        path: PathBuf,
+        #[clap(long, action=ArgAction::SetTrue, help = "Play with the imported team, the current own team becomes a local team")]
+        own: bool,
+    },
+    #[clap(about = "Inspect the save files of the profile")]
+    Store {
+        #[clap(subcommand)]
+        command: StoreCommand,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum StoreCommand {
+    #[clap(about = "Break down the size of the world save by entity type")]
+    Stats,
 }
 
 #[tokio::main]
 async fn main() -> AppResult<()> {
     let args = Args::parse();
+    if let Some(profile) = args.profile.as_ref() {
+        set_profile(profile)?;
+    }
+    set_compression(args.compress_saves);
+    match args.command {
+        Some(Command::Simulate {
+            games,
+            team,
+            planet,
+            format,
+        }) => {
+            let report = Simulator::new(args.seed, team, planet)?.run(games)?;
+            println!("{}", report.format(format)?);
+            return Ok(());
+        }
+        Some(Command::ExportTeam { path, team }) => {
+            return export_team(&path, team);
+        }
+        Some(Command::ImportTeam { path, own }) => {
+            return import_team(&path, own, args.seed, args.generate_local_world);
+        }
+        Some(Command::Store {
+            command: StoreCommand::Stats,
+        }) => {
+            println!("{}", store_stats()?.format());
+            return Ok(());
+        }
+        None => {}
+    }
+
+    let network_config = NetworkConfig::new(
+        args.listen_ip,
+        args.port,
+        args.seed_ip,
+        args.bootstrap,
+        args.mdns,
+    )?;
     if args.relayer_mode {
-        Relayer::new().run().await?;
+        Relayer::new(args.log_level, &network_config).run().await?;
     } else {
         App::new(
             args.seed,
@@ -34,7 +145,8 @@ async fn main() -> AppResult<()> {
             args.disable_audio,
             args.generate_local_world,
             args.reset_world,
-            args.seed_ip,
+            network_config,
+            args.rotate_keypair,
         )
         .run()
         .await?;
@@ -42,3 +154,50 @@ async fn main() -> AppResult<()> {
 
     Ok(())
 }
+
+fn export_team(path: &Path, team_name: Option<String>) -> AppResult<()> {
+    let world = load_world()?;
+    let team_id = match team_name {
+        Some(name) => {
+            world
+                .teams
+                .values()
+                .find(|team| team.name.to_lowercase() == name.to_lowercase())
+                .ok_or(format!("Team {} not found", name))?
+                .id
+        }
+        None => world.get_own_team()?.id,
+    };
+    let network_team = NetworkTeam::from_team_id(&world, &team_id)?;
+    save_team_file(&network_team, path)?;
+    println!(
+        "Exported team {} to {}",
+        network_team.team.name,
+        path.display()
+    );
+    Ok(())
+}
+
+fn import_team(
+    path: &Path,
+    as_own_team: bool,
+    seed: Option<u64>,
+    generate_local_world: bool,
+) -> AppResult<()> {
+    let network_team = load_team_file(path)?;
+    let mut world = if world_exists() {
+        load_world()?
+    } else if as_own_team {
+        // Start a new world around the imported team.
+        let mut world = World::new(seed);
+        world.initialize(generate_local_world)?;
+        world
+    } else {
+        return Err("No world to import the team into, start a game first or use --own".into());
+    };
+    let team_name = network_team.team.name.clone();
+    world.import_team(network_team, as_own_team)?;
+    save_world(&world, true)?;
+    println!("Imported team {} from {}", team_name, path.display());
+    Ok(())
+}
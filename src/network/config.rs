@@ -0,0 +1,163 @@
+use super::constants::{DEFAULT_PORT, DEFAULT_PORT_BACKUP, SEED_ADDRESS};
+use crate::types::AppResult;
+use libp2p::multiaddr::Protocol;
+use libp2p::Multiaddr;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct NetworkConfig {
+    // Tried in order, we listen on the first one that is available.
+    pub listen_addresses: Vec<Multiaddr>,
+    // Dialed once we are listening, and again when pinging the seed.
+    pub bootstrap_addresses: Vec<Multiaddr>,
+    pub enable_mdns: bool,
+}
+
+impl Default for NetworkConfig {
+    fn default() -> Self {
+        Self {
+            listen_addresses: Self::listen_addresses_for(IpAddr::V4(Ipv4Addr::UNSPECIFIED), None),
+            bootstrap_addresses: vec![SEED_ADDRESS.parse().expect("Invalid seed address")],
+            enable_mdns: false,
+        }
+    }
+}
+
+impl NetworkConfig {
+    /// Builds the configuration from the command line options.
+    /// The seed ip and the bootstrap addresses replace the default seed node.
+    pub fn new(
+        listen_ip: Option<IpAddr>,
+        port: Option<u16>,
+        seed_ip: Option<String>,
+        bootstrap: Vec<String>,
+        enable_mdns: bool,
+    ) -> AppResult<Self> {
+        let mut config = Self {
+            listen_addresses: Self::listen_addresses_for(
+                listen_ip.unwrap_or(IpAddr::V4(Ipv4Addr::UNSPECIFIED)),
+                port,
+            ),
+            enable_mdns,
+            ..Self::default()
+        };
+
+        let bootstrap_addresses = seed_ip
+            .iter()
+            .chain(bootstrap.iter())
+            .map(|address| parse_address(address))
+            .collect::<AppResult<Vec<Multiaddr>>>()?;
+        if !bootstrap_addresses.is_empty() {
+            config.bootstrap_addresses = bootstrap_addresses;
+        }
+
+        Ok(config)
+    }
+
+    fn listen_addresses_for(ip: IpAddr, port: Option<u16>) -> Vec<Multiaddr> {
+        // Without an explicit port, fall back to the backup one if the default is taken.
+        let ports = match port {
+            Some(port) => vec![port],
+            None => vec![DEFAULT_PORT, DEFAULT_PORT_BACKUP],
+        };
+        ports
+            .iter()
+            .map(|port| Multiaddr::from(ip).with(Protocol::Tcp(*port)))
+            .collect()
+    }
+}
+
+/// Parses a peer address given as a multiaddr, an ip or a host name, with an optional port.
+/// Addresses without a port use the default one.
+pub fn parse_address(address: &str) -> AppResult<Multiaddr> {
+    let address = address.trim();
+    if address.starts_with('/') {
+        return Ok(address.parse()?);
+    }
+    if let Ok(socket_address) = address.parse::<SocketAddr>() {
+        return Ok(Multiaddr::from(socket_address.ip()).with(Protocol::Tcp(socket_address.port())));
+    }
+    if let Ok(ip) = address.trim_matches(|c| c == '[' || c == ']').parse::<IpAddr>() {
+        return Ok(Multiaddr::from(ip).with(Protocol::Tcp(DEFAULT_PORT)));
+    }
+
+    let (host, port) = match address.rsplit_once(':') {
+        Some((host, port)) => (
+            host,
+            port.parse::<u16>()
+                .map_err(|_| format!("Invalid port in address {}", address))?,
+        ),
+        None => (address, DEFAULT_PORT),
+    };
+    if host.is_empty() || host.contains(|c: char| c.is_whitespace() || c == '/' || c == ':') {
+        return Err(format!("Invalid address {}", address).into());
+    }
+    Ok(Multiaddr::empty()
+        .with(Protocol::Dns(host.into()))
+        .with(Protocol::Tcp(port)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_address, NetworkConfig};
+    use crate::network::constants::{DEFAULT_PORT, DEFAULT_PORT_BACKUP};
+    use libp2p::Multiaddr;
+
+    #[test]
+    fn test_network_config() {
+        let address = |s: &str| s.parse::<Multiaddr>().unwrap();
+        assert_eq!(
+            parse_address("85.214.130.204").unwrap(),
+            address(&format!("/ip4/85.214.130.204/tcp/{DEFAULT_PORT}"))
+        );
+        assert_eq!(
+            parse_address("10.0.0.1:4000").unwrap(),
+            address("/ip4/10.0.0.1/tcp/4000")
+        );
+        assert_eq!(
+            parse_address("::1").unwrap(),
+            address(&format!("/ip6/::1/tcp/{DEFAULT_PORT}"))
+        );
+        assert_eq!(
+            parse_address("[2001:db8::1]:4000").unwrap(),
+            address("/ip6/2001:db8::1/tcp/4000")
+        );
+        assert_eq!(
+            parse_address("seed.example.org").unwrap(),
+            address(&format!("/dns/seed.example.org/tcp/{DEFAULT_PORT}"))
+        );
+        assert_eq!(
+            parse_address("/dns6/seed.example.org/tcp/4000").unwrap(),
+            address("/dns6/seed.example.org/tcp/4000")
+        );
+        assert!(parse_address("seed.example.org:port").is_err());
+        assert!(parse_address("").is_err());
+
+        let config = NetworkConfig::default();
+        assert_eq!(
+            config.listen_addresses,
+            vec![
+                address(&format!("/ip4/0.0.0.0/tcp/{DEFAULT_PORT}")),
+                address(&format!("/ip4/0.0.0.0/tcp/{DEFAULT_PORT_BACKUP}"))
+            ]
+        );
+
+        let config = NetworkConfig::new(
+            Some("::".parse().unwrap()),
+            Some(4000),
+            Some("10.0.0.1".into()),
+            vec!["seed.example.org".into()],
+            true,
+        )
+        .unwrap();
+        assert_eq!(config.listen_addresses, vec![address("/ip6/::/tcp/4000")]);
+        assert_eq!(
+            config.bootstrap_addresses,
+            vec![
+                address(&format!("/ip4/10.0.0.1/tcp/{DEFAULT_PORT}")),
+                address(&format!("/dns/seed.example.org/tcp/{DEFAULT_PORT}"))
+            ]
+        );
+        assert!(config.enable_mdns);
+    }
+}
@@ -1,13 +1,56 @@
+use crate::types::{Tick, DAYS, MINUTES};
+use std::time::Duration;
+
 pub struct SubscriptionTopic {}
 
 impl SubscriptionTopic {
     pub const TEAM: &'static str = "rebels-b2b-team";
-    pub const CHALLENGE: &'static str = "rebels-b2b-challenge";
     pub const MSG: &'static str = "rebels-b2b-msg";
     pub const GAME: &'static str = "rebels-b2b-game";
     pub const SEED_INFO: &'static str = "rebels-b2b-seed";
+    pub const HANDSHAKE: &'static str = "rebels-b2b-handshake";
 }
 
 pub const DEFAULT_PORT: u16 = 37202;
 pub const DEFAULT_PORT_BACKUP: u16 = 37203;
 pub const SEED_ADDRESS: &'static str = "/ip4/85.214.130.204/tcp/37202";
+
+pub const PROTOCOL_VERSION: u8 = 2;
+// Oldest protocol version we can still exchange data with.
+pub const MIN_PROTOCOL_VERSION: u8 = 2;
+pub const SUPPORTED_FEATURES: [&str; 6] =
+    ["team", "game", "challenge", "msg", "checksum", "leaderboard"];
+// Features a peer must support for us to exchange teams and games with it.
+pub const REQUIRED_FEATURES: [&str; 3] = ["team", "game", "challenge"];
+// Payloads must fit in a single gossipsub message (64 KiB by default).
+pub const MAX_PAYLOAD_SIZE: usize = 60 * 1024;
+pub const MAX_DECOMPRESSED_PAYLOAD_SIZE: usize = 1024 * 1024;
+pub const COMPRESSION_THRESHOLD: usize = 256;
+
+// Skills grow by less than 0.5 per game, and every game bumps the player version.
+pub const MAX_SKILL_INCREASE_PER_VERSION: f32 = 0.5;
+// Generated players average at most this much above their age-capped base level.
+pub const MAX_GENERATED_SKILL_ABOVE_BASE_LEVEL: f32 = 13.0;
+// Versions are set by the peer, so players we have never seen get credit for at most
+// this much average growth, about two dozen games worth of experience.
+pub const MAX_UNSEEN_PLAYER_SKILL_GROWTH: f32 = 2.0;
+// A game lasts 46 minutes, so a team cannot finish more than one game in this time.
+pub const MIN_GAME_DURATION: Tick = 40 * MINUTES;
+pub const MAX_NETWORK_TEAM_BALANCE: u32 = 100_000_000;
+
+// Number of game actions covered by each checksum message.
+pub const GAME_CHECKSUM_INTERVAL: usize = 20;
+
+// Challenges are sent directly to the challenged peer.
+pub const CHALLENGE_PROTOCOL: &str = "/rebels/challenge/1";
+pub const CHALLENGE_MAX_ATTEMPTS: u8 = 3;
+pub const CHALLENGE_EXPIRY: Tick = 5 * MINUTES;
+// Challenge requests are only answered once the challenged team accepts or declines.
+pub const CHALLENGE_REQUEST_TIMEOUT: Duration = Duration::from_millis(CHALLENGE_EXPIRY as u64);
+
+// The relayer keeps the latest team of every peer and the results of network games.
+pub const RELAYER_SAVE_INTERVAL: Duration = Duration::from_secs(60);
+pub const RELAYER_DIRECTORY_SIZE: usize = 32;
+pub const LEADERBOARD_SIZE: usize = 20;
+// Games without a result after this long are assumed abandoned.
+pub const RELAYER_GAME_EXPIRY: Tick = DAYS;
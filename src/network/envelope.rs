@@ -0,0 +1,259 @@
+use super::constants::{
+    SubscriptionTopic, COMPRESSION_THRESHOLD, MAX_DECOMPRESSED_PAYLOAD_SIZE, MAX_PAYLOAD_SIZE,
+    MIN_PROTOCOL_VERSION, PROTOCOL_VERSION,
+};
+use crate::types::{AppResult, SystemTimeTick, Tick};
+use flate2::{read::DeflateDecoder, write::DeflateEncoder, Compression as DeflateLevel};
+use serde::{de::DeserializeOwned, Serialize};
+use std::io::{Read, Write};
+
+// Header layout: version (1) | message type (1) | compression (1) | timestamp (16) | payload length (4)
+// The header layout and the handshake message must not change between protocol versions.
+const HEADER_SIZE: usize = 23;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[repr(u8)]
+pub enum MessageType {
+    Team = 0,
+    Msg = 2,
+    Game = 3,
+    SeedInfo = 4,
+    Handshake = 5,
+    GameChecksum = 6,
+    Leaderboard = 7,
+}
+
+impl MessageType {
+    pub fn topic(&self) -> &'static str {
+        match self {
+            Self::Team => SubscriptionTopic::TEAM,
+            Self::Msg => SubscriptionTopic::MSG,
+            Self::Game => SubscriptionTopic::GAME,
+            Self::SeedInfo => SubscriptionTopic::SEED_INFO,
+            Self::Handshake => SubscriptionTopic::HANDSHAKE,
+            Self::GameChecksum => SubscriptionTopic::GAME,
+            Self::Leaderboard => SubscriptionTopic::SEED_INFO,
+        }
+    }
+}
+
+impl TryFrom<u8> for MessageType {
+    type Error = String;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Self::Team),
+            2 => Ok(Self::Msg),
+            3 => Ok(Self::Game),
+            4 => Ok(Self::SeedInfo),
+            5 => Ok(Self::Handshake),
+            6 => Ok(Self::GameChecksum),
+            7 => Ok(Self::Leaderboard),
+            _ => Err(format!("Unknown message type {}", value)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[repr(u8)]
+pub enum Compression {
+    None = 0,
+    Deflate = 1,
+}
+
+impl TryFrom<u8> for Compression {
+    type Error = String;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Self::None),
+            1 => Ok(Self::Deflate),
+            _ => Err(format!("Unknown compression {}", value)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Envelope {
+    pub version: u8,
+    pub message_type: MessageType,
+    pub timestamp: Tick,
+    pub payload: Vec<u8>,
+}
+
+impl Envelope {
+    pub fn new(message_type: MessageType, payload: Vec<u8>) -> Self {
+        Self {
+            version: PROTOCOL_VERSION,
+            message_type,
+            timestamp: Tick::now(),
+            payload,
+        }
+    }
+
+    pub fn from_data<T: Serialize>(message_type: MessageType, data: &T) -> AppResult<Self> {
+        Ok(Self::new(message_type, serde_json::to_vec(data)?))
+    }
+
+    pub fn data<T: DeserializeOwned>(&self) -> AppResult<T> {
+        Ok(serde_json::from_slice(&self.payload)?)
+    }
+
+    pub fn encode(&self) -> AppResult<Vec<u8>> {
+        // Small payloads, like chat messages, do not benefit from compression.
+        let (compression, payload) = if self.payload.len() > COMPRESSION_THRESHOLD {
+            let mut encoder = DeflateEncoder::new(vec![], DeflateLevel::default());
+            encoder.write_all(&self.payload)?;
+            (Compression::Deflate, encoder.finish()?)
+        } else {
+            (Compression::None, self.payload.clone())
+        };
+
+        if payload.len() > MAX_PAYLOAD_SIZE {
+            return Err(format!(
+                "Message payload too large: {} bytes (max {})",
+                payload.len(),
+                MAX_PAYLOAD_SIZE
+            )
+            .into());
+        }
+
+        let mut bytes = Vec::with_capacity(HEADER_SIZE + payload.len());
+        bytes.push(self.version);
+        bytes.push(self.message_type as u8);
+        bytes.push(compression as u8);
+        bytes.extend_from_slice(&self.timestamp.to_le_bytes());
+        bytes.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(&payload);
+        Ok(bytes)
+    }
+
+    pub fn decode(bytes: &[u8]) -> AppResult<Self> {
+        if bytes.len() < HEADER_SIZE {
+            return Err(format!("Message too short: {} bytes", bytes.len()).into());
+        }
+
+        let version = bytes[0];
+        let message_type = MessageType::try_from(bytes[1])?;
+        // Handshakes are readable by every version, so that peers can tell why they are incompatible.
+        if !(MIN_PROTOCOL_VERSION..=PROTOCOL_VERSION).contains(&version)
+            && message_type != MessageType::Handshake
+        {
+            return Err(format!(
+                "Unsupported protocol version {} (supported {} to {})",
+                version, MIN_PROTOCOL_VERSION, PROTOCOL_VERSION
+            )
+            .into());
+        }
+        let compression = Compression::try_from(bytes[2])?;
+        let timestamp = Tick::from_le_bytes(bytes[3..19].try_into()?);
+        let payload_length = u32::from_le_bytes(bytes[19..HEADER_SIZE].try_into()?) as usize;
+
+        if payload_length > MAX_PAYLOAD_SIZE {
+            return Err(format!("Message payload too large: {} bytes", payload_length).into());
+        }
+        if bytes.len() - HEADER_SIZE != payload_length {
+            return Err(format!(
+                "Message payload length mismatch: expected {} bytes, got {}",
+                payload_length,
+                bytes.len() - HEADER_SIZE
+            )
+            .into());
+        }
+
+        let raw_payload = &bytes[HEADER_SIZE..];
+        let payload = match compression {
+            Compression::None => raw_payload.to_vec(),
+            Compression::Deflate => {
+                // Read one byte past the limit to detect oversized payloads
+                // without inflating all of them.
+                let mut payload = vec![];
+                DeflateDecoder::new(raw_payload)
+                    .take(MAX_DECOMPRESSED_PAYLOAD_SIZE as u64 + 1)
+                    .read_to_end(&mut payload)?;
+                if payload.len() > MAX_DECOMPRESSED_PAYLOAD_SIZE {
+                    return Err("Decompressed message payload too large".into());
+                }
+                payload
+            }
+        };
+
+        Ok(Self {
+            version,
+            message_type,
+            timestamp,
+            payload,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Envelope, MessageType, HEADER_SIZE};
+    use crate::network::constants::{MAX_PAYLOAD_SIZE, MIN_PROTOCOL_VERSION, PROTOCOL_VERSION};
+    use crate::network::types::{Handshake, NetworkTeam};
+    use crate::test_utils::test_world_and_game;
+
+    #[test]
+    fn test_envelope() {
+        let (world, game) = test_world_and_game();
+        let team_id = game.home_team_in_game.team_id;
+        let network_team = NetworkTeam::from_team_id(&world, &team_id).unwrap();
+
+        let envelope = Envelope::from_data(MessageType::Team, &network_team).unwrap();
+        let bytes = envelope.encode().unwrap();
+        // Team data is compressed on the wire.
+        assert!(bytes.len() < envelope.payload.len());
+
+        let decoded = Envelope::decode(&bytes).unwrap();
+        assert_eq!(decoded, envelope);
+        let team = decoded.data::<NetworkTeam>().unwrap().team;
+        assert_eq!(team, network_team.team);
+
+        let chat = Envelope::new(MessageType::Msg, b"hello".to_vec());
+        let bytes = chat.encode().unwrap();
+        assert_eq!(bytes.len(), HEADER_SIZE + 5);
+        assert_eq!(Envelope::decode(&bytes).unwrap(), chat);
+
+        // Truncated, unknown and oversized messages are rejected.
+        assert!(Envelope::decode(&bytes[..bytes.len() - 1]).is_err());
+        assert!(Envelope::decode(&bytes[..HEADER_SIZE - 1]).is_err());
+        let mut unknown_type = bytes.clone();
+        unknown_type[1] = 42;
+        assert!(Envelope::decode(&unknown_type).is_err());
+        let mut wrong_version = bytes.clone();
+        wrong_version[0] = PROTOCOL_VERSION + 1;
+        assert!(Envelope::decode(&wrong_version).is_err());
+        wrong_version[0] = MIN_PROTOCOL_VERSION - 1;
+        assert!(Envelope::decode(&wrong_version).is_err());
+        // Older peers we are still compatible with can be understood.
+        let mut old_version = bytes.clone();
+        old_version[0] = MIN_PROTOCOL_VERSION;
+        assert_eq!(Envelope::decode(&old_version).unwrap().payload, chat.payload);
+        let mut oversized = bytes.clone();
+        oversized[19..HEADER_SIZE].copy_from_slice(&(MAX_PAYLOAD_SIZE as u32 + 1).to_le_bytes());
+        assert!(Envelope::decode(&oversized).is_err());
+    }
+
+    #[test]
+    fn test_handshake() {
+        let handshake = Handshake::new();
+        assert!(handshake.check_compatibility().is_ok());
+
+        // Handshakes from other protocol versions can still be read.
+        let mut envelope = Envelope::from_data(MessageType::Handshake, &handshake).unwrap();
+        envelope.version = PROTOCOL_VERSION + 1;
+        let decoded = Envelope::decode(&envelope.encode().unwrap()).unwrap();
+        assert_eq!(decoded.data::<Handshake>().unwrap(), handshake);
+        envelope.message_type = MessageType::Team;
+        assert!(Envelope::decode(&envelope.encode().unwrap()).is_err());
+
+        let mut newer = handshake.clone();
+        newer.min_protocol_version = PROTOCOL_VERSION + 1;
+        assert!(newer.check_compatibility().is_err());
+
+        let mut limited = handshake.clone();
+        limited.features.retain(|f| f != "challenge");
+        assert!(limited.check_compatibility().is_err());
+    }
+}
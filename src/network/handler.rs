@@ -1,6 +1,11 @@
+use super::config::NetworkConfig;
 use super::constants::*;
+use super::envelope::{Envelope, MessageType};
 use super::network_callback::NetworkCallbackPreset;
-use super::types::{Challenge, ChallengeState, NetworkGame, NetworkTeam, SeedInfo};
+use super::types::{
+    Challenge, ChallengeState, GameChecksum, GameDesync, Handshake, Leaderboard, NetworkGame,
+    NetworkTeam, SeedInfo, TopicTraffic,
+};
 use crate::engine::types::TeamInGame;
 use crate::types::TeamId;
 use crate::types::{AppResult, GameId};
@@ -8,22 +13,52 @@ use crate::types::{SystemTimeTick, Tick};
 use crate::world::world::World;
 use libp2p::core::upgrade::Version;
 use libp2p::gossipsub::{self, IdentTopic, MessageId};
-use libp2p::swarm::{Config, SwarmEvent};
-use libp2p::{identity, noise, tcp, yamux, PeerId, Transport};
+use libp2p::request_response::{
+    self, OutboundFailure, ProtocolSupport, RequestId, ResponseChannel,
+};
+use libp2p::swarm::{
+    behaviour::toggle::Toggle, dial_opts::DialOpts, Config, NetworkBehaviour, SwarmEvent,
+};
+use libp2p::{dns, identity, mdns, noise, tcp, yamux, PeerId, StreamProtocol, Transport};
 use libp2p::{Multiaddr, Swarm};
 use std::collections::hash_map::DefaultHasher;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::error::Error;
 use std::fmt::Debug;
 use std::hash::{Hash, Hasher};
 use std::time::Duration;
-use void::Void;
+
+#[derive(NetworkBehaviour)]
+pub struct RebelsBehaviour {
+    pub gossipsub: gossipsub::Behaviour,
+    pub challenge: request_response::json::Behaviour<Challenge, Challenge>,
+    pub mdns: Toggle<mdns::tokio::Behaviour>,
+}
+
+#[allow(deprecated)]
+pub type NetworkEvent = SwarmEvent<RebelsBehaviourEvent, libp2p::swarm::THandlerErr<RebelsBehaviour>>;
 
 pub struct NetworkHandler {
-    pub swarm: Swarm<gossipsub::Behaviour>,
+    pub swarm: Swarm<RebelsBehaviour>,
     pub address: Multiaddr,
+    // Open challenges, keyed by the peer on the other side.
     challenges: HashMap<PeerId, Challenge>,
-    pub seed_address: Multiaddr,
+    // Challenge requests waiting for a response, with the number of attempts.
+    outbound_challenges: HashMap<RequestId, (PeerId, Challenge, u8)>,
+    response_channels: HashMap<RequestId, ResponseChannel<Challenge>>,
+    // Challenges received from each peer, waiting to be accepted or declined.
+    challenge_requests: HashMap<PeerId, RequestId>,
+    pub bootstrap_addresses: Vec<Multiaddr>,
+    // Handshakes of compatible peers, and the reason why other peers were rejected.
+    peers: HashMap<PeerId, Handshake>,
+    incompatible_peers: HashMap<PeerId, String>,
+    // Index of the next action to checksum for each of our network games.
+    sent_checksums: HashMap<GameId, usize>,
+    // Checksums received for actions that we have not simulated yet.
+    pending_checksums: Vec<(PeerId, GameChecksum)>,
+    desynced_games: HashSet<GameId>,
+    // Messages and bytes published on each topic.
+    pub sent_traffic: HashMap<String, TopicTraffic>,
 }
 
 impl Debug for NetworkHandler {
@@ -31,17 +66,23 @@ impl Debug for NetworkHandler {
         f.debug_struct("NetworkHandler")
             .field("address", &self.address)
             .field("challenges", &self.challenges)
+            .field("peers", &self.peers)
+            .field("incompatible_peers", &self.incompatible_peers)
             .finish()
     }
 }
 
 impl NetworkHandler {
-    pub fn new(seed_ip: Option<String>) -> Result<Self, Box<dyn Error>> {
-        env_logger::init();
-        let local_key = identity::Keypair::generate_ed25519();
+    pub fn new(
+        local_key: identity::Keypair,
+        config: &NetworkConfig,
+    ) -> Result<Self, Box<dyn Error>> {
+        // The relayer sets up its own logger before creating the handler.
+        let _ = env_logger::try_init();
         let local_peer_id = PeerId::from(local_key.public());
 
-        let tcp_transport = tcp::tokio::Transport::default()
+        // Resolve /dns addresses, so that bootstrap peers can be given by host name.
+        let tcp_transport = dns::tokio::Transport::system(tcp::tokio::Transport::default())?
             .upgrade(Version::V1Lazy)
             .authenticate(noise::Config::new(&local_key)?)
             .multiplex(yamux::Config::default())
@@ -82,50 +123,83 @@ impl NetworkHandler {
         gossipsub.subscribe(&IdentTopic::new(SubscriptionTopic::TEAM))?;
         gossipsub.subscribe(&IdentTopic::new(SubscriptionTopic::MSG))?;
         gossipsub.subscribe(&IdentTopic::new(SubscriptionTopic::GAME))?;
-        gossipsub.subscribe(&IdentTopic::new(SubscriptionTopic::CHALLENGE))?;
+        gossipsub.subscribe(&IdentTopic::new(SubscriptionTopic::HANDSHAKE))?;
+
+        let mut challenge_config = request_response::Config::default();
+        challenge_config.set_request_timeout(CHALLENGE_REQUEST_TIMEOUT);
+        let challenge = request_response::json::Behaviour::new(
+            [(
+                StreamProtocol::new(CHALLENGE_PROTOCOL),
+                ProtocolSupport::Full,
+            )],
+            challenge_config,
+        );
+
+        // Finds peers on the local network without going through the seed.
+        let mdns = if config.enable_mdns {
+            Some(mdns::tokio::Behaviour::new(
+                mdns::Config::default(),
+                local_peer_id,
+            )?)
+        } else {
+            None
+        };
 
         let mut swarm = Swarm::new(
             tcp_transport,
-            gossipsub,
+            RebelsBehaviour {
+                gossipsub,
+                challenge,
+                mdns: mdns.into(),
+            },
             local_peer_id,
             Config::with_tokio_executor(),
         );
 
         // swarm.listen_on("/ip4/0.0.0.0/udp/0/quic-v1".parse()?)?;
-        if swarm
-            .listen_on(format!("/ip4/0.0.0.0/tcp/{DEFAULT_PORT}").parse()?)
-            .is_err()
+        if !config
+            .listen_addresses
+            .iter()
+            .any(|address| swarm.listen_on(address.clone()).is_ok())
         {
-            swarm.listen_on(format!("/ip4/0.0.0.0/tcp/{DEFAULT_PORT_BACKUP}").parse()?)?;
+            return Err("Cannot listen on any address".into());
         }
 
-        let seed_address = match seed_ip {
-            Some(ip) => format!("/ip4/{ip}/tcp/{DEFAULT_PORT}")
-                .parse()
-                .expect("Invalid provided seed ip."),
-            None => SEED_ADDRESS.parse()?,
-        };
-
         Ok(Self {
             swarm,
             address: Multiaddr::empty(),
             challenges: HashMap::new(),
-            seed_address,
+            outbound_challenges: HashMap::new(),
+            response_channels: HashMap::new(),
+            challenge_requests: HashMap::new(),
+            bootstrap_addresses: config.bootstrap_addresses.clone(),
+            peers: HashMap::new(),
+            incompatible_peers: HashMap::new(),
+            sent_checksums: HashMap::new(),
+            pending_checksums: vec![],
+            desynced_games: HashSet::new(),
+            sent_traffic: HashMap::new(),
         })
     }
 
-    fn _send(&mut self, data: Vec<u8>, topic: &str) -> AppResult<MessageId> {
-        let timestamp = Tick::now().to_le_bytes().to_vec();
+    fn _send(&mut self, envelope: Envelope) -> AppResult<MessageId> {
+        let topic = envelope.message_type.topic();
+        let data = envelope.encode()?;
+        let size = data.len();
         let msg_id = self
             .swarm
             .behaviour_mut()
-            .publish(IdentTopic::new(topic), [timestamp, data].concat());
-        Ok(msg_id?)
+            .gossipsub
+            .publish(IdentTopic::new(topic), data)?;
+        self.sent_traffic
+            .entry(topic.to_string())
+            .or_default()
+            .record(size);
+        Ok(msg_id)
     }
 
-    pub fn add_challenge(&mut self, challenge: Challenge) {
-        self.challenges
-            .insert(challenge.home_peer_id.clone(), challenge);
+    pub fn add_challenge(&mut self, peer_id: PeerId, challenge: Challenge) {
+        self.challenges.insert(peer_id, challenge);
     }
 
     pub fn dial(&mut self, address: Multiaddr) -> AppResult<()> {
@@ -135,13 +209,65 @@ impl NetworkHandler {
         Ok(())
     }
 
+    /// Dials every bootstrap peer, failing only if none of them could be dialed.
+    pub fn dial_bootstrap_peers(&mut self) -> AppResult<()> {
+        let mut errors = vec![];
+        for address in self.bootstrap_addresses.clone() {
+            if let Err(err) = self.dial(address.clone()) {
+                errors.push(format!("{}: {}", address, err));
+            }
+        }
+        if !errors.is_empty() && errors.len() == self.bootstrap_addresses.len() {
+            return Err(format!("Cannot dial bootstrap peers: {}", errors.join(", ")).into());
+        }
+        Ok(())
+    }
+
     pub fn send_msg(&mut self, msg: String) -> AppResult<MessageId> {
-        self._send(msg.as_bytes().to_vec(), SubscriptionTopic::MSG)
+        self._send(Envelope::new(MessageType::Msg, msg.as_bytes().to_vec()))
     }
 
     pub fn send_seed_info(&mut self, info: SeedInfo) -> AppResult<MessageId> {
-        let serialized_info = serde_json::to_string(&info)?.as_bytes().to_vec();
-        self._send(serialized_info, SubscriptionTopic::SEED_INFO)
+        self._send(Envelope::from_data(MessageType::SeedInfo, &info)?)
+    }
+
+    pub fn send_leaderboard(&mut self, leaderboard: &Leaderboard) -> AppResult<MessageId> {
+        self._send(Envelope::from_data(MessageType::Leaderboard, leaderboard)?)
+    }
+
+    /// Forwards a team received from another peer, used by the relayer to share its directory.
+    pub fn relay_team(&mut self, network_team: &NetworkTeam) -> AppResult<MessageId> {
+        self._send(Envelope::from_data(MessageType::Team, network_team)?)
+    }
+
+    pub fn send_handshake(&mut self) -> AppResult<MessageId> {
+        self._send(Envelope::from_data(MessageType::Handshake, &Handshake::new())?)
+    }
+
+    /// Records the handshake of a peer. Incompatible peers are disconnected and
+    /// the reason is returned as an error.
+    pub fn register_handshake(&mut self, peer_id: PeerId, handshake: Handshake) -> AppResult<()> {
+        if let Err(err) = handshake.check_compatibility() {
+            self.peers.remove(&peer_id);
+            self.incompatible_peers.insert(peer_id, err.to_string());
+            let _ = self.swarm.disconnect_peer_id(peer_id);
+            return Err(format!("Rejected peer {}: {}", peer_id, err).into());
+        }
+        self.incompatible_peers.remove(&peer_id);
+        self.peers.insert(peer_id, handshake);
+        Ok(())
+    }
+
+    pub fn check_peer(&self, peer_id: Option<PeerId>) -> AppResult<()> {
+        if let Some(reason) = peer_id.and_then(|id| self.incompatible_peers.get(&id)) {
+            return Err(format!(
+                "Ignoring message from incompatible peer {}: {}",
+                peer_id.unwrap(),
+                reason
+            )
+            .into());
+        }
+        Ok(())
     }
 
     pub fn send_own_team(&mut self, world: &World) -> AppResult<MessageId> {
@@ -165,8 +291,129 @@ impl NetworkHandler {
 
     fn send_game(&mut self, world: &World, game_id: GameId) -> AppResult<MessageId> {
         let network_game = NetworkGame::from_game_id(&world, game_id)?;
-        let serialized_game = serde_json::to_string(&network_game)?.as_bytes().to_vec();
-        self._send(serialized_game, SubscriptionTopic::GAME)
+        self._send(Envelope::from_data(MessageType::Game, &network_game)?)
+    }
+
+    /// Sends the checksums of the latest actions of the own team network game,
+    /// once enough actions have been played or the game has ended.
+    pub fn send_game_checksum(&mut self, world: &World) -> AppResult<Option<MessageId>> {
+        self.sent_checksums
+            .retain(|game_id, _| world.get_network_game(*game_id).is_some());
+
+        // The final checksum is sent once the game has been moved to the
+        // ended network games, since the current game is cleared by then.
+        let own_team_id = world.own_team_id;
+        let mut game_ids = world
+            .ended_network_games
+            .values()
+            .filter(|(game, _)| {
+                game.home_team_in_game.team_id == own_team_id
+                    || game.away_team_in_game.team_id == own_team_id
+            })
+            .map(|(game, _)| game.id)
+            .collect::<Vec<GameId>>();
+        if let Some(game_id) = world.get_own_team()?.current_game {
+            game_ids.push(game_id);
+        }
+
+        let mut message_id = None;
+        for game_id in game_ids {
+            let Some(game) = world.get_network_game(game_id) else {
+                continue;
+            };
+            if game.home_team_in_game.peer_id.is_none()
+                || game.away_team_in_game.peer_id.is_none()
+            {
+                continue;
+            }
+
+            let from_action = *self.sent_checksums.get(&game_id).unwrap_or(&0);
+            // The final checksum has already been sent.
+            if from_action == usize::MAX {
+                continue;
+            }
+            let has_ended = game.timer.has_ended();
+            if !has_ended && game.action_results.len() < from_action + GAME_CHECKSUM_INTERVAL {
+                continue;
+            }
+
+            let checksum = GameChecksum::new(game, from_action);
+            let next_action = if has_ended {
+                usize::MAX
+            } else {
+                checksum.to_action()
+            };
+            message_id =
+                Some(self._send(Envelope::from_data(MessageType::GameChecksum, &checksum)?)?);
+            self.sent_checksums.insert(game_id, next_action);
+        }
+        Ok(message_id)
+    }
+
+    pub fn add_game_checksum(&mut self, peer_id: PeerId, checksum: GameChecksum) {
+        if !self.desynced_games.contains(&checksum.game_id) {
+            self.pending_checksums.push((peer_id, checksum));
+        }
+    }
+
+    /// Compares the received checksums with our own simulation of the games.
+    /// Checksums for actions we have not simulated yet are kept for later.
+    pub fn verify_game_checksums(&mut self, world: &World) -> Vec<GameDesync> {
+        let mut desyncs = vec![];
+        let mut pending = vec![];
+        for (peer_id, checksum) in self.pending_checksums.drain(..) {
+            if self.desynced_games.contains(&checksum.game_id) {
+                continue;
+            }
+            let Some(game) = world.get_network_game(checksum.game_id) else {
+                continue;
+            };
+            if game.action_results.len() < checksum.to_action()
+                || (checksum.ended && !game.timer.has_ended())
+            {
+                pending.push((peer_id, checksum));
+                continue;
+            }
+
+            let local_checksums = game.action_checksums(checksum.from_action);
+            let diverging_action = checksum
+                .action_checksums
+                .iter()
+                .zip(local_checksums.iter())
+                .position(|(remote, local)| remote != local)
+                .or_else(|| {
+                    // Both games ended, so they must have the same number of actions.
+                    if checksum.ended && local_checksums.len() != checksum.action_checksums.len() {
+                        Some(local_checksums.len().min(checksum.action_checksums.len()))
+                    } else {
+                        None
+                    }
+                });
+
+            let desync = GameDesync {
+                game_id: game.id,
+                peer_id,
+                action_index: diverging_action.map(|idx| checksum.from_action + idx),
+                local_checksum: diverging_action.and_then(|idx| local_checksums.get(idx).copied()),
+                remote_checksum: diverging_action
+                    .and_then(|idx| checksum.action_checksums.get(idx).copied()),
+                local_score: game.get_score(),
+                remote_score: checksum.score,
+                local_action: diverging_action.and_then(|idx| {
+                    game.action_results
+                        .get(checksum.from_action + idx)
+                        .cloned()
+                }),
+            };
+            if diverging_action.is_some()
+                || (checksum.ended && desync.local_score != desync.remote_score)
+            {
+                self.desynced_games.insert(game.id);
+                desyncs.push(desync);
+            }
+        }
+        self.pending_checksums = pending;
+        desyncs
     }
 
     fn send_team(&mut self, world: &World, team_id: TeamId) -> AppResult<MessageId> {
@@ -175,13 +422,76 @@ impl NetworkHandler {
         // This means that the team can be challenged online and it will not be stored.
         network_team.set_peer_id(self.swarm.local_peer_id().clone());
 
-        let serialized_team = serde_json::to_string(&network_team)?.as_bytes().to_vec();
-        self._send(serialized_team, SubscriptionTopic::TEAM)
+        self._send(Envelope::from_data(MessageType::Team, &network_team)?)
     }
 
-    pub fn send_challenge(&mut self, challenge: &Challenge) -> AppResult<MessageId> {
-        let serialized_challenge = serde_json::to_vec(challenge)?;
-        self._send(serialized_challenge, SubscriptionTopic::CHALLENGE)
+    fn request_challenge(&mut self, peer_id: PeerId, challenge: Challenge, attempt: u8) {
+        let request_id = self
+            .swarm
+            .behaviour_mut()
+            .challenge
+            .send_request(&peer_id, challenge.clone());
+        self.outbound_challenges
+            .insert(request_id, (peer_id, challenge, attempt));
+    }
+
+    pub fn respond_to_challenge(
+        &mut self,
+        request_id: RequestId,
+        challenge: Challenge,
+    ) -> AppResult<()> {
+        let channel = self
+            .response_channels
+            .remove(&request_id)
+            .ok_or("Challenge request already answered")?;
+        self.swarm
+            .behaviour_mut()
+            .challenge
+            .send_response(channel, challenge)
+            .map_err(|_| "Failed to send challenge response")?;
+        Ok(())
+    }
+
+    /// Keeps a challenge request open until the challenge is accepted or declined.
+    pub fn hold_challenge_request(&mut self, peer_id: PeerId, request_id: RequestId) {
+        self.challenge_requests.insert(peer_id, request_id);
+    }
+
+    /// Answers the challenge request held for the peer, if it has not timed out yet.
+    fn answer_challenge_request(&mut self, peer_id: PeerId, challenge: Challenge) -> AppResult<()> {
+        match self.challenge_requests.remove(&peer_id) {
+            Some(request_id) => self.respond_to_challenge(request_id, challenge),
+            None => Ok(()),
+        }
+    }
+
+    pub fn get_challenge(&self, peer_id: &PeerId) -> Option<&Challenge> {
+        self.challenges.get(peer_id)
+    }
+
+    pub fn remove_challenge(&mut self, peer_id: &PeerId) -> Option<Challenge> {
+        self.challenges.remove(peer_id)
+    }
+
+    /// Drops the challenges that have not been answered in time.
+    pub fn cleanup_challenges(&mut self, now: Tick) -> Vec<Challenge> {
+        let expired = self
+            .challenges
+            .iter()
+            .filter(|(_, challenge)| challenge.is_expired(now))
+            .map(|(peer_id, _)| *peer_id)
+            .collect::<Vec<PeerId>>();
+        let mut challenges = vec![];
+        for peer_id in expired {
+            let Some(mut challenge) = self.challenges.remove(&peer_id) else {
+                continue;
+            };
+            challenges.push(challenge.clone());
+            challenge.fail("Challenge expired".to_string());
+            // The challenger has already given up if the request timed out.
+            let _ = self.answer_challenge_request(peer_id, challenge);
+        }
+        challenges
     }
 
     pub fn can_handle_challenge(world: &World) -> AppResult<()> {
@@ -199,101 +509,216 @@ impl NetworkHandler {
     }
 
     pub fn send_new_challenge(&mut self, world: &World, peer_id: PeerId) -> AppResult<()> {
+        self.check_peer(Some(peer_id))?;
         self.send_own_team(world)?;
 
         let mut challenge = Challenge::new(self.swarm.local_peer_id().clone(), peer_id);
         let mut home_team_in_game =
-            TeamInGame::from_team_id(world.own_team_id, &world.teams, &world.players)
-                .ok_or("Cannot generate team in game")?;
+            TeamInGame::from_team_id(world.own_team_id, &world.teams, &world.players)?;
         home_team_in_game.peer_id = Some(self.swarm.local_peer_id().clone());
         challenge.home_team = Some(home_team_in_game);
 
-        self.send_challenge(&challenge)?;
+        self.challenges.insert(peer_id, challenge.clone());
+        self.request_challenge(peer_id, challenge, 1);
         Ok(())
     }
 
     pub fn accept_challenge(&mut self, world: &World, challenge: Challenge) -> AppResult<()> {
+        let mut challenge = challenge.clone();
         let mut handle_syn = || -> AppResult<()> {
             Self::can_handle_challenge(world)?;
 
-            let away_team = world.get_own_team()?;
-            if away_team.current_game.is_some() {
-                return Err("Cannot accept challenge, already in a game".into());
-            }
-
-            let try_away_team_in_game =
-                TeamInGame::from_team_id(world.own_team_id, &world.teams, &world.players);
-
-            if try_away_team_in_game.is_none() {
-                return Err("Cannot generate team in game for challenge")?;
-            }
-
-            let mut away_team_in_game = try_away_team_in_game.unwrap();
+            let mut away_team_in_game =
+                TeamInGame::from_team_id(world.own_team_id, &world.teams, &world.players)?;
             away_team_in_game.peer_id = Some(self.swarm.local_peer_id().clone());
 
-            let mut challenge = challenge.clone();
+            challenge.transition(ChallengeState::SynAck)?;
             challenge.away_team = Some(away_team_in_game);
-            challenge.state = ChallengeState::SynAck;
-            self.send_challenge(&challenge)?;
             Ok(())
         };
 
         if let Err(err) = handle_syn() {
-            let mut challenge = Challenge::new(challenge.home_peer_id, challenge.away_peer_id);
-            challenge.state = ChallengeState::Failed;
-            challenge.error_message = Some(err.to_string());
-            self.send_challenge(&challenge)?;
+            self.reject_challenge(challenge, err.to_string())?;
             return Err(err.to_string())?;
         }
+
+        self.answer_challenge_request(challenge.home_peer_id, challenge.clone())?;
+        self.challenges
+            .insert(challenge.home_peer_id, challenge.clone());
+        self.request_challenge(challenge.home_peer_id, challenge, 1);
         Ok(())
     }
 
     pub fn decline_challenge(&mut self, challenge: Challenge) -> AppResult<()> {
-        let mut challenge = challenge.clone();
-        challenge.state = ChallengeState::Failed;
-        challenge.error_message = Some("Declined".to_string());
-        self.send_challenge(&challenge)?;
+        self.reject_challenge(challenge, "Declined".to_string())
+    }
+
+    /// Drops a received challenge and answers the challenger's request with the failure.
+    fn reject_challenge(&mut self, challenge: Challenge, error_message: String) -> AppResult<()> {
+        let mut challenge = challenge;
         self.challenges.remove(&challenge.home_peer_id);
-        Ok(())
+        challenge.fail(error_message);
+        self.answer_challenge_request(challenge.home_peer_id, challenge)
+    }
+
+    /// Drops the challenge and lets the challenger know why.
+    pub fn fail_challenge(&mut self, challenge: Challenge, error_message: String) {
+        let mut challenge = challenge;
+        self.challenges.remove(&challenge.home_peer_id);
+        challenge.fail(error_message);
+        self.request_challenge(challenge.home_peer_id, challenge, 1);
+    }
+
+    fn handle_challenge_events(
+        &mut self,
+        event: request_response::Event<Challenge, Challenge>,
+    ) -> Option<NetworkCallbackPreset> {
+        match event {
+            request_response::Event::Message {
+                peer,
+                message:
+                    request_response::Message::Request {
+                        request_id,
+                        request,
+                        channel,
+                    },
+            } => {
+                self.response_channels.insert(request_id, channel);
+                Some(NetworkCallbackPreset::HandleChallengeRequest {
+                    peer_id: peer,
+                    request_id,
+                    challenge: request,
+                })
+            }
+            request_response::Event::Message {
+                peer,
+                message:
+                    request_response::Message::Response {
+                        request_id,
+                        response,
+                    },
+            } => {
+                self.outbound_challenges.remove(&request_id);
+                Some(NetworkCallbackPreset::HandleChallengeResponse {
+                    peer_id: peer,
+                    challenge: response,
+                })
+            }
+            request_response::Event::OutboundFailure {
+                peer,
+                request_id,
+                error,
+            } => {
+                let (peer_id, challenge, attempt) = self.outbound_challenges.remove(&request_id)?;
+                // Timed out challenges were not answered before expiring, retrying is pointless.
+                if attempt < CHALLENGE_MAX_ATTEMPTS
+                    && error != OutboundFailure::UnsupportedProtocols
+                    && error != OutboundFailure::Timeout
+                {
+                    self.request_challenge(peer_id, challenge, attempt + 1);
+                    return Some(NetworkCallbackPreset::PushSwarmPanelLog {
+                        timestamp: Tick::now(),
+                        text: format!(
+                            "Challenge request to {} failed ({}), retrying",
+                            peer, error
+                        ),
+                    });
+                }
+                self.challenges.remove(&peer_id);
+                Some(NetworkCallbackPreset::HandleChallengeFailure {
+                    peer_id,
+                    challenge,
+                    error: error.to_string(),
+                })
+            }
+            request_response::Event::InboundFailure {
+                peer,
+                request_id,
+                error,
+            } => {
+                self.response_channels.remove(&request_id);
+                self.challenge_requests.retain(|_, id| *id != request_id);
+                Some(NetworkCallbackPreset::PushSwarmPanelLog {
+                    timestamp: Tick::now(),
+                    text: format!("Challenge request from {} failed: {}", peer, error),
+                })
+            }
+            request_response::Event::ResponseSent { .. } => None,
+        }
+    }
+
+    fn handle_mdns_events(&mut self, event: mdns::Event) -> Option<NetworkCallbackPreset> {
+        match event {
+            mdns::Event::Discovered(peers) => {
+                let mut addresses: HashMap<PeerId, Vec<Multiaddr>> = HashMap::new();
+                for (peer_id, address) in peers {
+                    if !self.swarm.is_connected(&peer_id) {
+                        addresses.entry(peer_id).or_default().push(address);
+                    }
+                }
+
+                let mut dialed = vec![];
+                for (peer_id, addresses) in addresses {
+                    let opts = DialOpts::peer_id(peer_id).addresses(addresses).build();
+                    if self.swarm.dial(opts).is_ok() {
+                        dialed.push(peer_id.to_string());
+                    }
+                }
+                if dialed.is_empty() {
+                    return None;
+                }
+                Some(NetworkCallbackPreset::PushSwarmPanelLog {
+                    timestamp: Tick::now(),
+                    text: format!("Found peers on the local network: {}", dialed.join(", ")),
+                })
+            }
+            mdns::Event::Expired(_) => None,
+        }
     }
 
     pub fn handle_network_events(
         &mut self,
-        event: SwarmEvent<gossipsub::Event, Void>,
+        event: NetworkEvent,
     ) -> Option<NetworkCallbackPreset> {
         // self.swarm.connected_peers()
         match event {
             SwarmEvent::NewListenAddr { address, .. } => {
                 Some(NetworkCallbackPreset::BindAddress { address })
             }
-            SwarmEvent::Behaviour(gossipsub::Event::Message {
+            SwarmEvent::Behaviour(RebelsBehaviourEvent::Gossipsub(gossipsub::Event::Message {
                 propagation_source: _,
                 message_id: _,
                 message,
-            }) => match message.topic.clone() {
+            })) => match message.topic.clone() {
                 x if x == IdentTopic::new(SubscriptionTopic::TEAM).hash() => {
                     Some(NetworkCallbackPreset::HandleTeamTopic { message })
                 }
                 x if x == IdentTopic::new(SubscriptionTopic::MSG).hash() => {
                     Some(NetworkCallbackPreset::HandleMsgTopic { message })
                 }
-                x if x == IdentTopic::new(SubscriptionTopic::CHALLENGE).hash() => {
-                    Some(NetworkCallbackPreset::HandleChallengeTopic { message })
-                }
                 x if x == IdentTopic::new(SubscriptionTopic::GAME).hash() => {
                     Some(NetworkCallbackPreset::HandleGameTopic { message })
                 }
                 x if x == IdentTopic::new(SubscriptionTopic::SEED_INFO).hash() => {
                     Some(NetworkCallbackPreset::HandleSeedTopic { message })
                 }
+                x if x == IdentTopic::new(SubscriptionTopic::HANDSHAKE).hash() => {
+                    Some(NetworkCallbackPreset::HandleHandshakeTopic { message })
+                }
                 _ => None,
             },
-            SwarmEvent::Behaviour(gossipsub::Event::Subscribed { peer_id, topic }) => {
-                Some(NetworkCallbackPreset::Subscribe { peer_id, topic })
+            SwarmEvent::Behaviour(RebelsBehaviourEvent::Gossipsub(
+                gossipsub::Event::Subscribed { peer_id, topic },
+            )) => Some(NetworkCallbackPreset::Subscribe { peer_id, topic }),
+
+            SwarmEvent::Behaviour(RebelsBehaviourEvent::Gossipsub(
+                gossipsub::Event::Unsubscribed { peer_id, topic },
+            )) => Some(NetworkCallbackPreset::Unsubscribe { peer_id, topic }),
+            SwarmEvent::Behaviour(RebelsBehaviourEvent::Challenge(event)) => {
+                self.handle_challenge_events(event)
             }
-
-            SwarmEvent::Behaviour(gossipsub::Event::Unsubscribed { peer_id, topic }) => {
-                Some(NetworkCallbackPreset::Unsubscribe { peer_id, topic })
+            SwarmEvent::Behaviour(RebelsBehaviourEvent::Mdns(event)) => {
+                self.handle_mdns_events(event)
             }
             SwarmEvent::ExpiredListenAddr {
                 listener_id: _,
@@ -318,39 +743,104 @@ impl NetworkHandler {
 
 #[cfg(test)]
 mod tests {
+    use super::NetworkHandler;
     use crate::{
-        network::types::NetworkTeam,
-        types::{SystemTimeTick, Tick},
-        world::world::World,
+        network::{
+            config::NetworkConfig,
+            constants::CHALLENGE_EXPIRY,
+            envelope::{Envelope, MessageType},
+            types::{Challenge, ChallengeState, GameChecksum, NetworkTeam},
+        },
+        test_utils::test_world_and_game,
+        world::constants::{TickInterval, ENDED_NETWORK_GAME_EXPIRY},
     };
-    use rand::SeedableRng;
-    use rand_chacha::ChaCha8Rng;
+    use libp2p::{identity, PeerId};
 
     #[test]
     fn test_send_own_team() {
-        let mut world = World::new(None);
-        let rng = &mut ChaCha8Rng::from_entropy();
-        let home_planet = world.planets.keys().next().unwrap().clone();
-        let team_name = "Testen".to_string();
-        let ship_name = "Tosten".to_string();
-        let own_team_id = world.generate_random_team(rng, home_planet, team_name, ship_name);
-        let network_team = NetworkTeam::from_team_id(&world, &own_team_id.unwrap()).unwrap();
-
-        let timestamp = Tick::now().as_secs().to_le_bytes().to_vec();
-        let serialized_team = serde_json::to_string(&network_team)
-            .unwrap()
-            .as_bytes()
-            .to_vec();
-        let data = [timestamp.clone(), serialized_team].concat();
-
-        let deserialize_timestamp = u128::from_le_bytes(data[..16].try_into().unwrap());
-        let old_timestamp: u128 = u128::from_le_bytes(timestamp.as_slice().try_into().unwrap());
-        assert!(old_timestamp == deserialize_timestamp);
-        let try_deserialize_team = serde_json::from_slice::<NetworkTeam>(&data[16..]);
-        assert_eq!(try_deserialize_team.is_ok(), true);
-        let deserialized_team = try_deserialize_team.unwrap();
+        let (mut world, game) = test_world_and_game();
+        world.own_team_id = game.home_team_in_game.team_id;
+        let network_team = NetworkTeam::from_team_id(&world, &world.own_team_id).unwrap();
+
+        // The own team is sent in a team envelope, as in send_own_team.
+        let envelope = Envelope::from_data(MessageType::Team, &network_team).unwrap();
+        let decoded = Envelope::decode(&envelope.encode().unwrap()).unwrap();
+        assert_eq!(decoded.message_type, MessageType::Team);
+        let deserialized_team = decoded.data::<NetworkTeam>().unwrap();
         assert_eq!(deserialized_team.team, network_team.team);
-        assert_eq!(deserialized_team.players.len(), network_team.players.len());
-        assert_eq!(deserialized_team.players[0], network_team.players[0]);
+        // Players with a team never compare equal, so compare the serialized players.
+        assert_eq!(
+            serde_json::to_value(&deserialized_team.players).unwrap(),
+            serde_json::to_value(&network_team.players).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_challenge_state() {
+        let mut challenge = Challenge::new(PeerId::random(), PeerId::random());
+        assert!(challenge.transition(ChallengeState::Ack).is_err());
+        assert!(challenge.transition(ChallengeState::SynAck).is_ok());
+        assert!(challenge.transition(ChallengeState::SynAck).is_err());
+        assert!(challenge.transition(ChallengeState::Ack).is_ok());
+        assert!(challenge.transition(ChallengeState::Failed).is_err());
+
+        let challenge = Challenge::new(PeerId::random(), PeerId::random());
+        assert!(!challenge.is_expired(challenge.created_at + CHALLENGE_EXPIRY));
+        assert!(challenge.is_expired(challenge.created_at + CHALLENGE_EXPIRY + 1));
+
+        // The challenge travels as json over the request-response protocol.
+        let json = serde_json::to_vec(&challenge).unwrap();
+        assert_eq!(serde_json::from_slice::<Challenge>(&json).unwrap(), challenge);
+    }
+
+    #[tokio::test]
+    async fn test_final_checksum_after_game_cleanup() {
+        let (mut world, mut game) = test_world_and_game();
+        world.own_team_id = game.home_team_in_game.team_id;
+        game.home_team_in_game.peer_id = Some(PeerId::random());
+        game.away_team_in_game.peer_id = Some(PeerId::random());
+        for team_id in [game.home_team_in_game.team_id, game.away_team_in_game.team_id] {
+            let mut team = world.get_team_or_err(team_id).unwrap().clone();
+            team.current_game = Some(game.id);
+            world.teams.insert(team.id, team);
+        }
+        let game_id = game.id;
+        world.games.insert(game_id, game.clone());
+
+        // The peer simulates the same game on its side.
+        while !game.timer.has_ended() {
+            game.tick();
+        }
+
+        let mut now = world.last_tick_short_interval;
+        while world.games.contains_key(&game_id) {
+            now += TickInterval::SHORT;
+            world.handle_tick_events(now, true).unwrap();
+        }
+        assert!(world.get_own_team().unwrap().current_game.is_none());
+        assert!(world.get_network_game(game_id).unwrap().timer.has_ended());
+
+        // The final checksum of the peer can still be verified after cleanup.
+        let config = NetworkConfig {
+            listen_addresses: vec!["/ip4/127.0.0.1/tcp/0".parse().unwrap()],
+            bootstrap_addresses: vec![],
+            enable_mdns: false,
+        };
+        let mut handler =
+            NetworkHandler::new(identity::Keypair::generate_ed25519(), &config).unwrap();
+        let peer_id = PeerId::random();
+        handler.add_game_checksum(peer_id, GameChecksum::new(&game, 0));
+        assert!(handler.verify_game_checksums(&world).is_empty());
+        assert!(handler.pending_checksums.is_empty());
+
+        let mut checksum = GameChecksum::new(&game, 0);
+        checksum.score.0 += 1;
+        handler.add_game_checksum(peer_id, checksum);
+        assert_eq!(handler.verify_game_checksums(&world).len(), 1);
+
+        world
+            .handle_tick_events(now + ENDED_NETWORK_GAME_EXPIRY, true)
+            .unwrap();
+        assert!(world.get_network_game(game_id).is_none());
     }
 }
@@ -0,0 +1,572 @@
+//! Runs several peers on loopback, each with its own `App`, so that the network
+//! callbacks can be exercised end to end without starting multiple terminals.
+
+use super::config::NetworkConfig;
+use super::handler::NetworkHandler;
+use crate::app::App;
+use crate::relayer::{Relayer, RelayerState};
+use crate::types::{AppResult, GameId, TeamId};
+use crate::world::constants::{TickInterval, DEFAULT_PLANET_ID};
+use futures::future::{select_all, FutureExt};
+use futures::StreamExt;
+use libp2p::{identity, Multiaddr, PeerId};
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use std::time::Duration;
+use tokio::time::{sleep_until, Instant};
+
+const LOOPBACK_ADDRESS: &str = "/ip4/127.0.0.1/tcp/0";
+// Dirty teams are sent on this interval, like the app does on its tick.
+const TICK_INTERVAL: Duration = Duration::from_millis(100);
+
+pub struct TestNetwork {
+    pub peers: Vec<App>,
+    pub relayer: Option<Relayer>,
+}
+
+impl TestNetwork {
+    /// Starts the peers on random ports. Every peer dials the first one, which acts as the seed.
+    pub async fn new(size: usize) -> AppResult<Self> {
+        Self::start(size, false).await
+    }
+
+    /// Like `new`, but every peer also dials a relayer.
+    pub async fn with_relayer(size: usize) -> AppResult<Self> {
+        Self::start(size, true).await
+    }
+
+    async fn start(size: usize, with_relayer: bool) -> AppResult<Self> {
+        let mut network = Self {
+            peers: vec![],
+            relayer: None,
+        };
+        let mut config = NetworkConfig {
+            listen_addresses: vec![LOOPBACK_ADDRESS.parse()?],
+            bootstrap_addresses: vec![],
+            enable_mdns: false,
+        };
+        if with_relayer {
+            network.relayer = Some(Relayer::with_state(
+                RelayerState::default(),
+                identity::Keypair::generate_ed25519(),
+                &config,
+            )?);
+            network
+                .run_until_network(
+                    |network| network.relayer_address().is_some(),
+                    Duration::from_secs(5),
+                )
+                .await?;
+            config.bootstrap_addresses = network.relayer_address().into_iter().collect();
+        }
+        for idx in 0..size {
+            let mut app = Self::new_app(idx as u64)?;
+            let network_handler =
+                NetworkHandler::new(identity::Keypair::generate_ed25519(), &config)?;
+            app.network_handler = Some(network_handler);
+            network.peers.push(app);
+            network
+                .run_until(
+                    |peers| peers[idx].network_handler.as_ref().unwrap().address != Multiaddr::empty(),
+                    Duration::from_secs(5),
+                )
+                .await?;
+            if idx == 0 {
+                config.bootstrap_addresses.push(network.address(0));
+            }
+        }
+        Ok(network)
+    }
+
+    fn new_app(seed: u64) -> AppResult<App> {
+        let mut app = App::new(
+            Some(seed),
+            false,
+            true,
+            false,
+            false,
+            NetworkConfig::default(),
+            false,
+        );
+        let rng = &mut ChaCha8Rng::seed_from_u64(seed);
+        let team_id = app.world.generate_random_team(
+            rng,
+            DEFAULT_PLANET_ID.clone(),
+            format!("Peer {}", seed),
+            format!("Ship {}", seed),
+        )?;
+        app.world.own_team_id = team_id;
+        app.world.dirty_network = true;
+        Ok(app)
+    }
+
+    pub fn peer_id(&self, idx: usize) -> PeerId {
+        *self.peers[idx]
+            .network_handler
+            .as_ref()
+            .unwrap()
+            .swarm
+            .local_peer_id()
+    }
+
+    pub fn address(&self, idx: usize) -> Multiaddr {
+        self.peers[idx].network_handler.as_ref().unwrap().address.clone()
+    }
+
+    pub fn own_team_id(&self, idx: usize) -> TeamId {
+        self.peers[idx].world.own_team_id
+    }
+
+    pub fn relayer_address(&self) -> Option<Multiaddr> {
+        self.relayer
+            .as_ref()
+            .and_then(|relayer| relayer.network_handler.swarm.listeners().next().cloned())
+    }
+
+    /// Dispatches swarm events to the peers until the condition holds.
+    pub async fn run_until(
+        &mut self,
+        condition: impl Fn(&[App]) -> bool,
+        timeout: Duration,
+    ) -> AppResult<()> {
+        self.run_until_network(|network| condition(&network.peers), timeout)
+            .await
+    }
+
+    /// Dispatches swarm events to the peers and the relayer until the condition holds.
+    pub async fn run_until_network(
+        &mut self,
+        condition: impl Fn(&Self) -> bool,
+        timeout: Duration,
+    ) -> AppResult<()> {
+        let deadline = Instant::now() + timeout;
+        let mut next_tick = Instant::now();
+        while !condition(self) {
+            if Instant::now() > deadline {
+                return Err("Timed out waiting for the network".into());
+            }
+
+            // The relayer events are dispatched with the index after the last peer.
+            let relayer_idx = self.peers.len();
+            let next_events = self
+                .peers
+                .iter_mut()
+                .map(|app| &mut app.network_handler.as_mut().unwrap().swarm)
+                .chain(
+                    self.relayer
+                        .iter_mut()
+                        .map(|relayer| &mut relayer.network_handler.swarm),
+                )
+                .enumerate()
+                .map(|(idx, swarm)| {
+                    swarm
+                        .select_next_some()
+                        .map(move |event| (idx, event))
+                        .boxed()
+                })
+                .collect::<Vec<_>>();
+
+            let event = tokio::select! {
+                ((idx, event), _, _) = select_all(next_events) => Some((idx, event)),
+                _ = sleep_until(next_tick) => None,
+            };
+
+            match event {
+                Some((idx, event)) if idx == relayer_idx => {
+                    // The relayer only logs the messages it rejects.
+                    let _ = self.relayer.as_mut().unwrap().handle_network_events(event);
+                }
+                Some((idx, event)) => self.peers[idx].handle_network_events(event)?,
+                None => {}
+            }
+            if Instant::now() >= next_tick {
+                next_tick = Instant::now() + TICK_INTERVAL;
+                for app in self.peers.iter_mut() {
+                    app.verify_network_games();
+                    app.send_own_team_if_dirty();
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Waits until every peer knows the teams of all the others.
+    pub async fn exchange_teams(&mut self) -> AppResult<()> {
+        let team_ids = (0..self.peers.len())
+            .map(|idx| self.own_team_id(idx))
+            .collect::<Vec<TeamId>>();
+        self.run_until(
+            |peers| {
+                peers.iter().all(|app| {
+                    team_ids
+                        .iter()
+                        .all(|team_id| app.world.get_team(*team_id).is_some())
+                })
+            },
+            Duration::from_secs(20),
+        )
+        .await
+    }
+
+    /// Plays a challenge between two peers and returns the game once both have started it.
+    pub async fn start_challenge(&mut self, home_idx: usize, away_idx: usize) -> AppResult<GameId> {
+        let home_peer_id = self.peer_id(home_idx);
+        let away_peer_id = self.peer_id(away_idx);
+        let home = &mut self.peers[home_idx];
+        home.network_handler
+            .as_mut()
+            .unwrap()
+            .send_new_challenge(&home.world, away_peer_id)?;
+        self.run_until(
+            |peers| {
+                peers[away_idx]
+                    .network_handler
+                    .as_ref()
+                    .unwrap()
+                    .get_challenge(&home_peer_id)
+                    .is_some()
+            },
+            Duration::from_secs(10),
+        )
+        .await?;
+
+        let away = &mut self.peers[away_idx];
+        let challenge = away
+            .network_handler
+            .as_ref()
+            .unwrap()
+            .get_challenge(&home_peer_id)
+            .ok_or("Challenge not found")?
+            .clone();
+        away.network_handler
+            .as_mut()
+            .unwrap()
+            .accept_challenge(&away.world, challenge)?;
+        self.run_until(
+            |peers| {
+                [home_idx, away_idx].iter().all(|&idx| {
+                    peers[idx]
+                        .world
+                        .get_own_team()
+                        .map(|team| team.current_game.is_some())
+                        .unwrap_or_default()
+                })
+            },
+            Duration::from_secs(10),
+        )
+        .await?;
+
+        self.peers[home_idx]
+            .world
+            .get_own_team()?
+            .current_game
+            .ok_or("Game not found".into())
+    }
+
+    /// Simulates the game to its end on the given peer, as its world ticks would.
+    pub fn finish_game(&mut self, idx: usize, game_id: GameId) -> AppResult<()> {
+        let world = &mut self.peers[idx].world;
+        let mut now = world.last_tick_short_interval;
+        while world.get_game(game_id).is_some() {
+            now += TickInterval::SHORT;
+            world.handle_tick_events(now, true)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TestNetwork;
+    use crate::network::network_callback::NetworkCallbackPreset;
+    use crate::network::types::ChallengeState;
+    use crate::types::{AppResult, GameId, IdSystem, SystemTimeTick, Tick, MINUTES};
+    use crate::world::skill::MAX_SKILL;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_challenge_between_peers() -> AppResult<()> {
+        let mut network = TestNetwork::new(3).await?;
+        network.exchange_teams().await?;
+
+        let home_peer_id = network.peer_id(0);
+        let away_peer_id = network.peer_id(1);
+        let home = &mut network.peers[0];
+        home.network_handler
+            .as_mut()
+            .unwrap()
+            .send_new_challenge(&home.world, away_peer_id)?;
+
+        // SYN: the challenged peer receives the challenge.
+        network
+            .run_until(
+                |peers| {
+                    peers[1]
+                        .network_handler
+                        .as_ref()
+                        .unwrap()
+                        .get_challenge(&home_peer_id)
+                        .is_some()
+                },
+                Duration::from_secs(10),
+            )
+            .await?;
+
+        // SYN_ACK: the challenged peer accepts, the challenger answers with ACK.
+        let away = &mut network.peers[1];
+        let challenge = away
+            .network_handler
+            .as_ref()
+            .unwrap()
+            .get_challenge(&home_peer_id)
+            .unwrap()
+            .clone();
+        away.network_handler
+            .as_mut()
+            .unwrap()
+            .accept_challenge(&away.world, challenge)?;
+
+        network
+            .run_until(
+                |peers| {
+                    peers[..2].iter().all(|app| {
+                        app.world
+                            .get_own_team()
+                            .map(|team| team.current_game.is_some())
+                            .unwrap_or_default()
+                    })
+                },
+                Duration::from_secs(10),
+            )
+            .await?;
+
+        let game_id = network.peers[0]
+            .world
+            .get_own_team()?
+            .current_game
+            .unwrap();
+        let home_game = network.peers[0].world.get_game_or_err(game_id)?.clone();
+        let away_game = network.peers[1].world.get_game_or_err(game_id)?.clone();
+        // Players with a team never compare equal, so compare the serialized teams.
+        assert_eq!(
+            serde_json::to_value(&home_game.home_team_in_game)?,
+            serde_json::to_value(&away_game.home_team_in_game)?
+        );
+        assert_eq!(
+            serde_json::to_value(&home_game.away_team_in_game)?,
+            serde_json::to_value(&away_game.away_team_in_game)?
+        );
+        assert_eq!(home_game.starting_at, away_game.starting_at);
+        assert_eq!(home_game.location, away_game.location);
+        assert_eq!(
+            home_game.home_team_in_game.team_id,
+            network.own_team_id(0)
+        );
+        assert_eq!(
+            away_game.away_team_in_game.team_id,
+            network.own_team_id(1)
+        );
+
+        // Other peers learn about the game from the challenger.
+        network
+            .run_until(
+                |peers| peers[2].world.games.contains_key(&game_id),
+                Duration::from_secs(10),
+            )
+            .await?;
+
+        // Both peers simulate the same game.
+        let mut home_game = home_game;
+        let mut away_game = away_game;
+        for _ in 0..200 {
+            home_game.tick();
+            away_game.tick();
+        }
+        assert_eq!(home_game.action_checksums(0), away_game.action_checksums(0));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_declined_challenge() -> AppResult<()> {
+        let mut network = TestNetwork::new(2).await?;
+        network.exchange_teams().await?;
+
+        let home_peer_id = network.peer_id(0);
+        let away_peer_id = network.peer_id(1);
+        let home = &mut network.peers[0];
+        home.network_handler
+            .as_mut()
+            .unwrap()
+            .send_new_challenge(&home.world, away_peer_id)?;
+        network
+            .run_until(
+                |peers| {
+                    peers[1]
+                        .network_handler
+                        .as_ref()
+                        .unwrap()
+                        .get_challenge(&home_peer_id)
+                        .is_some()
+                },
+                Duration::from_secs(10),
+            )
+            .await?;
+
+        // The decline answers the original challenge request.
+        let away = &mut network.peers[1];
+        let challenge = away
+            .network_handler
+            .as_ref()
+            .unwrap()
+            .get_challenge(&home_peer_id)
+            .unwrap()
+            .clone();
+        away.network_handler
+            .as_mut()
+            .unwrap()
+            .decline_challenge(challenge)?;
+        network
+            .run_until(
+                |peers| {
+                    peers[0]
+                        .network_handler
+                        .as_ref()
+                        .unwrap()
+                        .get_challenge(&away_peer_id)
+                        .is_none()
+                },
+                Duration::from_secs(10),
+            )
+            .await?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_relayer_records_finished_game() -> AppResult<()> {
+        let mut network = TestNetwork::with_relayer(2).await?;
+        network.exchange_teams().await?;
+        let game_id = network.start_challenge(0, 1).await?;
+        network
+            .run_until_network(
+                |network| {
+                    network
+                        .relayer
+                        .as_ref()
+                        .unwrap()
+                        .state
+                        .game_record(game_id)
+                        .is_some()
+                },
+                Duration::from_secs(10),
+            )
+            .await?;
+
+        // The result is recorded once both peers sent their final checksum.
+        network.finish_game(0, game_id)?;
+        network.finish_game(1, game_id)?;
+        network
+            .run_until_network(
+                |network| {
+                    network
+                        .relayer
+                        .as_ref()
+                        .unwrap()
+                        .state
+                        .game_record(game_id)
+                        .and_then(|record| record.score)
+                        .is_some()
+                },
+                Duration::from_secs(20),
+            )
+            .await?;
+
+        let summary = network.peers[0].world.past_games.get(&game_id).unwrap();
+        let record = network
+            .relayer
+            .as_ref()
+            .unwrap()
+            .state
+            .game_record(game_id)
+            .unwrap();
+        assert_eq!(record.score, Some((summary.home_score, summary.away_score)));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_ack_keeps_accepted_away_team() -> AppResult<()> {
+        let mut network = TestNetwork::new(2).await?;
+        network.exchange_teams().await?;
+
+        let home_peer_id = network.peer_id(0);
+        let away_peer_id = network.peer_id(1);
+        let home = &mut network.peers[0];
+        home.network_handler
+            .as_mut()
+            .unwrap()
+            .send_new_challenge(&home.world, away_peer_id)?;
+        network
+            .run_until(
+                |peers| {
+                    peers[1]
+                        .network_handler
+                        .as_ref()
+                        .unwrap()
+                        .get_challenge(&home_peer_id)
+                        .is_some()
+                },
+                Duration::from_secs(10),
+            )
+            .await?;
+        let away = &mut network.peers[1];
+        let challenge = away
+            .network_handler
+            .as_ref()
+            .unwrap()
+            .get_challenge(&home_peer_id)
+            .unwrap()
+            .clone();
+        away.network_handler
+            .as_mut()
+            .unwrap()
+            .accept_challenge(&away.world, challenge)?;
+
+        // Forge the ACK of the challenger, without letting the network deliver the real one.
+        let accepted = away
+            .network_handler
+            .as_ref()
+            .unwrap()
+            .get_challenge(&home_peer_id)
+            .unwrap()
+            .clone();
+        let mut ack = accepted.clone();
+        ack.transition(ChallengeState::Ack)?;
+        ack.game_id = Some(GameId::new());
+        ack.starting_at = Some(Tick::now() + MINUTES);
+
+        // Another team in place of ours is rejected.
+        let mut swapped = ack.clone();
+        swapped.away_team = ack.home_team.clone();
+        let callback = NetworkCallbackPreset::handle_challenge_response(home_peer_id, swapped);
+        assert!(callback(away).is_err());
+        assert!(away.world.get_own_team()?.current_game.is_none());
+
+        // Our team with a tampered lineup is replaced by the one we accepted with.
+        away.network_handler
+            .as_mut()
+            .unwrap()
+            .add_challenge(home_peer_id, accepted.clone());
+        let mut tampered = ack.clone();
+        for player in tampered.away_team.as_mut().unwrap().players.values_mut() {
+            player.athleticism.quickness = MAX_SKILL;
+        }
+        let callback = NetworkCallbackPreset::handle_challenge_response(home_peer_id, tampered);
+        callback(away)?;
+        let game = away.world.get_game_or_err(ack.game_id.unwrap())?;
+        // Players with a team never compare equal, so compare the serialized teams.
+        assert_eq!(
+            serde_json::to_value(&game.away_team_in_game.players)?,
+            serde_json::to_value(&accepted.away_team.unwrap().players)?
+        );
+        Ok(())
+    }
+}
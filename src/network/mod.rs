@@ -1,4 +1,9 @@
+pub mod config;
 pub mod constants;
+pub mod envelope;
 pub mod handler;
+#[cfg(test)]
+mod harness;
 pub mod network_callback;
 pub mod types;
+pub mod validation;
@@ -1,11 +1,16 @@
 use super::constants::*;
+use super::envelope::{Envelope, MessageType};
 use super::handler::NetworkHandler;
-use super::types::{Challenge, ChallengeState, NetworkGame, NetworkTeam, SeedInfo};
+use super::types::{
+    Challenge, ChallengeState, GameChecksum, Handshake, Leaderboard, NetworkGame, NetworkTeam,
+    SeedInfo,
+};
 use crate::types::{AppResult, SystemTimeTick, Tick, MINUTES};
 use crate::types::{GameId, IdSystem};
 use crate::ui::utils::SwarmPanelEvent;
 use crate::{app::App, types::AppCallback};
 use libp2p::gossipsub::{IdentTopic, TopicHash};
+use libp2p::request_response::RequestId;
 use libp2p::{gossipsub::Message, Multiaddr, PeerId};
 
 #[derive(Debug, Clone)]
@@ -42,15 +47,29 @@ pub enum NetworkCallbackPreset {
     HandleMsgTopic {
         message: Message,
     },
-    HandleChallengeTopic {
-        message: Message,
-    },
     HandleGameTopic {
         message: Message,
     },
     HandleSeedTopic {
         message: Message,
     },
+    HandleHandshakeTopic {
+        message: Message,
+    },
+    HandleChallengeRequest {
+        peer_id: PeerId,
+        request_id: RequestId,
+        challenge: Challenge,
+    },
+    HandleChallengeResponse {
+        peer_id: PeerId,
+        challenge: Challenge,
+    },
+    HandleChallengeFailure {
+        peer_id: PeerId,
+        challenge: Challenge,
+        error: String,
+    },
 }
 impl NetworkCallbackPreset {
     fn push_swarm_panel_message(timestamp: Tick, peer_id: PeerId, text: String) -> AppCallback {
@@ -87,13 +106,12 @@ impl NetworkCallbackPreset {
             app.ui.swarm_panel.push_log_event(event);
             app.network_handler.as_mut().unwrap().address = address.clone();
 
-            let multiaddr = app.network_handler.as_ref().unwrap().seed_address.clone();
-            app.network_handler.as_mut().unwrap().dial(multiaddr)?;
+            app.network_handler.as_mut().unwrap().dial_bootstrap_peers()?;
             Ok(None)
         })
     }
 
-    fn subscribe(topic: TopicHash) -> AppCallback {
+    fn subscribe(peer_id: PeerId, topic: TopicHash) -> AppCallback {
         Box::new(move |app: &mut App| {
             let event = SwarmPanelEvent {
                 timestamp: Tick::now(),
@@ -107,6 +125,14 @@ impl NetworkCallbackPreset {
                     app.world.dirty_network = true;
                 }
             }
+
+            if topic == IdentTopic::new(SubscriptionTopic::HANDSHAKE).hash() {
+                let network_handler = app.network_handler.as_mut().unwrap();
+                // A peer that just subscribed may be on another version, let it know ours.
+                if network_handler.check_peer(Some(peer_id)).is_ok() {
+                    network_handler.send_handshake()?;
+                }
+            }
             Ok(None)
         })
     }
@@ -149,7 +175,8 @@ impl NetworkCallbackPreset {
 
     fn handle_team_topic(message: Message) -> AppCallback {
         Box::new(move |app: &mut App| {
-            let (timestamp, data) = split_message(&message);
+            let envelope = decode_message(app, &message, &[MessageType::Team])?;
+            let timestamp = envelope.timestamp;
             let peer_id = message.source.clone();
             let event = SwarmPanelEvent {
                 timestamp,
@@ -158,7 +185,7 @@ impl NetworkCallbackPreset {
             };
             app.ui.swarm_panel.push_log_event(event);
 
-            let try_deserialize_team = serde_json::from_slice::<NetworkTeam>(data);
+            let try_deserialize_team = envelope.data::<NetworkTeam>();
             if let Ok(network_team) = try_deserialize_team {
                 let event = SwarmPanelEvent {
                     timestamp,
@@ -169,8 +196,11 @@ impl NetworkCallbackPreset {
                     ),
                 };
                 app.ui.swarm_panel.push_log_event(event);
-                if let Some(id) = peer_id {
-                    app.ui.swarm_panel.add_peer_id(id, network_team.team.id);
+                // Teams relayed by the seed belong to peers we may not be connected to.
+                if peer_id.is_some() && peer_id == network_team.team.peer_id {
+                    app.ui
+                        .swarm_panel
+                        .add_peer_id(peer_id.unwrap(), network_team.team.id);
                 }
                 app.world.add_network_team(network_team)?;
             } else {
@@ -192,8 +222,9 @@ impl NetworkCallbackPreset {
 
     fn handle_msg_topic(message: Message) -> AppCallback {
         Box::new(move |app: &mut App| {
-            let (timestamp, data) = split_message(&message);
-            let text = std::str::from_utf8(data)?.to_string();
+            let envelope = decode_message(app, &message, &[MessageType::Msg])?;
+            let timestamp = envelope.timestamp;
+            let text = String::from_utf8(envelope.payload)?;
             let event = SwarmPanelEvent {
                 timestamp,
                 peer_id: message.source,
@@ -206,8 +237,25 @@ impl NetworkCallbackPreset {
 
     fn handle_game_topic(message: Message) -> AppCallback {
         Box::new(move |app: &mut App| {
-            let (timestamp, data) = split_message(&message);
+            let envelope = decode_message(
+                app,
+                &message,
+                &[MessageType::Game, MessageType::GameChecksum],
+            )?;
+            let timestamp = envelope.timestamp;
             let peer_id = message.source.clone();
+
+            if envelope.message_type == MessageType::GameChecksum {
+                let checksum = envelope.data::<GameChecksum>()?;
+                let network_handler = app.network_handler.as_mut().unwrap();
+                if let Some(id) = peer_id {
+                    if id != *network_handler.swarm.local_peer_id() {
+                        // Checksums are verified on the next tick, once our simulation caught up.
+                        network_handler.add_game_checksum(id, checksum);
+                    }
+                }
+                return Ok(None);
+            }
             let event = SwarmPanelEvent {
                 timestamp,
                 peer_id,
@@ -215,7 +263,7 @@ impl NetworkCallbackPreset {
             };
             app.ui.swarm_panel.push_log_event(event);
 
-            let try_deserialize_game = serde_json::from_slice::<NetworkGame>(data);
+            let try_deserialize_game = envelope.data::<NetworkGame>();
             if let Ok(game) = try_deserialize_game {
                 let event = SwarmPanelEvent {
                     timestamp,
@@ -243,8 +291,29 @@ impl NetworkCallbackPreset {
 
     pub fn handle_seed_topic(message: Message) -> AppCallback {
         Box::new(move |app: &mut App| {
-            let (timestamp, data) = split_message(&message);
-            let info = serde_json::from_slice::<SeedInfo>(data)?;
+            let envelope = decode_message(
+                app,
+                &message,
+                &[MessageType::SeedInfo, MessageType::Leaderboard],
+            )?;
+            let timestamp = envelope.timestamp;
+
+            if envelope.message_type == MessageType::Leaderboard {
+                let leaderboard = envelope.data::<Leaderboard>()?;
+                let event = SwarmPanelEvent {
+                    timestamp,
+                    peer_id: message.source,
+                    text: format!(
+                        "Leaderboard updated: {} teams, {} games",
+                        leaderboard.teams_count, leaderboard.games_count
+                    ),
+                };
+                app.ui.swarm_panel.push_log_event(event);
+                app.ui.swarm_panel.set_leaderboard(leaderboard);
+                return Ok(None);
+            }
+
+            let info = envelope.data::<SeedInfo>()?;
 
             let event = SwarmPanelEvent {
                 timestamp,
@@ -282,156 +351,276 @@ impl NetworkCallbackPreset {
         })
     }
 
-    pub fn handle_challenge_topic(message: Message) -> AppCallback {
+    fn handle_handshake_topic(message: Message) -> AppCallback {
         Box::new(move |app: &mut App| {
+            let peer_id = message.source.ok_or("Handshake without source peer")?;
             let network_handler = app.network_handler.as_mut().unwrap();
-            let self_peer_id = network_handler.swarm.local_peer_id().clone();
-            let (timestamp, data) = split_message(&message);
+            if peer_id == *network_handler.swarm.local_peer_id() {
+                return Ok(None);
+            }
+
+            let envelope = Envelope::decode(&message.data)?;
+            if envelope.message_type != MessageType::Handshake {
+                return Err("Unexpected message on handshake topic".into());
+            }
+            let handshake = envelope.data::<Handshake>()?;
+            let version = handshake.version();
+            if let Err(err) = network_handler.register_handshake(peer_id, handshake) {
+                // Drop anything we already received from the peer.
+                app.world.filter_peer_data(Some(peer_id));
+                app.ui.swarm_panel.remove_peer_id(&peer_id);
+                return Err(err);
+            }
 
-            let mut challenge = serde_json::from_slice::<Challenge>(data)?;
             let event = SwarmPanelEvent {
-                timestamp,
-                peer_id: message.source,
-                text: format!("\nChallenge: {}", challenge.format()),
+                timestamp: envelope.timestamp,
+                peer_id: Some(peer_id),
+                text: format!("Handshake with peer {}: version {}", peer_id, version),
             };
             app.ui.swarm_panel.push_log_event(event);
+            Ok(None)
+        })
+    }
 
-            match challenge.state {
-                //FIXME: I think a single player is trying to handle more than one state. We should enforce the roles more clearly, i.e. who is the challenger who the challenged and only handle relevant states
-                ChallengeState::Syn => {
-                    if challenge.home_peer_id == self_peer_id {
-                        return Err(format!("Team is challenge sender (should be receiver)").into());
-                    }
-
-                    if challenge.away_peer_id != self_peer_id {
-                        return Err(format!("Team is not challenge receiver").into());
-                    }
-
-                    network_handler.add_challenge(challenge.clone());
-
-                    app.ui
-                        .swarm_panel
-                        .add_challenge(challenge.home_peer_id.clone(), challenge.clone());
-
-                    return Ok(Some(
-                        "Challenge received.\nCheck the swarm panel".to_string(),
-                    ));
-                }
+    fn challenge_failed(
+        app: &mut App,
+        peer_id: PeerId,
+        challenge: &Challenge,
+    ) -> AppResult<Option<String>> {
+        app.network_handler
+            .as_mut()
+            .unwrap()
+            .remove_challenge(&peer_id);
+        app.ui.swarm_panel.remove_challenge(&challenge.home_peer_id);
+        let error = challenge
+            .error_message
+            .clone()
+            .unwrap_or("Unknown error".to_string());
+        app.ui.set_popup(crate::ui::ui::PopupMessage::Error(
+            format!("Challenge failed: {}", error),
+            Tick::now(),
+        ));
+        Err(format!("Challenge failed. {}", error))?
+    }
 
-                ChallengeState::SynAck => {
-                    if challenge.away_peer_id == self_peer_id {
-                        return Err(format!("Team is challenge receiver (should be sender)").into());
-                    }
+    /// Handles a challenge request sent directly by the other team.
+    /// Every request gets a response: errors are sent back as a failed challenge.
+    pub fn handle_challenge_request(
+        peer_id: PeerId,
+        request_id: RequestId,
+        challenge: Challenge,
+    ) -> AppCallback {
+        Box::new(move |app: &mut App| {
+            let self_peer_id = app
+                .network_handler
+                .as_ref()
+                .unwrap()
+                .swarm
+                .local_peer_id()
+                .clone();
+            let event = SwarmPanelEvent {
+                timestamp: Tick::now(),
+                peer_id: Some(peer_id),
+                text: format!("\nChallenge: {}", challenge.format()),
+            };
+            app.ui.swarm_panel.push_log_event(event);
 
-                    if challenge.home_peer_id != self_peer_id {
-                        return Err(format!("Team is not challenge sender").into());
+            let mut challenge = challenge.clone();
+            let mut handle_request = || -> AppResult<Option<String>> {
+                app.network_handler
+                    .as_ref()
+                    .unwrap()
+                    .check_peer(Some(peer_id))?;
+                match challenge.state {
+                    ChallengeState::Syn => {
+                        if challenge.away_peer_id != self_peer_id
+                            || challenge.home_peer_id != peer_id
+                        {
+                            return Err("Team is not challenge receiver".into());
+                        }
+                        NetworkHandler::can_handle_challenge(&app.world)?;
+                        app.network_handler
+                            .as_mut()
+                            .unwrap()
+                            .add_challenge(peer_id, challenge.clone());
+                        app.ui
+                            .swarm_panel
+                            .add_challenge(challenge.home_peer_id, challenge.clone());
+                        Ok(Some("Challenge received.\nCheck the swarm panel".to_string()))
                     }
 
-                    let mut handle_syn_ack = || -> AppResult<()> {
+                    ChallengeState::SynAck => {
+                        if challenge.home_peer_id != self_peer_id
+                            || challenge.away_peer_id != peer_id
+                        {
+                            return Err("Team is not challenge sender".into());
+                        }
+                        let network_handler = app.network_handler.as_mut().unwrap();
+                        let open_challenge = network_handler
+                            .get_challenge(&peer_id)
+                            .ok_or("No open challenge with this peer")?;
+                        if open_challenge.state != ChallengeState::Syn
+                            || open_challenge.home_team.as_ref().map(|t| t.team_id)
+                                != challenge.home_team.as_ref().map(|t| t.team_id)
+                        {
+                            return Err("Challenge does not match the one we sent".into());
+                        }
+                        // Play with the team we offered, not the one echoed back.
+                        challenge.home_team = open_challenge.home_team.clone();
                         NetworkHandler::can_handle_challenge(&app.world)?;
-                        challenge.state = ChallengeState::Ack;
+
+                        challenge.transition(ChallengeState::Ack)?;
                         challenge.game_id = Some(GameId::new());
                         challenge.starting_at = Some(Tick::now() + 2 * MINUTES);
+                        challenge.generate_game(&mut app.world)?;
+                        network_handler.remove_challenge(&peer_id);
+                        // Let third parties know about the game with the next team update.
+                        app.world.dirty_network = true;
 
                         let event = SwarmPanelEvent {
-                            timestamp,
-                            peer_id: message.source,
+                            timestamp: Tick::now(),
+                            peer_id: Some(peer_id),
                             text: format!("Challenge accepted, generating game"),
                         };
                         app.ui.swarm_panel.push_log_event(event);
-                        challenge.generate_game(&mut app.world)?;
                         app.ui.set_popup(crate::ui::ui::PopupMessage::Ok(
                             format!("Challenge accepted, game is starting."),
                             Tick::now(),
                         ));
-
-                        network_handler.send_challenge(&challenge)?;
-                        Ok(())
-                    };
-
-                    if let Err(err) = handle_syn_ack() {
-                        let mut challenge =
-                            Challenge::new(challenge.home_peer_id, challenge.away_peer_id);
-                        challenge.state = ChallengeState::Failed;
-                        challenge.error_message = Some(err.to_string());
-                        network_handler.send_challenge(&challenge)?;
-                        return Err(err.to_string())?;
+                        Ok(None)
                     }
-                }
 
-                ChallengeState::Ack => {
-                    // Not team challenge, we just generate game to display it in UI.
-                    if challenge.home_peer_id != self_peer_id
-                        && challenge.away_peer_id != self_peer_id
-                    {
-                        let event = SwarmPanelEvent {
-                            timestamp,
-                            peer_id: message.source,
-                            text: format!("Adding challenge from network"),
-                        };
-                        app.ui.swarm_panel.push_log_event(event);
-                        challenge.generate_game(&mut app.world)?;
-                        return Ok(None);
+                    ChallengeState::Failed => {
+                        if challenge.home_peer_id != self_peer_id
+                            && challenge.away_peer_id != self_peer_id
+                        {
+                            return Err("Challenge failed, but it's not our challenge.".into());
+                        }
+                        // Nothing to send back, the other peer already gave up.
+                        Self::challenge_failed(app, peer_id, &challenge).or(Ok(None))
                     }
 
-                    if challenge.home_peer_id == self_peer_id {
-                        return Err(format!("Team is challenge sender (should be receiver)").into());
-                    }
+                    ChallengeState::Ack => Err("Unexpected challenge state".into()),
+                }
+            };
 
-                    if challenge.away_peer_id != self_peer_id {
-                        return Err(format!("Team is not challenge receiver").into());
+            let result = handle_request();
+            let network_handler = app.network_handler.as_mut().unwrap();
+            match result {
+                Ok(message) => {
+                    // New challenges are answered once they are accepted or declined.
+                    if challenge.state == ChallengeState::Syn {
+                        network_handler.hold_challenge_request(peer_id, request_id);
+                    } else {
+                        network_handler.respond_to_challenge(request_id, challenge)?;
                     }
+                    Ok(message)
+                }
+                Err(err) => {
+                    network_handler.remove_challenge(&peer_id);
+                    challenge.fail(err.to_string());
+                    network_handler.respond_to_challenge(request_id, challenge)?;
+                    Err(err)
+                }
+            }
+        })
+    }
 
-                    let mut handle_ack = || -> AppResult<()> {
-                        NetworkHandler::can_handle_challenge(&app.world)?;
-                        let event = SwarmPanelEvent {
-                            timestamp,
-                            peer_id: message.source,
-                            text: format!("Challenge accepted, generating game"),
-                        };
-                        app.ui.swarm_panel.push_log_event(event);
-                        challenge.generate_game(&mut app.world)?;
-                        app.ui.set_popup(crate::ui::ui::PopupMessage::Ok(
-                            format!("Challenge accepted, game is starting."),
-                            Tick::now(),
-                        ));
-                        Ok(())
+    /// Handles the response of the other team to one of our challenge requests.
+    pub fn handle_challenge_response(peer_id: PeerId, challenge: Challenge) -> AppCallback {
+        Box::new(move |app: &mut App| {
+            let self_peer_id = app
+                .network_handler
+                .as_ref()
+                .unwrap()
+                .swarm
+                .local_peer_id()
+                .clone();
+            let mut challenge = challenge.clone();
+            match challenge.state {
+                ChallengeState::Syn => {
+                    let event = SwarmPanelEvent {
+                        timestamp: Tick::now(),
+                        peer_id: Some(peer_id),
+                        text: format!("Challenge delivered to {}", peer_id),
                     };
+                    app.ui.swarm_panel.push_log_event(event);
+                    Ok(None)
+                }
 
-                    if let Err(err) = handle_ack() {
-                        let mut challenge =
-                            Challenge::new(challenge.home_peer_id, challenge.away_peer_id);
-                        challenge.state = ChallengeState::Failed;
-                        challenge.error_message = Some(err.to_string());
-                        network_handler.send_challenge(&challenge)?;
-                        return Err(err.to_string())?;
+                ChallengeState::Ack => {
+                    if challenge.away_peer_id != self_peer_id
+                        || challenge.home_peer_id != peer_id
+                    {
+                        return Err("Team is not challenge receiver".into());
                     }
-                }
+                    let network_handler = app.network_handler.as_mut().unwrap();
+                    let open_challenge = network_handler
+                        .remove_challenge(&peer_id)
+                        .ok_or("No open challenge with this peer")?;
+                    app.ui.swarm_panel.remove_challenge(&peer_id);
+                    if open_challenge.state != ChallengeState::SynAck
+                        || open_challenge.away_team.as_ref().map(|t| t.team_id)
+                            != challenge.away_team.as_ref().map(|t| t.team_id)
+                    {
+                        let err = "Challenge does not match the one we accepted".to_string();
+                        network_handler.fail_challenge(challenge, err.clone());
+                        return Err(err.into());
+                    }
+                    // Play with the team we offered, not the one echoed back.
+                    challenge.away_team = open_challenge.away_team;
 
-                ChallengeState::Failed => {
-                    assert!(challenge.error_message.is_some());
-                    if challenge.home_peer_id != self_peer_id
-                        && challenge.away_peer_id != self_peer_id
+                    if let Err(err) = NetworkHandler::can_handle_challenge(&app.world)
+                        .and_then(|_| challenge.generate_game(&mut app.world))
                     {
-                        return Err("Challenge failed, but it's not our challenge.")?;
+                        app.network_handler
+                            .as_mut()
+                            .unwrap()
+                            .fail_challenge(challenge, err.to_string());
+                        return Err(err);
                     }
-                    app.ui.swarm_panel.remove_challenge(&challenge.home_peer_id);
-                    app.ui.set_popup(crate::ui::ui::PopupMessage::Error(
-                        format!(
-                            "Challenge failed: {}",
-                            challenge.error_message.clone().unwrap()
-                        ),
+
+                    let event = SwarmPanelEvent {
+                        timestamp: Tick::now(),
+                        peer_id: Some(peer_id),
+                        text: format!("Challenge accepted, generating game"),
+                    };
+                    app.ui.swarm_panel.push_log_event(event);
+                    app.ui.set_popup(crate::ui::ui::PopupMessage::Ok(
+                        format!("Challenge accepted, game is starting."),
                         Tick::now(),
                     ));
+                    Ok(None)
+                }
+
+                ChallengeState::Failed => Self::challenge_failed(app, peer_id, &challenge),
 
-                    return Err(format!(
-                        "Challenge failed. {}",
-                        challenge.error_message.unwrap()
-                    ))?;
+                // The challenged team also sends the accepted challenge as a new request.
+                ChallengeState::SynAck => {
+                    let event = SwarmPanelEvent {
+                        timestamp: Tick::now(),
+                        peer_id: Some(peer_id),
+                        text: format!("Challenge accepted by {}", peer_id),
+                    };
+                    app.ui.swarm_panel.push_log_event(event);
+                    Ok(None)
                 }
             }
+        })
+    }
 
-            Ok(None)
+    fn handle_challenge_failure(
+        peer_id: PeerId,
+        challenge: Challenge,
+        error: String,
+    ) -> AppCallback {
+        Box::new(move |app: &mut App| {
+            app.ui.swarm_panel.remove_challenge(&challenge.home_peer_id);
+            app.ui.set_popup(crate::ui::ui::PopupMessage::Error(
+                format!("Could not reach peer {}: {}", peer_id, error),
+                Tick::now(),
+            ));
+            Err(format!("Challenge request to {} failed: {}", peer_id, error))?
         })
     }
 
@@ -448,7 +637,9 @@ impl NetworkCallbackPreset {
                 Self::push_swarm_panel_log(timestamp.clone(), text.clone())(app)
             }
             Self::BindAddress { address } => Self::bind_address(address.clone())(app),
-            Self::Subscribe { peer_id: _, topic } => Self::subscribe(topic.clone())(app),
+            Self::Subscribe { peer_id, topic } => {
+                Self::subscribe(peer_id.clone(), topic.clone())(app)
+            }
             Self::Unsubscribe { peer_id, topic } => {
                 Self::unsubscribe(peer_id.clone(), topic.clone())(app)
             }
@@ -466,17 +657,45 @@ impl NetworkCallbackPreset {
             }
             Self::HandleTeamTopic { message } => Self::handle_team_topic(message.clone())(app),
             Self::HandleMsgTopic { message } => Self::handle_msg_topic(message.clone())(app),
-            Self::HandleChallengeTopic { message } => {
-                Self::handle_challenge_topic(message.clone())(app)
-            }
             Self::HandleGameTopic { message } => Self::handle_game_topic(message.clone())(app),
             Self::HandleSeedTopic { message } => Self::handle_seed_topic(message.clone())(app),
+            Self::HandleHandshakeTopic { message } => {
+                Self::handle_handshake_topic(message.clone())(app)
+            }
+            Self::HandleChallengeRequest {
+                peer_id,
+                request_id,
+                challenge,
+            } => Self::handle_challenge_request(*peer_id, *request_id, challenge.clone())(app),
+            Self::HandleChallengeResponse { peer_id, challenge } => {
+                Self::handle_challenge_response(*peer_id, challenge.clone())(app)
+            }
+            Self::HandleChallengeFailure {
+                peer_id,
+                challenge,
+                error,
+            } => Self::handle_challenge_failure(*peer_id, challenge.clone(), error.clone())(app),
         }
     }
 }
 
-pub fn split_message(message: &Message) -> (Tick, &[u8]) {
-    // Deserialize the data as a team. first 16 bytes are the timestamp, so we slice them off
-    let timestamp = Tick::from_le_bytes(message.data[..16].try_into().unwrap());
-    (timestamp, &message.data[16..])
+/// Decodes the message envelope, rejecting messages of the wrong type for the topic
+/// and messages from peers that failed the handshake.
+pub fn decode_message(
+    app: &App,
+    message: &Message,
+    message_types: &[MessageType],
+) -> AppResult<Envelope> {
+    if let Some(network_handler) = app.network_handler.as_ref() {
+        network_handler.check_peer(message.source)?;
+    }
+    let envelope = Envelope::decode(&message.data)?;
+    if !message_types.contains(&envelope.message_type) {
+        return Err(format!(
+            "Unexpected message type {:?} (expected {:?})",
+            envelope.message_type, message_types
+        )
+        .into());
+    }
+    Ok(envelope)
 }
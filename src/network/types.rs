@@ -1,8 +1,14 @@
 use std::collections::HashMap;
 
+use super::constants::{
+    CHALLENGE_EXPIRY, MIN_PROTOCOL_VERSION, PROTOCOL_VERSION, REQUIRED_FEATURES,
+    SUPPORTED_FEATURES,
+};
+use crate::engine::action::ActionOutput;
+use crate::engine::game::Game;
 use crate::engine::timer::Timer;
 use crate::engine::types::GameStats;
-use crate::types::{PlanetId, Tick};
+use crate::types::{PlanetId, SystemTimeTick, Tick};
 use crate::world::position::{Position, MAX_POSITION};
 use crate::{
     engine::types::TeamInGame,
@@ -22,6 +28,24 @@ pub enum ChallengeState {
     Failed,
 }
 
+impl ChallengeState {
+    /// The challenger sends Syn, the challenged team answers with SynAck
+    /// and the challenger confirms with Ack. Either side can fail until the game is set.
+    pub fn can_transition_to(&self, next: &ChallengeState) -> bool {
+        matches!(
+            (self, next),
+            (Self::Syn, Self::SynAck)
+                | (Self::Syn, Self::Failed)
+                | (Self::SynAck, Self::Ack)
+                | (Self::SynAck, Self::Failed)
+        )
+    }
+
+    pub fn is_final(&self) -> bool {
+        matches!(self, Self::Ack | Self::Failed)
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct Challenge {
     pub state: ChallengeState,
@@ -32,6 +56,8 @@ pub struct Challenge {
     pub game_id: Option<GameId>,
     pub starting_at: Option<Tick>,
     pub error_message: Option<String>,
+    #[serde(default)]
+    pub created_at: Tick,
 }
 
 impl Challenge {
@@ -45,7 +71,26 @@ impl Challenge {
             game_id: None,
             starting_at: None,
             error_message: None,
+            created_at: Tick::now(),
+        }
+    }
+
+    pub fn transition(&mut self, state: ChallengeState) -> AppResult<()> {
+        if !self.state.can_transition_to(&state) {
+            return Err(format!("Invalid challenge transition {} -> {}", self.state, state).into());
         }
+        self.state = state;
+        Ok(())
+    }
+
+    pub fn fail(&mut self, error_message: String) {
+        self.state = ChallengeState::Failed;
+        self.error_message = Some(error_message);
+    }
+
+    /// Challenges that are not answered in time are dropped.
+    pub fn is_expired(&self, now: Tick) -> bool {
+        !self.state.is_final() && now > self.created_at + CHALLENGE_EXPIRY
     }
 
     pub fn format(&self) -> String {
@@ -138,6 +183,7 @@ impl NetworkGame {
                 .ok_or("Cannot get player stats for home team in game".to_string())?;
             player_stats.initial_tiredness = player_stat.initial_tiredness;
             player_stats.tiredness = player_stat.tiredness;
+            player_stats.morale = player_stat.morale;
             stats.insert(player_id.clone(), player_stats.clone());
         }
         home_team_in_game.stats = stats;
@@ -155,6 +201,7 @@ impl NetworkGame {
                 .ok_or("Cannot get player stats for away team in game".to_string())?;
             player_stats.initial_tiredness = player_stat.initial_tiredness;
             player_stats.tiredness = player_stat.tiredness;
+            player_stats.morale = player_stat.morale;
             stats.insert(player_id.clone(), player_stats.clone());
         }
         away_team_in_game.stats = stats;
@@ -171,6 +218,125 @@ impl NetworkGame {
     }
 }
 
+/// Checksums of a window of actions of a network game, used to detect desyncs.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct GameChecksum {
+    pub game_id: GameId,
+    pub from_action: usize,
+    pub action_checksums: Vec<u64>,
+    pub score: (u16, u16),
+    pub ended: bool,
+}
+
+impl GameChecksum {
+    pub fn new(game: &Game, from_action: usize) -> Self {
+        Self {
+            game_id: game.id,
+            from_action,
+            action_checksums: game.action_checksums(from_action),
+            score: game.get_score(),
+            ended: game.timer.has_ended(),
+        }
+    }
+
+    pub fn to_action(&self) -> usize {
+        self.from_action + self.action_checksums.len()
+    }
+}
+
+/// Diagnostic dump of the first action where two simulations of a game diverged.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GameDesync {
+    pub game_id: GameId,
+    pub peer_id: PeerId,
+    pub action_index: Option<usize>,
+    pub local_checksum: Option<u64>,
+    pub remote_checksum: Option<u64>,
+    pub local_score: (u16, u16),
+    pub remote_score: (u16, u16),
+    pub local_action: Option<ActionOutput>,
+}
+
+impl GameDesync {
+    pub fn description(&self) -> String {
+        match self.action_index {
+            Some(idx) => format!("Desync in game {} at action {}", self.game_id, idx),
+            None => format!(
+                "Desync in game {}: final score {}-{} but peer got {}-{}",
+                self.game_id,
+                self.local_score.0,
+                self.local_score.1,
+                self.remote_score.0,
+                self.remote_score.1
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Handshake {
+    pub protocol_version: u8,
+    pub min_protocol_version: u8,
+    pub features: Vec<String>,
+    pub version_major: usize,
+    pub version_minor: usize,
+    pub version_patch: usize,
+}
+
+impl Handshake {
+    pub fn new() -> Self {
+        Self {
+            protocol_version: PROTOCOL_VERSION,
+            min_protocol_version: MIN_PROTOCOL_VERSION,
+            features: SUPPORTED_FEATURES.iter().map(|f| f.to_string()).collect(),
+            version_major: env!("CARGO_PKG_VERSION_MAJOR").parse().unwrap(),
+            version_minor: env!("CARGO_PKG_VERSION_MINOR").parse().unwrap(),
+            version_patch: env!("CARGO_PKG_VERSION_PATCH").parse().unwrap(),
+        }
+    }
+
+    pub fn version(&self) -> String {
+        format!(
+            "{}.{}.{}",
+            self.version_major, self.version_minor, self.version_patch
+        )
+    }
+
+    pub fn supports(&self, feature: &str) -> bool {
+        self.features.iter().any(|f| f == feature)
+    }
+
+    /// Checks that the peer that sent the handshake can exchange data with us.
+    pub fn check_compatibility(&self) -> AppResult<()> {
+        if self.protocol_version < MIN_PROTOCOL_VERSION {
+            return Err(format!(
+                "Peer runs version {} with protocol {}, which is too old (min {}).",
+                self.version(),
+                self.protocol_version,
+                MIN_PROTOCOL_VERSION
+            )
+            .into());
+        }
+        if self.min_protocol_version > PROTOCOL_VERSION {
+            return Err(format!(
+                "Peer runs version {} and requires protocol {}, please update.",
+                self.version(),
+                self.min_protocol_version
+            )
+            .into());
+        }
+        if let Some(feature) = REQUIRED_FEATURES.iter().find(|f| !self.supports(f)) {
+            return Err(format!(
+                "Peer runs version {} without support for {}.",
+                self.version(),
+                feature
+            )
+            .into());
+        }
+        Ok(())
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct SeedInfo {
     pub connected_peers_count: usize,
@@ -191,3 +357,77 @@ impl SeedInfo {
         }
     }
 }
+
+/// A network game as tracked by the relayer, with the final score once it has ended.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct GameRecord {
+    pub game_id: GameId,
+    pub home_team_id: TeamId,
+    pub home_team_name: String,
+    pub home_peer_id: Option<PeerId>,
+    pub away_team_id: TeamId,
+    pub away_team_name: String,
+    pub away_peer_id: Option<PeerId>,
+    pub starting_at: Tick,
+    pub score: Option<(u16, u16)>,
+}
+
+impl GameRecord {
+    pub fn from_network_game(game: &NetworkGame) -> Self {
+        Self {
+            game_id: game.id,
+            home_team_id: game.home_team_in_game.team_id,
+            home_team_name: game.home_team_in_game.name.clone(),
+            home_peer_id: game.home_team_in_game.peer_id,
+            away_team_id: game.away_team_in_game.team_id,
+            away_team_name: game.away_team_in_game.name.clone(),
+            away_peer_id: game.away_team_in_game.peer_id,
+            starting_at: game.starting_at,
+            score: None,
+        }
+    }
+
+    pub fn involves_peer(&self, peer_id: PeerId) -> bool {
+        self.home_peer_id == Some(peer_id) || self.away_peer_id == Some(peer_id)
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct LeaderboardEntry {
+    pub team_id: TeamId,
+    pub team_name: String,
+    pub played: u32,
+    pub wins: u32,
+    pub losses: u32,
+    pub ties: u32,
+    pub points_for: u32,
+    pub points_against: u32,
+}
+
+impl LeaderboardEntry {
+    pub fn point_difference(&self) -> i64 {
+        self.points_for as i64 - self.points_against as i64
+    }
+}
+
+/// Global ranking of network teams, computed by the relayer from finished network games.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct Leaderboard {
+    pub entries: Vec<LeaderboardEntry>,
+    pub teams_count: usize,
+    pub games_count: usize,
+    pub updated_at: Tick,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+pub struct TopicTraffic {
+    pub messages: u64,
+    pub bytes: u64,
+}
+
+impl TopicTraffic {
+    pub fn record(&mut self, bytes: usize) {
+        self.messages += 1;
+        self.bytes += bytes as u64;
+    }
+}
@@ -0,0 +1,243 @@
+use super::constants::{
+    MAX_GENERATED_SKILL_ABOVE_BASE_LEVEL, MAX_NETWORK_TEAM_BALANCE, MAX_SKILL_INCREASE_PER_VERSION,
+    MAX_UNSEEN_PLAYER_SKILL_GROWTH, MIN_GAME_DURATION,
+};
+use super::types::NetworkTeam;
+use crate::engine::constants::MAX_TIREDNESS;
+use crate::types::{AppResult, SystemTimeTick, Tick};
+use crate::world::{
+    constants::{MAX_MORALE, MAX_PLAYERS_PER_TEAM},
+    player::Player,
+    skill::{MIN_SKILL, MAX_SKILL},
+    world::World,
+};
+use std::collections::HashSet;
+
+// Allows for rounding errors in skill updates.
+const SKILL_TOLERANCE: f32 = 0.001;
+
+fn is_in_bounds(value: f32, min: f32, max: f32) -> bool {
+    value.is_finite() && value >= min - SKILL_TOLERANCE && value <= max + SKILL_TOLERANCE
+}
+
+/// `max_games` is how many games the player could have played since we last stored it.
+fn validate_player(player: &Player, world: &World, max_games: u64) -> AppResult<()> {
+    let name = format!("{} {}", player.info.first_name, player.info.last_name);
+    for skill in player.current_skill_array() {
+        if !is_in_bounds(skill, MIN_SKILL, MAX_SKILL) {
+            return Err(format!("Player {} has skill {} out of bounds", name, skill).into());
+        }
+    }
+    if !is_in_bounds(player.reputation, 0.0, MAX_SKILL) {
+        return Err(format!("Player {} has invalid reputation", name).into());
+    }
+    if !is_in_bounds(player.tiredness, 0.0, MAX_TIREDNESS) {
+        return Err(format!("Player {} has invalid tiredness", name).into());
+    }
+    if !is_in_bounds(player.morale, 0.0, MAX_MORALE) {
+        return Err(format!("Player {} has invalid morale", name).into());
+    }
+
+    // Skills only grow by playing games, and every game bumps the player version.
+    // The peer sets the version, so the growth is also bounded by the elapsed time.
+    if let Some(db_player) = world.get_player(player.id) {
+        if db_player.version < player.version {
+            let max_increase = (player.version - db_player.version).min(max_games) as f32
+                * MAX_SKILL_INCREASE_PER_VERSION;
+            let old_skills = db_player.current_skill_array();
+            for (idx, skill) in player.current_skill_array().iter().enumerate() {
+                if skill - old_skills[idx] > max_increase + SKILL_TOLERANCE {
+                    return Err(format!(
+                        "Player {} skill grew from {:.2} to {:.2} in {} versions",
+                        name,
+                        old_skills[idx],
+                        skill,
+                        player.version - db_player.version
+                    )
+                    .into());
+                }
+            }
+        }
+    } else {
+        // Unseen players must be reachable from a generated player of the same age,
+        // which also bounds their value.
+        let skill_count = player.current_skill_array().len() as f32;
+        let generated_skills =
+            (player.info.age / 8.0 + MAX_GENERATED_SKILL_ABOVE_BASE_LEVEL) * skill_count;
+        let max_increase = (player.version as f32 * MAX_SKILL_INCREASE_PER_VERSION)
+            .min(MAX_UNSEEN_PLAYER_SKILL_GROWTH)
+            * skill_count;
+        if player.total_skills() as f32 > generated_skills + max_increase {
+            return Err(format!(
+                "Player {} has implausible skills for age {} and version {}",
+                name, player.info.age, player.version
+            )
+            .into());
+        }
+    }
+    Ok(())
+}
+
+/// Checks that a team received over the network could have been obtained by playing the game.
+pub fn validate_network_team(network_team: &NetworkTeam, world: &World) -> AppResult<()> {
+    if network_team.team.peer_id.is_none() {
+        return Err("Cannot receive team without peer_id over the network.".into());
+    }
+    validate_team(network_team, world)
+}
+
+/// Checks the roster, resources and players of a team, wherever it comes from.
+pub fn validate_team(network_team: &NetworkTeam, world: &World) -> AppResult<()> {
+    let NetworkTeam { team, players } = network_team;
+    let roster_size = team.player_ids.len();
+    if roster_size > MAX_PLAYERS_PER_TEAM {
+        return Err(format!(
+            "Team {} has {} players (max {})",
+            team.name, roster_size, MAX_PLAYERS_PER_TEAM
+        )
+        .into());
+    }
+    if roster_size > team.spaceship.capacity() as usize {
+        return Err(format!(
+            "Team {} has {} players but its spaceship only fits {}",
+            team.name,
+            roster_size,
+            team.spaceship.capacity()
+        )
+        .into());
+    }
+    if team.fuel > team.spaceship.tank() {
+        return Err(format!("Team {} has more fuel than its tank can hold", team.name).into());
+    }
+    if team.balance > MAX_NETWORK_TEAM_BALANCE {
+        return Err(format!("Team {} has an implausible balance", team.name).into());
+    }
+    if !is_in_bounds(team.reputation, 0.0, MAX_SKILL) {
+        return Err(format!("Team {} has invalid reputation", team.name).into());
+    }
+
+    let player_ids = team.player_ids.iter().collect::<HashSet<_>>();
+    if player_ids.len() != roster_size
+        || players.len() != roster_size
+        || players.iter().any(|p| !player_ids.contains(&p.id))
+    {
+        return Err(format!("Team {} roster does not match its players", team.name).into());
+    }
+
+    // Without a previous update, only one game could have been played in the meantime.
+    let max_games = world
+        .network_team_received_at
+        .get(&team.id)
+        .map(|received_at| Tick::now().saturating_sub(*received_at) / MIN_GAME_DURATION)
+        .unwrap_or_default()
+        + 1;
+    for player in players.iter() {
+        if player.peer_id != team.peer_id || player.team != Some(team.id) {
+            return Err(format!(
+                "Player {} {} does not belong to team {}",
+                player.info.first_name, player.info.last_name, team.name
+            )
+            .into());
+        }
+        validate_player(player, world, max_games as u64)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::validate_network_team;
+    use crate::network::constants::MIN_GAME_DURATION;
+    use crate::network::types::NetworkTeam;
+    use crate::test_utils::test_world_and_game;
+    use crate::types::{IdSystem, PlayerId, SystemTimeTick, Tick};
+    use crate::world::skill::{Athleticism, Defense, Mental, Offense, Technical, MAX_SKILL};
+    use libp2p::PeerId;
+
+    #[test]
+    fn test_validate_network_team() {
+        let (mut world, game) = test_world_and_game();
+        let team_id = game.home_team_in_game.team_id;
+        let mut network_team = NetworkTeam::from_team_id(&world, &team_id).unwrap();
+        network_team.set_peer_id(PeerId::random());
+        assert!(validate_network_team(&network_team, &world).is_ok());
+
+        // Skills cannot jump to the maximum in a single version.
+        let mut cheater = network_team.clone();
+        let player = &mut cheater.players[0];
+        player.version += 1;
+        player.offense.long_range = MAX_SKILL;
+        player.defense.steal = MAX_SKILL;
+        player.technical.passing = MAX_SKILL;
+        assert!(validate_network_team(&cheater, &world).is_err());
+
+        let mut cheater = network_team.clone();
+        cheater.players[0].athleticism.quickness = MAX_SKILL + 5.0;
+        assert!(validate_network_team(&cheater, &world).is_err());
+
+        let mut cheater = network_team.clone();
+        cheater.team.player_ids.push(PlayerId::new());
+        assert!(validate_network_team(&cheater, &world).is_err());
+
+        let mut cheater = network_team.clone();
+        cheater.team.fuel = cheater.team.spaceship.tank() + 1;
+        assert!(validate_network_team(&cheater, &world).is_err());
+
+        // Inflating the version does not allow more growth than the elapsed time does.
+        let player_id = network_team.players[0].id;
+        world.players.get_mut(&player_id).unwrap().offense.long_range = 0.0;
+        let mut grown = network_team.clone();
+        grown.players[0].version += 1000;
+        grown.players[0].offense.long_range = 2.0;
+        assert!(validate_network_team(&grown, &world).is_err());
+        world
+            .network_team_received_at
+            .insert(team_id, Tick::now() - 4 * MIN_GAME_DURATION);
+        assert!(validate_network_team(&grown, &world).is_ok());
+
+        // Teams that have never been seen cannot show up with maxed out players.
+        let (other_world, _) = test_world_and_game();
+        assert!(validate_network_team(&network_team, &other_world).is_ok());
+        let mut cheater = network_team.clone();
+        for player in cheater.players.iter_mut() {
+            player.athleticism = Athleticism {
+                quickness: MAX_SKILL,
+                vertical: MAX_SKILL,
+                strength: MAX_SKILL,
+                stamina: MAX_SKILL,
+            };
+            player.offense = Offense {
+                dunk: MAX_SKILL,
+                close_range: MAX_SKILL,
+                medium_range: MAX_SKILL,
+                long_range: MAX_SKILL,
+            };
+            player.technical = Technical {
+                passing: MAX_SKILL,
+                ball_handling: MAX_SKILL,
+                post_moves: MAX_SKILL,
+                rebounding: MAX_SKILL,
+            };
+            player.defense = Defense {
+                steal: MAX_SKILL,
+                block: MAX_SKILL,
+                perimeter_defense: MAX_SKILL,
+                interior_defense: MAX_SKILL,
+            };
+            player.mental = Mental {
+                vision: MAX_SKILL,
+                positioning: MAX_SKILL,
+                off_ball_movement: MAX_SKILL,
+                charisma: MAX_SKILL,
+            };
+        }
+        assert!(validate_network_team(&cheater, &other_world).is_err());
+        // Neither can a young player make up for it with a huge version.
+        cheater.players[0].info.age = 20.0;
+        for player in cheater.players.iter_mut() {
+            player.version += 1000;
+        }
+        assert!(validate_network_team(&cheater, &other_world).is_err());
+    }
+}
@@ -1,58 +1,736 @@
-use crate::network::constants::SubscriptionTopic;
-use crate::network::{handler::NetworkHandler, types::SeedInfo};
-use crate::types::AppResult;
+use crate::network::config::NetworkConfig;
+use crate::network::constants::{
+    SubscriptionTopic, LEADERBOARD_SIZE, RELAYER_DIRECTORY_SIZE, RELAYER_GAME_EXPIRY,
+    RELAYER_SAVE_INTERVAL,
+};
+use crate::network::envelope::{Envelope, MessageType};
+use crate::network::handler::{NetworkEvent, NetworkHandler, RebelsBehaviourEvent};
+use crate::network::types::{
+    GameChecksum, GameRecord, Leaderboard, LeaderboardEntry, NetworkGame, NetworkTeam, SeedInfo,
+    TopicTraffic,
+};
+use crate::network::validation::validate_network_team;
+use crate::store::{
+    load_from_json, load_or_generate_keypair, save_to_json, PERSISTED_RELAYER_FILENAME,
+    PERSISTED_RELAYER_KEYPAIR_FILENAME,
+};
+use crate::types::{AppResult, GameId, SystemTimeTick, TeamId, Tick};
+use crate::world::world::World;
+use env_logger::Env;
 use futures::StreamExt;
+use itertools::Itertools;
 use libp2p::gossipsub::IdentTopic;
-use libp2p::{gossipsub, swarm::SwarmEvent};
+use libp2p::{gossipsub, identity, swarm::SwarmEvent, PeerId};
+use log::{debug, info, warn, LevelFilter};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
+use tokio::io::{AsyncBufReadExt, BufReader};
 use tokio::select;
-use void::Void;
+
+/// Teams and game results collected by the relayer, persisted across restarts.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RelayerState {
+    teams: HashMap<TeamId, NetworkTeam>,
+    last_seen: HashMap<TeamId, Tick>,
+    games: HashMap<GameId, GameRecord>,
+    #[serde(default)]
+    banned_peers: HashSet<PeerId>,
+    // Final checksums waiting for the other team to confirm them.
+    #[serde(default)]
+    final_checksums: HashMap<GameId, (PeerId, GameChecksum)>,
+}
+
+impl RelayerState {
+    pub fn load() -> AppResult<Self> {
+        load_from_json(PERSISTED_RELAYER_FILENAME)
+    }
+
+    pub fn save(&self) -> AppResult<()> {
+        save_to_json(PERSISTED_RELAYER_FILENAME, self)
+    }
+
+    /// Stores the team if it is newer than the one we know.
+    /// Teams are only accepted from the peer that owns them.
+    pub fn add_team(
+        &mut self,
+        peer_id: PeerId,
+        network_team: NetworkTeam,
+        now: Tick,
+    ) -> AppResult<bool> {
+        let team = &network_team.team;
+        if self.is_banned(peer_id) {
+            return Err(format!("Peer {} is banned", peer_id).into());
+        }
+        if team.peer_id != Some(peer_id) {
+            return Err(format!("Team {} was not sent by its owner", team.name).into());
+        }
+        if let Some(db_team) = self.teams.get(&team.id) {
+            if db_team.team.peer_id != team.peer_id {
+                return Err(format!("Team {} belongs to another peer", team.name).into());
+            }
+            if db_team.team.version > team.version {
+                return Ok(false);
+            }
+        }
+        // The relayer has no world of its own: players are checked against
+        // the last version of the team we stored, if any.
+        let mut known_world = World::default();
+        if let Some(db_team) = self.teams.get(&team.id) {
+            for player in db_team.players.iter() {
+                known_world.players.insert(player.id, player.clone());
+            }
+            if let Some(last_seen) = self.last_seen.get(&team.id) {
+                known_world
+                    .network_team_received_at
+                    .insert(team.id, *last_seen);
+            }
+        }
+        validate_network_team(&network_team, &known_world)?;
+        self.last_seen.insert(team.id, now);
+        self.teams.insert(team.id, network_team);
+        Ok(true)
+    }
+
+    pub fn is_banned(&self, peer_id: PeerId) -> bool {
+        self.banned_peers.contains(&peer_id)
+    }
+
+    /// Bans the peer and removes its teams from the directory.
+    pub fn ban_peer(&mut self, peer_id: PeerId) {
+        self.banned_peers.insert(peer_id);
+        self.teams
+            .retain(|_, network_team| network_team.team.peer_id != Some(peer_id));
+        self.last_seen.retain(|team_id, _| self.teams.contains_key(team_id));
+    }
+
+    pub fn unban_peer(&mut self, peer_id: PeerId) -> bool {
+        self.banned_peers.remove(&peer_id)
+    }
+
+    pub fn team_of_peer(&self, peer_id: PeerId) -> Option<&NetworkTeam> {
+        self.teams
+            .values()
+            .find(|network_team| network_team.team.peer_id == Some(peer_id))
+    }
+
+    pub fn game_record(&self, game_id: GameId) -> Option<&GameRecord> {
+        self.games.get(&game_id)
+    }
+
+    pub fn add_game(&mut self, record: GameRecord) -> bool {
+        if self.games.contains_key(&record.game_id) {
+            return false;
+        }
+        self.games.insert(record.game_id, record);
+        true
+    }
+
+    /// Records the final score of a game once both teams have reported
+    /// the same final score after the same number of actions.
+    pub fn add_game_checksum(&mut self, peer_id: PeerId, checksum: &GameChecksum) -> bool {
+        if !checksum.ended {
+            return false;
+        }
+        let record = match self.games.get_mut(&checksum.game_id) {
+            Some(record) if record.score.is_none() && record.involves_peer(peer_id) => record,
+            _ => return false,
+        };
+        match self.final_checksums.remove(&checksum.game_id) {
+            Some((other_peer_id, other_checksum)) if other_peer_id != peer_id => {
+                // The window of actions in the final checksums depends on when each
+                // peer sent its previous checksums, so only the outcome is compared.
+                if other_checksum.score != checksum.score
+                    || other_checksum.to_action() != checksum.to_action()
+                {
+                    warn!(
+                        "Teams reported different results for game {}",
+                        checksum.game_id
+                    );
+                    return false;
+                }
+                record.score = Some(checksum.score);
+                true
+            }
+            _ => {
+                self.final_checksums
+                    .insert(checksum.game_id, (peer_id, checksum.clone()));
+                false
+            }
+        }
+    }
+
+    pub fn cleanup_games(&mut self, now: Tick) {
+        self.games.retain(|_, record| {
+            record.score.is_some() || record.starting_at + RELAYER_GAME_EXPIRY > now
+        });
+        self.final_checksums
+            .retain(|game_id, _| self.games.contains_key(game_id));
+    }
+
+    /// Returns the most recently seen teams, leaving out the ones owned by the given peer.
+    pub fn directory(&self, exclude: Option<PeerId>) -> Vec<&NetworkTeam> {
+        self.teams
+            .values()
+            .filter(|network_team| exclude.is_none() || network_team.team.peer_id != exclude)
+            .sorted_by_key(|network_team| {
+                std::cmp::Reverse(self.last_seen.get(&network_team.team.id).copied())
+            })
+            .take(RELAYER_DIRECTORY_SIZE)
+            .collect()
+    }
+
+    pub fn leaderboard(&self, now: Tick) -> Leaderboard {
+        let mut entries: HashMap<TeamId, LeaderboardEntry> = HashMap::new();
+        let results = self
+            .games
+            .values()
+            .filter_map(|record| record.score.map(|score| (record, score)))
+            .collect_vec();
+
+        for (record, (home_score, away_score)) in results.iter() {
+            for (team_id, team_name, own_score, other_score) in [
+                (
+                    record.home_team_id,
+                    &record.home_team_name,
+                    *home_score,
+                    *away_score,
+                ),
+                (
+                    record.away_team_id,
+                    &record.away_team_name,
+                    *away_score,
+                    *home_score,
+                ),
+            ] {
+                let entry = entries.entry(team_id).or_insert_with(|| LeaderboardEntry {
+                    team_id,
+                    team_name: team_name.clone(),
+                    ..Default::default()
+                });
+                entry.played += 1;
+                entry.points_for += own_score as u32;
+                entry.points_against += other_score as u32;
+                match own_score.cmp(&other_score) {
+                    std::cmp::Ordering::Greater => entry.wins += 1,
+                    std::cmp::Ordering::Less => entry.losses += 1,
+                    std::cmp::Ordering::Equal => entry.ties += 1,
+                }
+            }
+        }
+
+        // Prefer the latest team name, teams can be renamed between games.
+        for entry in entries.values_mut() {
+            if let Some(network_team) = self.teams.get(&entry.team_id) {
+                entry.team_name = network_team.team.name.clone();
+            }
+        }
+
+        Leaderboard {
+            entries: entries
+                .into_values()
+                .sorted_by(|a, b| {
+                    b.wins
+                        .cmp(&a.wins)
+                        .then(b.point_difference().cmp(&a.point_difference()))
+                        .then(b.points_for.cmp(&a.points_for))
+                })
+                .take(LEADERBOARD_SIZE)
+                .collect(),
+            teams_count: self.teams.len(),
+            games_count: results.len(),
+            updated_at: now,
+        }
+    }
+}
+
+/// Counters reported by the `metrics` admin command and logged periodically.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct RelayerMetrics {
+    pub started_at: Tick,
+    pub connected_peers: usize,
+    pub peak_connected_peers: usize,
+    pub total_connections: u64,
+    pub received: HashMap<String, TopicTraffic>,
+    pub sent: HashMap<String, TopicTraffic>,
+    pub rejected_messages: u64,
+    pub teams_count: usize,
+    pub games_count: usize,
+    pub banned_peers_count: usize,
+}
+
+impl RelayerMetrics {
+    pub fn bytes_in(&self) -> u64 {
+        self.received.values().map(|traffic| traffic.bytes).sum()
+    }
+
+    pub fn bytes_out(&self) -> u64 {
+        self.sent.values().map(|traffic| traffic.bytes).sum()
+    }
+
+    pub fn summary(&self) -> String {
+        format!(
+            "peers {} (peak {}), connections {}, in {} msg/{} B, out {} msg/{} B, rejected {}, teams {}, games {}",
+            self.connected_peers,
+            self.peak_connected_peers,
+            self.total_connections,
+            self.received.values().map(|traffic| traffic.messages).sum::<u64>(),
+            self.bytes_in(),
+            self.sent.values().map(|traffic| traffic.messages).sum::<u64>(),
+            self.bytes_out(),
+            self.rejected_messages,
+            self.teams_count,
+            self.games_count,
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum AdminCommand {
+    Help,
+    Metrics,
+    Peers,
+    Ban { peer_id: PeerId },
+    Unban { peer_id: PeerId },
+    Broadcast { message: String },
+    Quit,
+}
+
+impl FromStr for AdminCommand {
+    type Err = String;
+
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        let line = line.trim();
+        let (command, argument) = line.split_once(' ').unwrap_or((line, ""));
+        let argument = argument.trim();
+        let parse_peer_id = || {
+            PeerId::from_str(argument).map_err(|err| format!("Invalid peer id {}: {}", argument, err))
+        };
+        match command {
+            "help" => Ok(Self::Help),
+            "metrics" => Ok(Self::Metrics),
+            "peers" => Ok(Self::Peers),
+            "ban" => Ok(Self::Ban {
+                peer_id: parse_peer_id()?,
+            }),
+            "unban" => Ok(Self::Unban {
+                peer_id: parse_peer_id()?,
+            }),
+            "broadcast" if !argument.is_empty() => Ok(Self::Broadcast {
+                message: argument.to_string(),
+            }),
+            "broadcast" => Err("Usage: broadcast <message>".to_string()),
+            "quit" => Ok(Self::Quit),
+            _ => Err(format!("Unknown command {}. Type help for a list.", command)),
+        }
+    }
+}
+
+const ADMIN_HELP: &str = "Commands:
+  metrics             Print relayer metrics as json
+  peers               List connected peers
+  ban <peer_id>       Disconnect a peer and ignore it from now on
+  unban <peer_id>     Lift a ban
+  broadcast <message> Show a message to every connected player
+  quit                Save and stop the relayer";
 
 pub struct Relayer {
     pub running: bool,
-    network_handler: NetworkHandler,
+    pub(crate) network_handler: NetworkHandler,
+    pub(crate) state: RelayerState,
+    metrics: RelayerMetrics,
+    dirty: bool,
 }
 
 impl Relayer {
-    pub fn new() -> Self {
-        Self {
-            running: true,
-            network_handler: NetworkHandler::new(None)
-                .expect("Failed to initialize network handler"),
+    pub fn new(log_level: LevelFilter, network_config: &NetworkConfig) -> Self {
+        // Only our own logs by default, RUST_LOG can still enable the libp2p ones.
+        env_logger::Builder::from_env(
+            Env::default().default_filter_or(format!("rebels={}", log_level)),
+        )
+        .init();
+
+        let state = RelayerState::load().unwrap_or_else(|err| {
+            info!("Starting with an empty relayer state: {}", err);
+            RelayerState::default()
+        });
+        // Keep the same peer id across restarts, clients may be configured to dial it.
+        let local_key = load_or_generate_keypair(PERSISTED_RELAYER_KEYPAIR_FILENAME, false)
+            .expect("Failed to load relayer keypair");
+        Self::with_state(state, local_key, network_config)
+            .expect("Failed to initialize network handler")
+    }
+
+    pub fn with_state(
+        state: RelayerState,
+        local_key: identity::Keypair,
+        network_config: &NetworkConfig,
+    ) -> AppResult<Self> {
+        let mut network_handler = NetworkHandler::new(local_key, network_config)?;
+        for peer_id in state.banned_peers.iter() {
+            network_handler
+                .swarm
+                .behaviour_mut()
+                .gossipsub
+                .blacklist_peer(peer_id);
         }
+
+        Ok(Self {
+            running: true,
+            network_handler,
+            state,
+            metrics: RelayerMetrics {
+                started_at: Tick::now(),
+                ..Default::default()
+            },
+            dirty: false,
+        })
     }
 
     pub async fn run(&mut self) -> AppResult<()> {
-        println!("Starting relayer. Press Ctrl-C to exit.");
+        info!(
+            "Starting relayer {}. Type help for admin commands, Ctrl-C to exit.",
+            self.network_handler.swarm.local_peer_id()
+        );
+        let mut save_interval = tokio::time::interval(RELAYER_SAVE_INTERVAL);
+        let mut stdin = BufReader::new(tokio::io::stdin()).lines();
+        // Stdin is closed when running as a service, stop polling it.
+        let mut stdin_open = true;
         while self.running {
             select! {
                     swarm_event = self.network_handler.swarm.select_next_some() =>  {
-                        let result = self.handle_network_events(swarm_event);
-                        if result.is_err() {
-                            println!("Error handling network event: {:?}", result);
+                        if let Err(err) = self.handle_network_events(swarm_event) {
+                            warn!("Error handling network event: {}", err);
                         }
                 }
+                line = stdin.next_line(), if stdin_open => {
+                    match line {
+                        Ok(Some(line)) if line.trim().is_empty() => {}
+                        Ok(Some(line)) => match self.handle_admin_command(&line) {
+                            Ok(output) => println!("{}", output),
+                            Err(err) => println!("{}", err),
+                        },
+                        _ => stdin_open = false,
+                    }
+                }
+                _ = save_interval.tick() => {
+                    info!("Metrics: {}", self.metrics().summary());
+                    if let Err(err) = self.save() {
+                        warn!("Error saving relayer state: {}", err);
+                    }
+                }
+            }
+        }
+        self.save()
+    }
+
+    fn save(&mut self) -> AppResult<()> {
+        if self.dirty {
+            self.state.cleanup_games(Tick::now());
+            self.state.save()?;
+            self.dirty = false;
+        }
+        Ok(())
+    }
+
+    pub fn metrics(&self) -> RelayerMetrics {
+        let mut metrics = self.metrics.clone();
+        metrics.connected_peers = self.network_handler.swarm.connected_peers().count();
+        metrics.sent = self.network_handler.sent_traffic.clone();
+        metrics.teams_count = self.state.teams.len();
+        metrics.games_count = self.state.games.len();
+        metrics.banned_peers_count = self.state.banned_peers.len();
+        metrics
+    }
+
+    pub fn handle_admin_command(&mut self, line: &str) -> AppResult<String> {
+        let command = AdminCommand::from_str(line)?;
+        info!("Admin command: {:?}", command);
+        let output = match command {
+            AdminCommand::Help => ADMIN_HELP.to_string(),
+            AdminCommand::Metrics => serde_json::to_string_pretty(&self.metrics())?,
+            AdminCommand::Peers => self
+                .network_handler
+                .swarm
+                .connected_peers()
+                .map(|peer_id| match self.state.team_of_peer(*peer_id) {
+                    Some(network_team) => format!("{} {}", peer_id, network_team.team.name),
+                    None => peer_id.to_string(),
+                })
+                .join("\n"),
+            AdminCommand::Ban { peer_id } => {
+                self.state.ban_peer(peer_id);
+                self.network_handler
+                    .swarm
+                    .behaviour_mut()
+                    .gossipsub
+                    .blacklist_peer(&peer_id);
+                let _ = self.network_handler.swarm.disconnect_peer_id(peer_id);
+                self.dirty = true;
+                format!("Banned {}", peer_id)
+            }
+            AdminCommand::Unban { peer_id } => {
+                if !self.state.unban_peer(peer_id) {
+                    return Err(format!("Peer {} is not banned", peer_id).into());
+                }
+                self.network_handler
+                    .swarm
+                    .behaviour_mut()
+                    .gossipsub
+                    .remove_blacklisted_peer(&peer_id);
+                self.dirty = true;
+                format!("Unbanned {}", peer_id)
+            }
+            AdminCommand::Broadcast { message } => {
+                self.network_handler.send_seed_info(SeedInfo::new(
+                    self.network_handler.swarm.connected_peers().count(),
+                    Some(message),
+                ))?;
+                "Message sent".to_string()
             }
+            AdminCommand::Quit => {
+                self.running = false;
+                "Stopping relayer".to_string()
+            }
+        };
+        Ok(output)
+    }
+
+    fn send_directory(&mut self, peer_id: PeerId) -> AppResult<()> {
+        let directory = self
+            .state
+            .directory(Some(peer_id))
+            .into_iter()
+            .cloned()
+            .collect_vec();
+        debug!("Sending {} teams to {}", directory.len(), peer_id);
+        for network_team in directory.iter() {
+            self.network_handler.relay_team(network_team)?;
         }
         Ok(())
     }
 
-    pub fn handle_network_events(
-        &mut self,
-        network_event: SwarmEvent<gossipsub::Event, Void>,
-    ) -> AppResult<()> {
-        println!("Received network event: {:?}", network_event);
+    fn handle_message(&mut self, message: gossipsub::Message) -> AppResult<()> {
+        self.metrics
+            .received
+            .entry(message.topic.to_string())
+            .or_default()
+            .record(message.data.len());
+        let peer_id = message.source.ok_or("Message without source peer")?;
+        let envelope = Envelope::decode(&message.data)?;
+        match envelope.message_type {
+            MessageType::Team => {
+                let network_team = envelope.data::<NetworkTeam>()?;
+                if self.state.add_team(peer_id, network_team, Tick::now())? {
+                    self.dirty = true;
+                }
+            }
+            MessageType::Game => {
+                let game = envelope.data::<NetworkGame>()?;
+                if self.state.add_game(GameRecord::from_network_game(&game)) {
+                    self.dirty = true;
+                }
+            }
+            MessageType::GameChecksum => {
+                let checksum = envelope.data::<GameChecksum>()?;
+                if self.state.add_game_checksum(peer_id, &checksum) {
+                    info!("Recorded result of game {}", checksum.game_id);
+                    self.dirty = true;
+                    self.network_handler
+                        .send_leaderboard(&self.state.leaderboard(Tick::now()))?;
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    pub fn handle_network_events(&mut self, network_event: NetworkEvent) -> AppResult<()> {
+        debug!("Received network event: {:?}", network_event);
         match network_event {
-            SwarmEvent::Behaviour(gossipsub::Event::Subscribed { peer_id, topic }) => {
+            SwarmEvent::Behaviour(RebelsBehaviourEvent::Gossipsub(
+                gossipsub::Event::Subscribed { peer_id, topic },
+            )) => {
                 if topic == IdentTopic::new(SubscriptionTopic::SEED_INFO).hash() {
-                    println!("Sending info to {}", peer_id);
+                    debug!("Sending info to {}", peer_id);
                     self.network_handler.send_seed_info(SeedInfo::new(
                         self.network_handler.swarm.connected_peers().count(),
                         None,
                     ))?;
+                    self.network_handler
+                        .send_leaderboard(&self.state.leaderboard(Tick::now()))?;
+                }
+                if topic == IdentTopic::new(SubscriptionTopic::TEAM).hash() {
+                    self.send_directory(peer_id)?;
+                }
+            }
+            SwarmEvent::Behaviour(RebelsBehaviourEvent::Gossipsub(gossipsub::Event::Message {
+                message,
+                ..
+            })) => {
+                if let Err(err) = self.handle_message(message) {
+                    self.metrics.rejected_messages += 1;
+                    return Err(err);
                 }
             }
+            SwarmEvent::ConnectionEstablished { peer_id, .. } => {
+                if self.state.is_banned(peer_id) {
+                    info!("Disconnecting banned peer {}", peer_id);
+                    let _ = self.network_handler.swarm.disconnect_peer_id(peer_id);
+                    return Ok(());
+                }
+                info!("Connected to {}", peer_id);
+                self.metrics.total_connections += 1;
+                self.metrics.peak_connected_peers = self
+                    .metrics
+                    .peak_connected_peers
+                    .max(self.network_handler.swarm.connected_peers().count());
+            }
+            SwarmEvent::ConnectionClosed { peer_id, .. } => {
+                info!("Disconnected from {}", peer_id);
+            }
             _ => {}
         }
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{AdminCommand, RelayerState};
+    use crate::test_utils::test_world_and_game;
+    use crate::network::types::{GameChecksum, GameRecord, NetworkTeam};
+    use crate::types::{GameId, IdSystem, SystemTimeTick, Tick};
+    use crate::world::skill::MAX_SKILL;
+    use libp2p::PeerId;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_relayer_state() {
+        let (world, game) = test_world_and_game();
+        let now = Tick::now();
+        let mut state = RelayerState::default();
+        let mut teams = vec![];
+        for team_id in [game.home_team_in_game.team_id, game.away_team_in_game.team_id] {
+            let mut network_team = NetworkTeam::from_team_id(&world, &team_id).unwrap();
+            let peer_id = PeerId::random();
+            network_team.set_peer_id(peer_id);
+            assert!(state.add_team(peer_id, network_team.clone(), now).unwrap());
+            teams.push((peer_id, network_team));
+        }
+
+        // Teams are only accepted from their owner, and old versions are ignored.
+        let (home_peer_id, home_team) = teams[0].clone();
+        let (away_peer_id, away_team) = teams[1].clone();
+        assert!(state.add_team(away_peer_id, home_team.clone(), now).is_err());
+        let mut old_team = home_team.clone();
+        old_team.team.version = 0;
+        state.teams.get_mut(&home_team.team.id).unwrap().team.version = 1;
+        assert!(!state.add_team(home_peer_id, old_team, now).unwrap());
+
+        // Teams must pass the same validation as on clients.
+        let mut cheater = home_team.clone();
+        cheater.team.version += 1;
+        cheater.team.fuel = cheater.team.spaceship.tank() + 1;
+        assert!(state.add_team(home_peer_id, cheater, now).is_err());
+        let mut cheater = home_team.clone();
+        cheater.team.version += 1;
+        cheater.players[0].version += 1;
+        cheater.players[0].offense.long_range = MAX_SKILL;
+        cheater.players[0].defense.steal = MAX_SKILL;
+        assert!(state.add_team(home_peer_id, cheater, now).is_err());
+
+        let directory = state.directory(Some(home_peer_id));
+        assert_eq!(directory.len(), 1);
+        assert_eq!(directory[0].team.id, away_team.team.id);
+
+        let record = GameRecord {
+            game_id: GameId::new(),
+            home_team_id: home_team.team.id,
+            home_team_name: home_team.team.name.clone(),
+            home_peer_id: Some(home_peer_id),
+            away_team_id: away_team.team.id,
+            away_team_name: away_team.team.name.clone(),
+            away_peer_id: Some(away_peer_id),
+            starting_at: now,
+            score: None,
+        };
+        assert!(state.add_game(record.clone()));
+        assert!(!state.add_game(record.clone()));
+
+        let mut checksum = GameChecksum {
+            game_id: record.game_id,
+            from_action: 0,
+            action_checksums: vec![],
+            score: (80, 72),
+            ended: false,
+        };
+        assert!(!state.add_game_checksum(home_peer_id, &checksum));
+        checksum.ended = true;
+        // Only the teams playing the game can report its result.
+        assert!(!state.add_game_checksum(PeerId::random(), &checksum));
+        // The result is recorded only once both teams agree on it.
+        assert!(!state.add_game_checksum(home_peer_id, &checksum));
+        assert!(!state.add_game_checksum(home_peer_id, &checksum));
+        let mut tampered = checksum.clone();
+        tampered.score = (72, 80);
+        assert!(!state.add_game_checksum(away_peer_id, &tampered));
+        assert!(state.leaderboard(now).entries.is_empty());
+        assert!(!state.add_game_checksum(home_peer_id, &checksum));
+        let mut tampered = checksum.clone();
+        tampered.action_checksums = vec![0];
+        assert!(!state.add_game_checksum(away_peer_id, &tampered));
+        // Peers may have sent their previous checksums at different actions.
+        checksum.action_checksums = vec![1, 2, 3];
+        assert!(!state.add_game_checksum(home_peer_id, &checksum));
+        let mut window = checksum.clone();
+        window.from_action = 1;
+        window.action_checksums = vec![2, 3];
+        assert!(state.add_game_checksum(away_peer_id, &window));
+        assert!(!state.add_game_checksum(home_peer_id, &checksum));
+
+        let leaderboard = state.leaderboard(now);
+        assert_eq!(leaderboard.games_count, 1);
+        assert_eq!(leaderboard.teams_count, 2);
+        assert_eq!(leaderboard.entries[0].team_id, home_team.team.id);
+        assert_eq!(leaderboard.entries[0].wins, 1);
+        assert_eq!(leaderboard.entries[1].losses, 1);
+        assert_eq!(leaderboard.entries[1].point_difference(), -8);
+
+        // The state survives a restart.
+        let json = serde_json::to_string(&state).unwrap();
+        let restored = serde_json::from_str::<RelayerState>(&json).unwrap();
+        assert_eq!(restored.leaderboard(now), leaderboard);
+    }
+
+    #[test]
+    fn test_admin_commands() {
+        let (world, game) = test_world_and_game();
+        let team_id = game.home_team_in_game.team_id;
+        let mut network_team = NetworkTeam::from_team_id(&world, &team_id).unwrap();
+        let peer_id = PeerId::random();
+        network_team.set_peer_id(peer_id);
+
+        let command = AdminCommand::from_str(&format!("ban {}", peer_id)).unwrap();
+        assert_eq!(command, AdminCommand::Ban { peer_id });
+        assert_eq!(
+            AdminCommand::from_str("broadcast  Server restart in 5 minutes ").unwrap(),
+            AdminCommand::Broadcast {
+                message: "Server restart in 5 minutes".to_string()
+            }
+        );
+        assert!(AdminCommand::from_str("ban not-a-peer").is_err());
+        assert!(AdminCommand::from_str("broadcast").is_err());
+        assert!(AdminCommand::from_str("reboot").is_err());
+
+        // Banning a peer drops its teams and rejects new ones.
+        let mut state = RelayerState::default();
+        let now = Tick::now();
+        assert!(state.add_team(peer_id, network_team.clone(), now).unwrap());
+        state.ban_peer(peer_id);
+        assert!(state.team_of_peer(peer_id).is_none());
+        assert!(state.add_team(peer_id, network_team.clone(), now).is_err());
+        assert!(state.unban_peer(peer_id));
+        assert!(state.add_team(peer_id, network_team, now).unwrap());
+    }
+}
@@ -0,0 +1,287 @@
+use crate::engine::game::Game;
+use crate::engine::types::{GameStatsMap, TeamInGame};
+use crate::network::types::NetworkTeam;
+use crate::store::load_team_file;
+use crate::types::{AppResult, GameId, PlanetId, TeamId, Tick};
+use crate::world::constants::DEFAULT_PLANET_ID;
+use crate::world::utils::TEAM_DATA;
+use crate::world::world::World;
+use clap::ValueEnum;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::path::PathBuf;
+
+const SCORE_DISTRIBUTION_BUCKET: u16 = 10;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, ValueEnum)]
+pub enum OutputFormat {
+    #[default]
+    Json,
+    Csv,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub struct BoxScore {
+    pub points: f32,
+    pub made_2pt: f32,
+    pub attempted_2pt: f32,
+    pub made_3pt: f32,
+    pub attempted_3pt: f32,
+    pub made_ft: f32,
+    pub attempted_ft: f32,
+    pub offensive_rebounds: f32,
+    pub defensive_rebounds: f32,
+    pub assists: f32,
+    pub steals: f32,
+    pub blocks: f32,
+    pub turnovers: f32,
+    pub fouls: f32,
+}
+
+impl BoxScore {
+    fn add_stats(&mut self, stats: &GameStatsMap) {
+        for s in stats.values() {
+            self.points += s.points as f32;
+            self.made_2pt += s.made_2pt as f32;
+            self.attempted_2pt += s.attempted_2pt as f32;
+            self.made_3pt += s.made_3pt as f32;
+            self.attempted_3pt += s.attempted_3pt as f32;
+            self.made_ft += s.made_ft as f32;
+            self.attempted_ft += s.attempted_ft as f32;
+            self.offensive_rebounds += s.offensive_rebounds as f32;
+            self.defensive_rebounds += s.defensive_rebounds as f32;
+            self.assists += s.assists as f32;
+            self.steals += s.steals as f32;
+            self.blocks += s.blocks as f32;
+            self.turnovers += s.turnovers as f32;
+            self.fouls += s.fouls as f32;
+        }
+    }
+
+    fn averaged(&self, games: usize) -> Self {
+        let n = games.max(1) as f32;
+        Self {
+            points: self.points / n,
+            made_2pt: self.made_2pt / n,
+            attempted_2pt: self.attempted_2pt / n,
+            made_3pt: self.made_3pt / n,
+            attempted_3pt: self.attempted_3pt / n,
+            made_ft: self.made_ft / n,
+            attempted_ft: self.attempted_ft / n,
+            offensive_rebounds: self.offensive_rebounds / n,
+            defensive_rebounds: self.defensive_rebounds / n,
+            assists: self.assists / n,
+            steals: self.steals / n,
+            blocks: self.blocks / n,
+            turnovers: self.turnovers / n,
+            fouls: self.fouls / n,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct TeamReport {
+    pub team_id: TeamId,
+    pub name: String,
+    pub wins: usize,
+    pub win_rate: f32,
+    pub average_box_score: BoxScore,
+    // Number of games per score bucket, keyed by the lower bound of the bucket.
+    pub score_distribution: BTreeMap<u16, usize>,
+    #[serde(skip)]
+    total_box_score: BoxScore,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SimulationReport {
+    pub seed: u64,
+    pub games: usize,
+    pub planet: String,
+    pub ties: usize,
+    pub teams: [TeamReport; 2],
+}
+
+impl SimulationReport {
+    pub fn format(&self, format: OutputFormat) -> AppResult<String> {
+        match format {
+            OutputFormat::Json => Ok(serde_json::to_string_pretty(self)?),
+            OutputFormat::Csv => Ok(self.to_csv()),
+        }
+    }
+
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from(
+            "team_id,name,games,wins,win_rate,points,made_2pt,attempted_2pt,made_3pt,attempted_3pt,made_ft,attempted_ft,offensive_rebounds,defensive_rebounds,assists,steals,blocks,turnovers,fouls\n",
+        );
+        for team in self.teams.iter() {
+            let b = team.average_box_score;
+            let _ = writeln!(
+                csv,
+                "{},{},{},{},{:.3},{:.2},{:.2},{:.2},{:.2},{:.2},{:.2},{:.2},{:.2},{:.2},{:.2},{:.2},{:.2},{:.2},{:.2}",
+                team.team_id,
+                team.name,
+                self.games,
+                team.wins,
+                team.win_rate,
+                b.points,
+                b.made_2pt,
+                b.attempted_2pt,
+                b.made_3pt,
+                b.attempted_3pt,
+                b.made_ft,
+                b.attempted_ft,
+                b.offensive_rebounds,
+                b.defensive_rebounds,
+                b.assists,
+                b.steals,
+                b.blocks,
+                b.turnovers,
+                b.fouls
+            );
+        }
+        csv
+    }
+}
+
+pub struct Simulator {
+    world: World,
+    rng: ChaCha8Rng,
+    team_ids: [TeamId; 2],
+    planet_id: PlanetId,
+}
+
+impl Simulator {
+    pub fn new(
+        seed: Option<u64>,
+        team_files: Vec<PathBuf>,
+        planet_name: Option<String>,
+    ) -> AppResult<Self> {
+        if team_files.len() > 2 {
+            return Err("At most two teams can be loaded".into());
+        }
+
+        let mut world = World::new(seed);
+        let mut rng = ChaCha8Rng::seed_from_u64(world.seed);
+
+        let planet_id = match planet_name {
+            Some(name) => {
+                world
+                    .planets
+                    .values()
+                    .find(|planet| planet.name.to_lowercase() == name.to_lowercase())
+                    .ok_or(format!("Planet {} not found", name))?
+                    .id
+            }
+            None => DEFAULT_PLANET_ID.clone(),
+        };
+
+        let mut team_ids = vec![];
+        for path in team_files.iter() {
+            let NetworkTeam { team, players } = load_team_file(path)?;
+            team_ids.push(team.id);
+            for player in players {
+                world.players.insert(player.id, player);
+            }
+            world.teams.insert(team.id, team);
+        }
+
+        let names = &TEAM_DATA.as_ref().ok_or("Cannot load team data")?.names;
+        while team_ids.len() < 2 {
+            let (team_name, ship_name) = names[team_ids.len()].clone();
+            team_ids.push(world.generate_random_team(
+                &mut rng,
+                DEFAULT_PLANET_ID.clone(),
+                team_name,
+                ship_name,
+            )?);
+        }
+
+        Ok(Self {
+            world,
+            rng,
+            team_ids: [team_ids[0], team_ids[1]],
+            planet_id,
+        })
+    }
+
+    fn team_in_game(&self, team_id: TeamId) -> AppResult<TeamInGame> {
+        TeamInGame::from_team_id(team_id, &self.world.teams, &self.world.players)
+    }
+
+    pub fn run(&mut self, games: usize) -> AppResult<SimulationReport> {
+        let planet = self.world.get_planet_or_err(self.planet_id)?.clone();
+        let mut report = SimulationReport {
+            seed: self.world.seed,
+            games,
+            planet: planet.name.clone(),
+            ..Default::default()
+        };
+        for (idx, team_id) in self.team_ids.iter().enumerate() {
+            report.teams[idx].team_id = *team_id;
+            report.teams[idx].name = self.world.get_team_or_err(*team_id)?.name.clone();
+        }
+
+        for n in 0..games {
+            // Teams alternate playing at home.
+            let (home_idx, away_idx) = if n % 2 == 0 { (0, 1) } else { (1, 0) };
+            let mut game = Game::new(
+                GameId::from_bytes(self.rng.gen()),
+                self.team_in_game(self.team_ids[home_idx])?,
+                self.team_in_game(self.team_ids[away_idx])?,
+                n as Tick,
+                &planet,
+            );
+            while !game.timer.has_ended() {
+                game.tick();
+            }
+
+            let (home_score, away_score) = game.get_score();
+            for (idx, score, stats) in [
+                (home_idx, home_score, &game.home_team_in_game.stats),
+                (away_idx, away_score, &game.away_team_in_game.stats),
+            ] {
+                let team = &mut report.teams[idx];
+                team.total_box_score.add_stats(stats);
+                *team
+                    .score_distribution
+                    .entry(score / SCORE_DISTRIBUTION_BUCKET * SCORE_DISTRIBUTION_BUCKET)
+                    .or_insert(0) += 1;
+            }
+
+            if home_score > away_score {
+                report.teams[home_idx].wins += 1;
+            } else if away_score > home_score {
+                report.teams[away_idx].wins += 1;
+            } else {
+                report.ties += 1;
+            }
+        }
+
+        for team in report.teams.iter_mut() {
+            team.win_rate = team.wins as f32 / games.max(1) as f32;
+            team.average_box_score = team.total_box_score.averaged(games);
+        }
+
+        Ok(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Simulator;
+
+    #[test]
+    fn test_simulator() {
+        let mut simulator = Simulator::new(Some(7), vec![], None).unwrap();
+        let report = simulator.run(4).unwrap();
+        assert_eq!(report.games, 4);
+        assert_eq!(report.teams[0].wins + report.teams[1].wins + report.ties, 4);
+        for team in report.teams.iter() {
+            assert_eq!(team.score_distribution.values().sum::<usize>(), 4);
+        }
+        assert_eq!(report.to_csv().lines().count(), 3);
+    }
+}
@@ -1,62 +1,533 @@
-use crate::{types::AppResult, world::world::World};
+use crate::{
+    engine::game::Game,
+    network::types::NetworkTeam,
+    types::{AppResult, GameId, SystemTimeTick, Tick, MINUTES},
+    world::{
+        migrations::{GAME_MIGRATIONS, TEAM_MIGRATIONS, WORLD_MIGRATIONS},
+        world::World,
+    },
+};
 use directories;
 use include_dir::{include_dir, Dir};
-use serde::{Deserialize, Serialize};
-use std::{error::Error, fs::File, path::PathBuf};
+use libp2p::identity;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::Value;
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use std::{
+    error::Error,
+    fs::File,
+    io::{BufRead, BufReader, BufWriter, Write},
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        RwLock,
+    },
+};
 
 pub static ASSETS_DIR: Dir = include_dir!("$CARGO_MANIFEST_DIR/assets/");
 pub static PERSISTED_WORLD_FILENAME: &str = "world.json";
 pub static PERSISTED_GAMES_PREFIX: &str = "game_";
+pub static PERSISTED_DESYNC_PREFIX: &str = "desync_";
+pub static PERSISTED_KEYPAIR_FILENAME: &str = "keypair";
+pub static PERSISTED_RELAYER_FILENAME: &str = "relayer.json";
+pub static PERSISTED_RELAYER_KEYPAIR_FILENAME: &str = "relayer_keypair";
+pub static DEFAULT_PROFILE: &str = "default";
+static PROFILES_DIRNAME: &str = "profiles";
+const MAX_PROFILE_NAME_LENGTH: usize = 32;
+static BACKUP_SUFFIX: &str = ".back";
+static TEMP_SUFFIX: &str = ".tmp";
+// Number of world backups kept, older ones are deleted.
+pub const MAX_WORLD_BACKUPS: usize = 5;
+// A new backup is written on quit, or when saving if the newest one is older than this.
+pub const WORLD_BACKUP_INTERVAL: Tick = 10 * MINUTES;
 
-fn store_path(filename: &str) -> Result<PathBuf, Box<dyn Error>> {
+// Profile whose files are read and written, the default one when unset.
+static PROFILE: RwLock<Option<String>> = RwLock::new(None);
+// Versioned saves are gzipped when set. Compressed files are recognized when loading either way.
+static COMPRESS_SAVES: AtomicBool = AtomicBool::new(false);
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+fn root_dir() -> Result<PathBuf, Box<dyn Error>> {
     let dirs = directories::ProjectDirs::from("org", "frittura", "rebels")
         .ok_or("Failed to get directories")?;
-    let config_dirs = dirs.config_dir();
-    if !config_dirs.exists() {
-        std::fs::create_dir_all(config_dirs)?;
+    Ok(dirs.config_dir().to_path_buf())
+}
+
+/// The default profile lives in the root directory, so that saves from before profiles still load.
+/// Other profiles have their own world, past games and keypair in a subdirectory.
+fn store_dir() -> Result<PathBuf, Box<dyn Error>> {
+    let profile = current_profile();
+    let dir = if profile == DEFAULT_PROFILE {
+        root_dir()?
+    } else {
+        root_dir()?.join(PROFILES_DIRNAME).join(profile)
+    };
+    if !dir.exists() {
+        std::fs::create_dir_all(&dir)?;
+    }
+    Ok(dir)
+}
+
+fn validate_profile_name(name: &str) -> AppResult<()> {
+    if name.is_empty() || name.len() > MAX_PROFILE_NAME_LENGTH {
+        return Err(format!(
+            "Profile name must have between 1 and {} characters",
+            MAX_PROFILE_NAME_LENGTH
+        )
+        .into());
+    }
+    if !name
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(format!(
+            "Invalid profile name {}, use only letters, digits, '-' and '_'",
+            name
+        )
+        .into());
+    }
+    Ok(())
+}
+
+pub fn current_profile() -> String {
+    PROFILE
+        .read()
+        .ok()
+        .and_then(|profile| profile.clone())
+        .unwrap_or(DEFAULT_PROFILE.to_string())
+}
+
+/// Switches the profile used by the store. The profile is created on first use.
+pub fn set_profile(name: &str) -> AppResult<()> {
+    validate_profile_name(name)?;
+    let mut profile = PROFILE.write().map_err(|_| "Failed to set profile")?;
+    *profile = Some(name.to_string());
+    Ok(())
+}
+
+/// Lists the default profile and the ones created so far, sorted by name.
+pub fn profiles() -> AppResult<Vec<String>> {
+    let mut profiles = vec![];
+    let profiles_dir = root_dir()?.join(PROFILES_DIRNAME);
+    if profiles_dir.exists() {
+        for entry in std::fs::read_dir(profiles_dir)? {
+            let entry = entry?;
+            let name = entry.file_name().to_string_lossy().to_string();
+            if entry.file_type()?.is_dir()
+                && name != DEFAULT_PROFILE
+                && validate_profile_name(&name).is_ok()
+            {
+                profiles.push(name);
+            }
+        }
+    }
+    profiles.sort();
+    profiles.insert(0, DEFAULT_PROFILE.to_string());
+    Ok(profiles)
+}
+
+pub fn set_compression(enabled: bool) {
+    COMPRESS_SAVES.store(enabled, Ordering::Relaxed);
+}
+
+fn store_path(filename: &str) -> Result<PathBuf, Box<dyn Error>> {
+    Ok(store_dir()?.join(filename))
+}
+
+// Upgrades saved data from one schema version to the next.
+pub type Migration = fn(&mut Value) -> AppResult<()>;
+
+#[derive(Serialize)]
+struct SaveFile<'a, T> {
+    version: u32,
+    data: &'a T,
+}
+
+/// Saves data together with its schema version, which is the number of known migrations.
+pub fn save_versioned<T: Serialize>(
+    filename: &str,
+    data: &T,
+    migrations: &[Migration],
+) -> AppResult<()> {
+    write_json(
+        &store_path(filename)?,
+        &SaveFile {
+            version: migrations.len() as u32,
+            data,
+        },
+        COMPRESS_SAVES.load(Ordering::Relaxed),
+    )
+}
+
+/// Loads data saved with `save_versioned`, migrating it to the current schema.
+pub fn load_versioned<T: DeserializeOwned>(
+    filename: &str,
+    migrations: &[Migration],
+) -> AppResult<T> {
+    let value: Value = load_from_json(filename)?;
+    migrate(value, migrations)
+}
+
+/// Files saved before versioning hold the data directly and are treated as version 0.
+fn split_version(value: Value) -> AppResult<(usize, Value)> {
+    match value {
+        Value::Object(mut map) if map.len() == 2 && map.contains_key("data") => {
+            let version = map
+                .get("version")
+                .and_then(|v| v.as_u64())
+                .ok_or("Invalid save version")? as usize;
+            Ok((version, map.remove("data").unwrap()))
+        }
+        data => Ok((0, data)),
     }
-    let path = config_dirs.join(filename);
-    Ok(path)
+}
+
+pub fn migrate<T: DeserializeOwned>(value: Value, migrations: &[Migration]) -> AppResult<T> {
+    let (version, mut data) = split_version(value)?;
+
+    if version > migrations.len() {
+        return Err(format!(
+            "Save version {} is newer than the supported version {}, please update the game",
+            version,
+            migrations.len()
+        )
+        .into());
+    }
+    for (from_version, migration) in migrations.iter().enumerate().skip(version) {
+        migration(&mut data)
+            .map_err(|e| format!("Failed to migrate save from version {}: {}", from_version, e))?;
+    }
+    Ok(serde_json::from_value(data)?)
 }
 
 pub fn save_world(world: &World, with_backup: bool) -> AppResult<()> {
     let stored_world = world.to_store();
-    save_to_json(PERSISTED_WORLD_FILENAME, &stored_world)?;
-    if with_backup {
-        let backup_filename = format!("{}.back", PERSISTED_WORLD_FILENAME);
-        save_to_json(&backup_filename, &stored_world)?;
+    save_versioned(PERSISTED_WORLD_FILENAME, &stored_world, WORLD_MIGRATIONS)?;
+
+    let now = Tick::now();
+    let backup_is_due = world_backups()?
+        .first()
+        .map(|(timestamp, _)| now.saturating_sub(*timestamp) >= WORLD_BACKUP_INTERVAL)
+        .unwrap_or(true);
+    if with_backup || backup_is_due {
+        backup_world(now)?;
+    }
+    Ok(())
+}
+
+pub fn load_world() -> AppResult<World> {
+    load_versioned(PERSISTED_WORLD_FILENAME, WORLD_MIGRATIONS).map(World::from_store)
+}
+
+fn backup_filename(timestamp: Tick) -> String {
+    format!("{}.{}{}", PERSISTED_WORLD_FILENAME, timestamp, BACKUP_SUFFIX)
+}
+
+/// Returns the timestamp of a world backup from its filename.
+fn backup_timestamp(filename: &str) -> Option<Tick> {
+    filename
+        .strip_prefix(PERSISTED_WORLD_FILENAME)?
+        .strip_prefix('.')?
+        .strip_suffix(BACKUP_SUFFIX)?
+        .parse()
+        .ok()
+}
+
+fn copy_file(from: &str, to: &str) -> AppResult<()> {
+    let temp_path = store_path(&format!("{}{}", to, TEMP_SUFFIX))?;
+    std::fs::copy(store_path(from)?, &temp_path)?;
+    std::fs::rename(temp_path, store_path(to)?)?;
+    Ok(())
+}
+
+/// Copies the current world save to a new backup and deletes the oldest backups.
+fn backup_world(timestamp: Tick) -> AppResult<()> {
+    copy_file(PERSISTED_WORLD_FILENAME, &backup_filename(timestamp))?;
+
+    for (_, filename) in world_backups()?.iter().skip(MAX_WORLD_BACKUPS) {
+        std::fs::remove_file(store_path(filename)?)?;
     }
     Ok(())
 }
 
+/// Lists the world backups with their timestamps, newest first.
+/// Backups written before timestamps were added are dated by their modification time.
+pub fn world_backups() -> AppResult<Vec<(Tick, String)>> {
+    let legacy_filename = format!("{}{}", PERSISTED_WORLD_FILENAME, BACKUP_SUFFIX);
+    let mut backups = vec![];
+    for entry in std::fs::read_dir(store_dir()?)? {
+        let entry = entry?;
+        let filename = entry.file_name().to_string_lossy().to_string();
+        if let Some(timestamp) = backup_timestamp(&filename) {
+            backups.push((timestamp, filename));
+        } else if filename == legacy_filename {
+            let timestamp = Tick::from_system_time(entry.metadata()?.modified()?);
+            backups.push((timestamp, filename));
+        }
+    }
+    backups.sort_by(|a, b| b.cmp(a));
+    Ok(backups)
+}
+
+/// Returns the newest backup that can be loaded, to recover from a corrupted save.
+pub fn newest_valid_world_backup() -> Option<(Tick, String)> {
+    world_backups()
+        .ok()?
+        .into_iter()
+        .find(|(_, filename)| load_world_backup(filename).is_ok())
+}
+
+pub fn load_world_backup(filename: &str) -> AppResult<World> {
+    load_versioned(filename, WORLD_MIGRATIONS).map(World::from_store)
+}
+
+/// Replaces the world save with a backup. The corrupted save is kept aside for inspection.
+pub fn restore_world_backup(filename: &str) -> AppResult<World> {
+    let world = load_world_backup(filename)?;
+    if world_exists() {
+        std::fs::rename(
+            store_path(PERSISTED_WORLD_FILENAME)?,
+            store_path(&format!("{}.corrupted", PERSISTED_WORLD_FILENAME))?,
+        )?;
+    }
+    copy_file(filename, PERSISTED_WORLD_FILENAME)?;
+    Ok(world)
+}
+
 pub fn get_world_size() -> AppResult<u64> {
     let size = file_data(PERSISTED_WORLD_FILENAME)?.len();
     // let file_size = File::open(store_path(filename)?)?.metadata()?.len();
     Ok(size)
 }
 
+#[derive(Debug, Default)]
+pub struct StoreStats {
+    pub profile: String,
+    pub world_file_size: u64,
+    pub world_data_size: u64,
+    pub compressed: bool,
+    // Number of entities and serialized size, per entity type.
+    pub entities: Vec<(&'static str, usize, u64)>,
+    pub game_files: (usize, u64),
+    pub backups: (usize, u64),
+}
+
+impl StoreStats {
+    pub fn format(&self) -> String {
+        let mut lines = vec![
+            format!("Profile: {}", self.profile),
+            format!(
+                "World save: {} on disk ({})",
+                format_size(self.world_file_size),
+                if self.compressed {
+                    format!("compressed from {}", format_size(self.world_data_size))
+                } else {
+                    "not compressed".to_string()
+                }
+            ),
+        ];
+        for (name, count, size) in self.entities.iter() {
+            let share = size * 100 / self.world_data_size.max(1);
+            lines.push(format!(
+                "  {:<12} {:>6} {:>10} {:>4}%",
+                name,
+                count,
+                format_size(*size),
+                share
+            ));
+        }
+        lines.push(format!(
+            "Past games: {} files, {}",
+            self.game_files.0,
+            format_size(self.game_files.1)
+        ));
+        lines.push(format!(
+            "World backups: {} files, {}",
+            self.backups.0,
+            format_size(self.backups.1)
+        ));
+        lines.join("\n")
+    }
+}
+
+fn format_size(bytes: u64) -> String {
+    format!("{:.1} KB", bytes as f64 / 1024.0)
+}
+
+/// Breaks down the world save of the current profile by entity type.
+pub fn store_stats() -> AppResult<StoreStats> {
+    let path = store_path(PERSISTED_WORLD_FILENAME)?;
+    let mut reader = BufReader::new(File::open(&path)?);
+    let compressed = reader.fill_buf()?.starts_with(&GZIP_MAGIC);
+    let (_, world) = split_version(read_json(&path)?)?;
+    let serialized_size = |value: &Value| serde_json::to_string(value).map(|s| s.len() as u64);
+
+    let mut entities = vec![];
+    for (name, key) in [
+        ("teams", "teams"),
+        ("planets", "planets"),
+        ("games", "games"),
+        ("past games", "past_games"),
+        ("leagues", "leagues"),
+    ] {
+        let Some(map) = world.get(key).and_then(|v| v.as_object()) else {
+            continue;
+        };
+        entities.push((name, map.len(), serialized_size(&world[key])?));
+    }
+    // Free agents are split from players in a team, since every planet has its own.
+    if let Some(players) = world.get("players").and_then(|v| v.as_object()) {
+        let (free_agents, team_players): (Vec<&Value>, Vec<&Value>) = players
+            .values()
+            .partition(|player| player.get("team").map(|t| t.is_null()).unwrap_or(true));
+        for (name, players) in [("players", team_players), ("free agents", free_agents)] {
+            let size = players
+                .iter()
+                .map(|player| serialized_size(player))
+                .sum::<Result<u64, _>>()?;
+            entities.push((name, players.len(), size));
+        }
+    }
+    let world_data_size = serialized_size(&world)?;
+
+    let mut game_files = (0, 0);
+    for entry in std::fs::read_dir(store_dir()?)? {
+        let entry = entry?;
+        if entry
+            .file_name()
+            .to_string_lossy()
+            .starts_with(PERSISTED_GAMES_PREFIX)
+        {
+            game_files.0 += 1;
+            game_files.1 += entry.metadata()?.len();
+        }
+    }
+    let mut backups = (0, 0);
+    for (_, filename) in world_backups()? {
+        backups.0 += 1;
+        backups.1 += file_data(&filename)?.len();
+    }
+
+    Ok(StoreStats {
+        profile: current_profile(),
+        world_file_size: file_data(PERSISTED_WORLD_FILENAME)?.len(),
+        world_data_size,
+        compressed,
+        entities,
+        game_files,
+        backups,
+    })
+}
+
 pub fn save_to_json<T: Serialize>(filename: &str, data: &T) -> AppResult<()> {
-    let file = File::create(store_path(filename)?)?;
+    write_json(&store_path(filename)?, data, false)
+}
+
+fn write_json<T: Serialize>(path: &Path, data: &T, compress: bool) -> AppResult<()> {
+    // Write to a temporary file and rename it, so that a crash never leaves a truncated file.
+    let mut temp_path = path.as_os_str().to_owned();
+    temp_path.push(TEMP_SUFFIX);
+    let file = File::create(&temp_path)?;
     assert!(file.metadata()?.is_file());
-    let buffer = std::io::BufWriter::new(file);
-    serde_json::to_writer(buffer, data)?;
+    let mut buffer = BufWriter::new(file);
+    if compress {
+        let mut encoder = GzEncoder::new(&mut buffer, Compression::default());
+        serde_json::to_writer(&mut encoder, data)?;
+        encoder.finish()?;
+    } else {
+        serde_json::to_writer(&mut buffer, data)?;
+    }
+    buffer.flush()?;
+    buffer.get_ref().sync_all()?;
+    std::fs::rename(temp_path, path)?;
     Ok(())
 }
 
 pub fn load_from_json<T: for<'a> Deserialize<'a>>(filename: &str) -> AppResult<T> {
-    let file = File::open(store_path(filename)?)?;
-    let data: T = serde_json::from_reader(file)?;
-    Ok(data)
+    read_json(&store_path(filename)?)
+}
+
+fn read_json<T: DeserializeOwned>(path: &Path) -> AppResult<T> {
+    let mut reader = BufReader::new(File::open(path)?);
+    if reader.fill_buf()?.starts_with(&GZIP_MAGIC) {
+        Ok(serde_json::from_reader(GzDecoder::new(reader))?)
+    } else {
+        Ok(serde_json::from_reader(reader)?)
+    }
 }
 
+pub fn save_game(game: &Game) -> AppResult<()> {
+    save_versioned(
+        format!("{}{}.json", PERSISTED_GAMES_PREFIX, game.id).as_str(),
+        &game.compacted(),
+        GAME_MIGRATIONS,
+    )
+}
+
+pub fn load_game(game_id: GameId) -> AppResult<Game> {
+    load_versioned(
+        format!("{}{}.json", PERSISTED_GAMES_PREFIX, game_id).as_str(),
+        GAME_MIGRATIONS,
+    )
+    .map(Game::expanded)
+}
+
+/// Writes a team to a file that can be imported into another world, for example on another machine.
+pub fn save_team_file(network_team: &NetworkTeam, path: &Path) -> AppResult<()> {
+    write_json(
+        path,
+        &SaveFile {
+            version: TEAM_MIGRATIONS.len() as u32,
+            data: network_team,
+        },
+        false,
+    )
+}
+
+pub fn load_team_file(path: &Path) -> AppResult<NetworkTeam> {
+    let value: Value =
+        read_json(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    migrate(value, TEAM_MIGRATIONS)
+}
+
+pub fn save_keypair(filename: &str, keypair: &identity::Keypair) -> AppResult<()> {
+    let path = store_path(filename)?;
+    std::fs::write(&path, keypair.to_protobuf_encoding()?)?;
+    // The keypair is our network identity, keep it private.
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600))?;
+    }
+    Ok(())
+}
+
+pub fn load_keypair(filename: &str) -> AppResult<identity::Keypair> {
+    let bytes = std::fs::read(store_path(filename)?)?;
+    Ok(identity::Keypair::from_protobuf_encoding(&bytes)?)
+}
+
+/// Loads the persisted keypair so that our peer id is stable across restarts.
+/// A new keypair is generated and stored if none exists or if rotate is set.
+pub fn load_or_generate_keypair(filename: &str, rotate: bool) -> AppResult<identity::Keypair> {
+    if !rotate {
+        if let Ok(keypair) = load_keypair(filename) {
+            return Ok(keypair);
+        }
+    }
+    let keypair = identity::Keypair::generate_ed25519();
+    save_keypair(filename, &keypair)?;
+    Ok(keypair)
+}
+
+/// Deletes the files of the current profile, other profiles are left untouched.
 pub fn reset() -> AppResult<()> {
-    let dirs = directories::ProjectDirs::from("org", "frittura", "rebels")
-        .ok_or("Failed to get directories")?;
-    let config_dirs = dirs.config_dir();
-    if config_dirs.exists() {
-        std::fs::remove_dir_all(config_dirs)?;
+    for entry in std::fs::read_dir(store_dir()?)? {
+        let entry = entry?;
+        if entry.file_type()?.is_file() {
+            std::fs::remove_file(entry.path())?;
+        }
     }
-    std::fs::create_dir_all(config_dirs)?;
     Ok(())
 }
 
@@ -101,5 +572,33 @@ mod tests {
         let world = World::new(None);
         let result = super::save_to_json("test", &world);
         assert!(result.is_ok());
+        // The temporary file is renamed over the save.
+        assert!(!super::store_path("test.tmp").unwrap().exists());
+        let loaded: World = super::load_from_json("test").unwrap();
+        assert_eq!(loaded.seed, world.seed);
+
+        // Compressed saves are recognized when loading.
+        super::write_json(&super::store_path("test").unwrap(), &world, true).unwrap();
+        let loaded: World = super::load_from_json("test").unwrap();
+        assert_eq!(loaded.seed, world.seed);
+    }
+
+    #[test]
+    fn test_profile_name() {
+        assert!(super::validate_profile_name("test-crew_2").is_ok());
+        assert!(super::validate_profile_name("").is_err());
+        assert!(super::validate_profile_name("../main").is_err());
+        assert!(super::validate_profile_name("main crew").is_err());
+        assert!(super::validate_profile_name(&"a".repeat(33)).is_err());
+    }
+
+    #[test]
+    fn test_backup_filename() {
+        let filename = super::backup_filename(1700000000000);
+        assert_eq!(filename, "world.json.1700000000000.back");
+        assert_eq!(super::backup_timestamp(&filename), Some(1700000000000));
+        assert_eq!(super::backup_timestamp("world.json.back"), None);
+        assert_eq!(super::backup_timestamp(&format!("{}.tmp", filename)), None);
+        assert_eq!(super::backup_timestamp("game_1700000000000.back"), None);
     }
 }
@@ -0,0 +1,35 @@
+//! Fixtures shared by the unit tests of the different modules.
+
+use crate::engine::{game::Game, types::TeamInGame};
+use crate::types::{GameId, IdSystem, SystemTimeTick, Tick};
+use crate::world::{constants::DEFAULT_PLANET_ID, world::World};
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+/// A world with two freshly generated teams on the default planet and a game between them.
+pub fn test_world_and_game() -> (World, Game) {
+    let mut world = World::new(None);
+    let rng = &mut ChaCha8Rng::seed_from_u64(world.seed);
+    let mut team_ids = vec![];
+    for (team_name, ship_name) in [("Testen", "Tosten"), ("Holalo", "Halley")] {
+        team_ids.push(
+            world
+                .generate_random_team(
+                    rng,
+                    DEFAULT_PLANET_ID.clone(),
+                    team_name.to_string(),
+                    ship_name.to_string(),
+                )
+                .unwrap(),
+        );
+    }
+
+    let game = Game::new(
+        GameId::new(),
+        TeamInGame::from_team_id(team_ids[0], &world.teams, &world.players).unwrap(),
+        TeamInGame::from_team_id(team_ids[1], &world.teams, &world.players).unwrap(),
+        Tick::now(),
+        &world.get_planet(DEFAULT_PLANET_ID.clone()).unwrap(),
+    );
+    (world, game)
+}
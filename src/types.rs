@@ -4,7 +4,7 @@ use crate::{
         game::{Game, GameSummary},
         types::GameStatsMap,
     },
-    world::{planet::Planet, player::Player, team::Team},
+    world::{league::League, planet::Planet, player::Player, team::Team},
 };
 use chrono::{prelude::DateTime, Datelike, Local, Timelike};
 use itertools::Itertools;
@@ -57,6 +57,7 @@ pub type TeamMap = HashMap<TeamId, Team>;
 pub type PlanetMap = HashMap<PlanetId, Planet>;
 pub type GameMap = HashMap<GameId, Game>;
 pub type GameSummaryMap = HashMap<GameId, GameSummary>;
+pub type LeagueMap = HashMap<PlanetId, League>;
 
 pub trait SortablePlayerMap {
     fn by_position(&self, stats: &GameStatsMap) -> Vec<&Player>;
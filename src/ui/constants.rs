@@ -20,6 +20,7 @@ impl UiKey {
     pub const TRAINING_FOCUS: KeyCode = KeyCode::Char('t');
     pub const AUTO_ASSIGN: KeyCode = KeyCode::Char('a');
     pub const SET_OFFENSE_TACTIC: KeyCode = KeyCode::Char('o');
+    pub const SET_DEFENSE_TACTIC: KeyCode = KeyCode::Char('x');
     pub const CYCLE_FILTER: KeyCode = KeyCode::Char('=');
     pub const HIRE_FIRE: KeyCode = KeyCode::Char('s');
     pub const LOCK_PLAYER: KeyCode = KeyCode::Char('l');
@@ -29,6 +30,14 @@ impl UiKey {
     pub const SET_PILOT: KeyCode = KeyCode::Char('e');
     pub const PITCH_VIEW: KeyCode = KeyCode::Char('v');
     pub const TRAVEL: KeyCode = KeyCode::Char('t');
+    pub const BUY_FUEL: KeyCode = KeyCode::Char('f');
+    pub const JOIN_LEAGUES: KeyCode = KeyCode::Char('j');
+    pub const REPLAY_PAUSE: KeyCode = KeyCode::Char('p');
+    pub const REPLAY_SLOWER: KeyCode = KeyCode::Char('-');
+    pub const REPLAY_FASTER: KeyCode = KeyCode::Char('+');
+    pub const REPLAY_BACKWARD: KeyCode = KeyCode::Char(',');
+    pub const REPLAY_FORWARD: KeyCode = KeyCode::Char('.');
+    pub const REPLAY_STOP: KeyCode = KeyCode::Char('q');
 }
 pub trait PrintableKeyCode {
     fn to_string(&self) -> String;
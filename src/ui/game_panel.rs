@@ -10,7 +10,7 @@ use super::{
     widgets::{default_block, selectable_list, DOWN_ARROW_SPAN, SWITCH_ARROW_SPAN, UP_ARROW_SPAN},
 };
 use crate::engine::constants::MAX_TIREDNESS;
-use crate::types::AppResult;
+use crate::types::{AppResult, SystemTimeTick, Tick, SECONDS};
 use crate::world::planet::PlanetType;
 use crate::{
     engine::{
@@ -54,6 +54,61 @@ enum PitchViewFilter {
     Fourth,
 }
 
+const REPLAY_DEFAULT_SPEED: u16 = 8;
+const REPLAY_MAX_SPEED: u16 = 64;
+const REPLAY_SEEK_STEP: u16 = 60;
+
+#[derive(Debug)]
+struct Replay {
+    start: Game,
+    game: Game,
+    paused: bool,
+    speed: u16,
+    last_update: Tick,
+    // Milliseconds of game time accumulated since the last game tick.
+    progress: Tick,
+}
+
+impl Replay {
+    fn new(game: &Game) -> Self {
+        let start = game.rewound();
+        let mut game = start.clone();
+        // Tick once so that the replay does not show the pre-game countdown.
+        game.tick();
+        Self {
+            start,
+            game,
+            paused: false,
+            speed: REPLAY_DEFAULT_SPEED,
+            last_update: Tick::now(),
+            progress: 0,
+        }
+    }
+
+    fn update(&mut self) {
+        let now = Tick::now();
+        if !self.paused {
+            self.progress += (now - self.last_update) * self.speed as Tick;
+            while self.progress >= SECONDS && !self.game.timer.has_ended() {
+                self.game.tick();
+                self.progress -= SECONDS;
+            }
+        }
+        self.last_update = now;
+    }
+
+    fn seek(&mut self, target: Timer) {
+        // Games can only be played forward, so seeking backward replays from the start.
+        if target.value < self.game.timer.value {
+            self.game = self.start.clone();
+        }
+        while self.game.timer.value < target.value.max(1) && !self.game.timer.has_ended() {
+            self.game.tick();
+        }
+        self.progress = 0;
+    }
+}
+
 #[derive(Debug, Default)]
 pub struct GamePanel {
     pub index: usize,
@@ -64,6 +119,7 @@ pub struct GamePanel {
     debug_mode: bool,
     action_results: Vec<ActionOutput>,
     tick: usize,
+    replay: Option<Replay>,
     callback_registry: Rc<RefCell<CallbackRegistry>>,
     gif_map: Rc<RefCell<GifMap>>,
 }
@@ -80,7 +136,15 @@ impl GamePanel {
         }
     }
 
-    fn selected_game<'a>(&self, world: &'a World) -> Option<&'a Game> {
+    pub fn start_replay(&mut self, game: &Game) {
+        self.replay = Some(Replay::new(game));
+        self.commentary_index = 0;
+    }
+
+    fn selected_game<'a>(&'a self, world: &'a World) -> Option<&'a Game> {
+        if let Some(replay) = self.replay.as_ref() {
+            return Some(&replay.game);
+        }
         if self.index >= self.games.len() {
             return None;
         }
@@ -109,6 +173,30 @@ impl GamePanel {
     }
 
     fn build_game_list(&mut self, frame: &mut Frame, world: &World, area: Rect) {
+        if let Some(replay) = self.replay.as_ref() {
+            let game = &replay.game;
+            let (home_score, away_score) = game.get_score();
+            let status = if replay.paused {
+                "paused".to_string()
+            } else {
+                format!("x{}", replay.speed)
+            };
+            let options = vec![(
+                format!(
+                    "{:>12} {:>3}-{:<3} {:<12}",
+                    game.home_team_in_game.name, home_score, away_score, game.away_team_in_game.name
+                ),
+                UiStyle::OWN_TEAM,
+            )];
+            let list = selectable_list(options, &self.callback_registry);
+            frame.render_stateful_widget(
+                list.block(default_block().title(format!("Replay {}", status))),
+                area,
+                &mut ClickableListState::default().with_selected(Some(0)),
+            );
+            return;
+        }
+
         let options = self
             .games
             .iter()
@@ -199,11 +287,24 @@ impl GamePanel {
             ])
             .split(top_split[2]);
 
+        let gravity_line = match game.gravity_bonus() {
+            0 => format!("Gravity {}: standard conditions", game.gravity),
+            _ => format!(
+                "Gravity {}: jump {:+}, long range {:+}, fatigue x{:.2}",
+                game.gravity,
+                game.gravity_bonus(),
+                -game.long_range_malus(),
+                game.gravity_tiredness_factor()
+            ),
+        };
         frame.render_widget(
-            Paragraph::new(format!(
-                "Playing on {}",
-                world.get_planet_or_err(game.location).unwrap().name
-            ))
+            Paragraph::new(vec![
+                Line::from(format!(
+                    "Playing on {}",
+                    world.get_planet_or_err(game.location).unwrap().name
+                )),
+                Line::from(gravity_line),
+            ])
             .alignment(Alignment::Center),
             central_split[2],
         );
@@ -424,6 +525,19 @@ impl GamePanel {
                 "{:>18}<--{:<}",
                 game.home_team_in_game.defense_tactic, game.away_team_in_game.offense_tactic
             )),
+            Line::from(format!(
+                "{:>18}   {:<}",
+                format!(
+                    "Momentum {}/{}",
+                    game.home_team_in_game.offensive_momentum,
+                    game.home_team_in_game.defensive_momentum
+                ),
+                format!(
+                    "{}/{} Momentum",
+                    game.away_team_in_game.offensive_momentum,
+                    game.away_team_in_game.defensive_momentum
+                )
+            )),
         ];
 
         let mut timer_lines = self.build_timer_lines(world, game);
@@ -523,6 +637,8 @@ impl GamePanel {
         let mut made_2pt_total = 0;
         let mut attempted_3pt_total = 0;
         let mut made_3pt_total = 0;
+        let mut attempted_ft_total = 0;
+        let mut made_ft_total = 0;
         let mut assists_total = 0;
         let mut turnovers_total = 0;
         let mut defensive_rebounds_total = 0;
@@ -539,6 +655,8 @@ impl GamePanel {
             made_2pt_total += player_data.made_2pt as u16;
             attempted_3pt_total += player_data.attempted_3pt as u16;
             made_3pt_total += player_data.made_3pt as u16;
+            attempted_ft_total += player_data.attempted_ft as u16;
+            made_ft_total += player_data.made_ft as u16;
             assists_total += player_data.assists as u16;
             turnovers_total += player_data.turnovers as u16;
             defensive_rebounds_total += player_data.defensive_rebounds as u16;
@@ -588,6 +706,10 @@ impl GamePanel {
                     "{:>2}/{:<2}",
                     players_data[&player.id].made_3pt, players_data[&player.id].attempted_3pt
                 )),
+                Cell::from(format!(
+                    "{:>2}/{:<2}",
+                    players_data[&player.id].made_ft, players_data[&player.id].attempted_ft
+                )),
                 Cell::from(format!(
                     "{:>3}/{:<2}",
                     players_data[&player.id].assists, players_data[&player.id].turnovers
@@ -612,6 +734,7 @@ impl GamePanel {
             Cell::from(format!("{:^3}", points_total)),
             Cell::from(format!("{:>2}/{:<2}", made_2pt_total, attempted_2pt_total)),
             Cell::from(format!("{:>2}/{:<2}", made_3pt_total, attempted_3pt_total)),
+            Cell::from(format!("{:>2}/{:<2}", made_ft_total, attempted_ft_total)),
             Cell::from(format!("{:>3}/{:<2}", assists_total, turnovers_total)),
             Cell::from(format!(
                 "{:>3}/{:<3}",
@@ -634,6 +757,7 @@ impl GamePanel {
                 Constraint::Length(4),
                 Constraint::Length(6),
                 Constraint::Length(5),
+                Constraint::Length(5),
                 Constraint::Length(6),
                 Constraint::Length(7),
                 Constraint::Length(3),
@@ -652,6 +776,7 @@ impl GamePanel {
             "Pts",
             " 2pt ",
             " 3pt ",
+            " FT  ",
             "Ast/TO",
             "DRb/ORb",
             "Stl",
@@ -667,6 +792,7 @@ impl GamePanel {
             "Pts",
             " 2pt ",
             " 3pt ",
+            " FT  ",
             "Ast/TO",
             "DRb/ORb",
             "Stl",
@@ -695,6 +821,7 @@ impl GamePanel {
             Constraint::Length(3), //points
             Constraint::Length(6), //2pt
             Constraint::Length(5), //3pt
+            Constraint::Length(5), //free throws
             Constraint::Length(6), //assists/turnovers
             Constraint::Length(7), //defensive rebounds/offensive rebounds
             Constraint::Length(3), //steals
@@ -804,6 +931,10 @@ impl Screen for GamePanel {
             }
         }
 
+        if let Some(replay) = self.replay.as_mut() {
+            replay.update();
+        }
+
         if let Some(game) = self.selected_game(world) {
             if self.commentary_index == 0 {
                 self.action_results = game.action_results.clone();
@@ -815,7 +946,7 @@ impl Screen for GamePanel {
     }
 
     fn render(&mut self, frame: &mut Frame, world: &World, area: Rect) -> AppResult<()> {
-        if self.games.len() == 0 {
+        if self.games.len() == 0 && self.replay.is_none() {
             frame.render_widget(
                 Paragraph::new(" No games today!"),
                 area.inner(&Margin {
@@ -843,6 +974,41 @@ impl Screen for GamePanel {
         &mut self,
         key_event: crossterm::event::KeyEvent,
     ) -> Option<UiCallbackPreset> {
+        if let Some(replay) = self.replay.as_mut() {
+            match key_event.code {
+                UiKey::REPLAY_PAUSE => {
+                    replay.paused = !replay.paused;
+                    return None;
+                }
+                UiKey::REPLAY_SLOWER => {
+                    replay.speed = (replay.speed / 2).max(1);
+                    return None;
+                }
+                UiKey::REPLAY_FASTER => {
+                    replay.speed = (replay.speed * 2).min(REPLAY_MAX_SPEED);
+                    return None;
+                }
+                UiKey::REPLAY_BACKWARD => {
+                    let target = replay.game.timer.value.saturating_sub(REPLAY_SEEK_STEP);
+                    replay.seek(Timer::from(target));
+                    self.commentary_index = 0;
+                    return None;
+                }
+                UiKey::REPLAY_FORWARD => {
+                    let target = replay.game.timer.value + REPLAY_SEEK_STEP;
+                    replay.seek(Timer::from(target));
+                    self.commentary_index = 0;
+                    return None;
+                }
+                UiKey::REPLAY_STOP => {
+                    self.replay = None;
+                    self.commentary_index = 0;
+                    return None;
+                }
+                _ => {}
+            }
+        }
+
         match key_event.code {
             KeyCode::Up => self.next_index(),
             KeyCode::Down => self.previous_index(),
@@ -890,6 +1056,52 @@ impl Screen for GamePanel {
 
     fn footer_spans(&self) -> Vec<Span> {
         let next_view = if self.pitch_view { "Score" } else { "Pitch" };
+        if let Some(replay) = self.replay.as_ref() {
+            return vec![
+                Span::styled(
+                    format!(" {} ", UiKey::REPLAY_PAUSE.to_string()),
+                    Style::default().bg(Color::Gray).fg(Color::DarkGray),
+                ),
+                Span::styled(
+                    if replay.paused { " Resume " } else { " Pause " },
+                    Style::default().fg(Color::DarkGray),
+                ),
+                Span::styled(
+                    format!(
+                        " {}/{} ",
+                        UiKey::REPLAY_SLOWER.to_string(),
+                        UiKey::REPLAY_FASTER.to_string()
+                    ),
+                    Style::default().bg(Color::Gray).fg(Color::DarkGray),
+                ),
+                Span::styled(
+                    format!(" Speed: x{:<2} ", replay.speed),
+                    Style::default().fg(Color::DarkGray),
+                ),
+                Span::styled(
+                    format!(
+                        " {}/{} ",
+                        UiKey::REPLAY_BACKWARD.to_string(),
+                        UiKey::REPLAY_FORWARD.to_string()
+                    ),
+                    Style::default().bg(Color::Gray).fg(Color::DarkGray),
+                ),
+                Span::styled(" Seek ", Style::default().fg(Color::DarkGray)),
+                Span::styled(
+                    format!(" {} ", UiKey::PITCH_VIEW.to_string()),
+                    Style::default().bg(Color::Gray).fg(Color::DarkGray),
+                ),
+                Span::styled(
+                    format!(" Change view: {} ", next_view),
+                    Style::default().fg(Color::DarkGray),
+                ),
+                Span::styled(
+                    format!(" {} ", UiKey::REPLAY_STOP.to_string()),
+                    Style::default().bg(Color::Gray).fg(Color::DarkGray),
+                ),
+                Span::styled(" Stop replay ", Style::default().fg(Color::DarkGray)),
+            ];
+        }
         vec![
             Span::styled(
                 " Enter ",
@@ -933,3 +1145,29 @@ impl SplitPanel for GamePanel {
         self.commentary_index = 0;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::GamePanel;
+    use crate::test_utils::test_world_and_game;
+    use crate::ui::{constants::UiKey, traits::Screen};
+    use crossterm::event::{KeyCode, KeyEvent};
+
+    #[test]
+    fn test_replay_keys() {
+        let (_, game) = test_world_and_game();
+        let mut panel = GamePanel::default();
+        panel.start_replay(&game);
+
+        // Esc quits the app before keys reach the panels.
+        assert_ne!(UiKey::REPLAY_STOP, KeyCode::Esc);
+        assert!(panel
+            .handle_key_events(KeyEvent::from(UiKey::REPLAY_PAUSE))
+            .is_none());
+        assert!(panel.replay.as_ref().unwrap().paused);
+        assert!(panel
+            .handle_key_events(KeyEvent::from(UiKey::REPLAY_STOP))
+            .is_none());
+        assert!(panel.replay.is_none());
+    }
+}
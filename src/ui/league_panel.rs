@@ -0,0 +1,311 @@
+use super::clickable_list::ClickableListState;
+use super::ui_callback::{CallbackRegistry, UiCallbackPreset};
+use super::{
+    constants::{PrintableKeyCode, UiKey, UiStyle, LEFT_PANEL_WIDTH},
+    traits::{Screen, SplitPanel},
+    widgets::{default_block, selectable_list},
+};
+use crate::types::{AppResult, PlanetId, SystemTimeTick, TeamId};
+use crate::world::{
+    league::{Fixture, League, LeaguePhase},
+    world::World,
+};
+use core::fmt::Debug;
+use crossterm::event::KeyCode;
+use itertools::Itertools;
+use ratatui::layout::Margin;
+use ratatui::{
+    layout::{Constraint, Direction, Layout},
+    prelude::Rect,
+    style::{Color, Style},
+    text::{Line, Span},
+    widgets::{Cell, Paragraph, Row, Table},
+    Frame,
+};
+use std::{cell::RefCell, rc::Rc};
+
+#[derive(Debug, Default)]
+pub struct LeaguePanel {
+    pub index: usize,
+    leagues: Vec<PlanetId>,
+    own_team_in_leagues: bool,
+    callback_registry: Rc<RefCell<CallbackRegistry>>,
+}
+
+impl LeaguePanel {
+    pub fn new(callback_registry: Rc<RefCell<CallbackRegistry>>) -> Self {
+        Self {
+            callback_registry,
+            ..Default::default()
+        }
+    }
+
+    fn selected_league<'a>(&self, world: &'a World) -> Option<&'a League> {
+        world.leagues.get(self.leagues.get(self.index)?)
+    }
+
+    fn team_name(world: &World, team_id: TeamId) -> String {
+        world
+            .get_team(team_id)
+            .map(|team| team.name.clone())
+            .unwrap_or("Unknown".to_string())
+    }
+
+    fn team_style(world: &World, team_id: TeamId) -> Style {
+        if team_id == world.own_team_id {
+            UiStyle::OWN_TEAM
+        } else {
+            UiStyle::DEFAULT
+        }
+    }
+
+    fn build_league_list(&self, frame: &mut Frame, world: &World, area: Rect) {
+        let options = self
+            .leagues
+            .iter()
+            .filter_map(|id| world.leagues.get(id))
+            .map(|league| {
+                let style = if league.team_ids.contains(&world.own_team_id) {
+                    UiStyle::OWN_TEAM
+                } else {
+                    UiStyle::DEFAULT
+                };
+                (format!("{} season {}", league.name, league.season), style)
+            })
+            .collect_vec();
+
+        let list = selectable_list(options, &self.callback_registry);
+        frame.render_stateful_widget(
+            list.block(default_block().title("Leagues ↓/↑")),
+            area,
+            &mut ClickableListState::default().with_selected(Some(self.index)),
+        );
+    }
+
+    fn build_standings(&self, frame: &mut Frame, world: &World, league: &League, area: Rect) {
+        let header = Row::new(
+            [" #", "Team", "P", "W", "L", "T", "PF", "PA", "Pts"]
+                .iter()
+                .map(|h| Cell::from(*h).style(UiStyle::HEADER)),
+        );
+        let rows = league
+            .standings()
+            .iter()
+            .enumerate()
+            .map(|(idx, standing)| {
+                Row::new(vec![
+                    Cell::from(format!("{:>2}", idx + 1)),
+                    Cell::from(Self::team_name(world, standing.team_id))
+                        .style(Self::team_style(world, standing.team_id)),
+                    Cell::from(format!("{:>2}", standing.played)),
+                    Cell::from(format!("{:>2}", standing.wins)),
+                    Cell::from(format!("{:>2}", standing.losses)),
+                    Cell::from(format!("{:>2}", standing.ties)),
+                    Cell::from(format!("{:>4}", standing.points_for)),
+                    Cell::from(format!("{:>4}", standing.points_against)),
+                    Cell::from(format!("{:>3}", standing.points())),
+                ])
+            })
+            .collect_vec();
+
+        let title = match league.phase {
+            LeaguePhase::Finished => format!(
+                " Standings - Champion: {} ",
+                league
+                    .champion
+                    .map(|id| Self::team_name(world, id))
+                    .unwrap_or_default()
+            ),
+            phase => format!(" Standings - {} ", phase),
+        };
+
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Length(3),
+                Constraint::Length(20),
+                Constraint::Length(3),
+                Constraint::Length(3),
+                Constraint::Length(3),
+                Constraint::Length(3),
+                Constraint::Length(5),
+                Constraint::Length(5),
+                Constraint::Length(4),
+            ],
+        )
+        .header(header)
+        .block(default_block().title(title));
+        frame.render_widget(table, area);
+    }
+
+    fn format_fixture(world: &World, fixture: &Fixture) -> Line<'static> {
+        let result = match fixture.score {
+            Some((home_score, away_score)) => format!("{:>3}-{:<3}", home_score, away_score),
+            None if fixture.game_id.is_some() => " live  ".to_string(),
+            None => "  vs   ".to_string(),
+        };
+        Line::from(vec![
+            Span::styled(
+                format!(" {:>20} ", Self::team_name(world, fixture.home_team_id)),
+                Self::team_style(world, fixture.home_team_id),
+            ),
+            Span::raw(result),
+            Span::styled(
+                format!(" {:<20}", Self::team_name(world, fixture.away_team_id)),
+                Self::team_style(world, fixture.away_team_id),
+            ),
+        ])
+    }
+
+    fn build_fixtures(&self, frame: &mut Frame, world: &World, league: &League, area: Rect) {
+        let now = world.last_tick_short_interval;
+        let current_round = league.current_round();
+        let mut lines = vec![];
+        // Show the results of the previous round and the upcoming fixtures.
+        for (round, fixtures) in league
+            .fixtures
+            .iter()
+            .filter(|f| f.round + 1 >= current_round)
+            .group_by(|f| f.round)
+            .into_iter()
+        {
+            let fixtures = fixtures.collect_vec();
+            let kind = if fixtures.iter().any(|f| f.is_playoff) {
+                "Playoffs"
+            } else {
+                "Round"
+            };
+            let round_start = league.round_start(round);
+            let when = if round_start > now {
+                format!("in {}", (round_start - now).formatted())
+            } else {
+                round_start.formatted_as_date()
+            };
+            lines.push(Line::from(Span::styled(
+                format!(" {} {} - {}", kind, round + 1, when),
+                UiStyle::HEADER,
+            )));
+            for fixture in fixtures {
+                lines.push(Self::format_fixture(world, fixture));
+            }
+            lines.push(Line::from(""));
+        }
+
+        if league.phase == LeaguePhase::Finished {
+            let next_season_at = league.next_season_at();
+            if next_season_at > now {
+                lines.push(Line::from(format!(
+                    " Next season starts in {}",
+                    (next_season_at - now).formatted()
+                )));
+            }
+        }
+
+        frame.render_widget(
+            Paragraph::new(lines).block(default_block().title(" Fixtures ")),
+            area,
+        );
+    }
+}
+
+impl Screen for LeaguePanel {
+    fn name(&self) -> &str {
+        "Leagues"
+    }
+
+    fn update(&mut self, world: &World) -> AppResult<()> {
+        if world.dirty_ui || self.leagues.len() != world.leagues.len() {
+            self.leagues = world
+                .leagues
+                .values()
+                .sorted_by(|a, b| a.name.cmp(&b.name))
+                .map(|league| league.region)
+                .collect();
+        }
+        if self.index >= self.leagues.len() {
+            self.set_index(0);
+        }
+        self.own_team_in_leagues = world.own_team_in_leagues;
+        Ok(())
+    }
+
+    fn render(&mut self, frame: &mut Frame, world: &World, area: Rect) -> AppResult<()> {
+        let league = match self.selected_league(world) {
+            Some(league) => league,
+            None => {
+                frame.render_widget(
+                    Paragraph::new(" No leagues yet!"),
+                    area.inner(&Margin {
+                        vertical: 1,
+                        horizontal: 1,
+                    }),
+                );
+                return Ok(());
+            }
+        };
+
+        let split = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Length(LEFT_PANEL_WIDTH), Constraint::Min(10)])
+            .split(area);
+        self.build_league_list(frame, world, split[0]);
+
+        let right_split = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Length(54), Constraint::Min(10)])
+            .split(split[1]);
+        self.build_standings(frame, world, league, right_split[0]);
+        self.build_fixtures(frame, world, league, right_split[1]);
+        Ok(())
+    }
+
+    fn handle_key_events(
+        &mut self,
+        key_event: crossterm::event::KeyEvent,
+    ) -> Option<UiCallbackPreset> {
+        match key_event.code {
+            KeyCode::Up => self.next_index(),
+            KeyCode::Down => self.previous_index(),
+            UiKey::JOIN_LEAGUES => return Some(UiCallbackPreset::ToggleLeagueParticipation),
+            _ => {}
+        }
+        None
+    }
+
+    fn footer_spans(&self) -> Vec<Span> {
+        vec![
+            Span::styled(
+                " ↓/↑ ",
+                Style::default().bg(Color::Gray).fg(Color::DarkGray),
+            ),
+            Span::styled(" Select league ", Style::default().fg(Color::DarkGray)),
+            Span::styled(
+                format!(" {} ", UiKey::JOIN_LEAGUES.to_string()),
+                Style::default().bg(Color::Gray).fg(Color::DarkGray),
+            ),
+            Span::styled(
+                // Changes only apply from the next season.
+                if self.own_team_in_leagues {
+                    " Leave leagues next season "
+                } else {
+                    " Join leagues next season "
+                },
+                Style::default().fg(Color::DarkGray),
+            ),
+        ]
+    }
+}
+
+impl SplitPanel for LeaguePanel {
+    fn index(&self) -> usize {
+        self.index
+    }
+
+    fn max_index(&self) -> usize {
+        self.leagues.len()
+    }
+
+    fn set_index(&mut self, index: usize) {
+        self.index = index;
+    }
+}
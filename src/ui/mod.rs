@@ -6,6 +6,7 @@ mod constants;
 mod galaxy_panel;
 mod game_panel;
 mod gif_map;
+mod league_panel;
 mod my_team_panel;
 mod new_team_screen;
 mod player_panel;
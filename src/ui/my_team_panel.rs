@@ -11,7 +11,7 @@ use crate::{
     image::spaceship::SPACESHIP_IMAGE_HEIGHT,
     types::{SystemTimeTick, TeamId},
     world::{
-        constants::{BASE_BONUS, BONUS_PER_SKILL},
+        constants::{BASE_BONUS, BONUS_PER_SKILL, CURRENCY_SYMBOL, FUEL_COST_PER_TONNE},
         role::CrewRole,
         skill::GameSkill,
     },
@@ -27,6 +27,7 @@ use crate::{
     },
 };
 use core::fmt::Debug;
+use itertools::Itertools;
 use ratatui::{
     layout::Margin,
     prelude::{Constraint, Direction, Layout, Rect},
@@ -226,7 +227,8 @@ impl Screen for MyTeamPanel {
                 Constraint::Length(6),
                 Constraint::Length(3),  //margin
                 Constraint::Length(32), //auto-assign
-                Constraint::Length(32), //tactic
+                Constraint::Length(32), //offense tactic
+                Constraint::Length(32), //defense tactic
                 Constraint::Min(0),
             ])
             .split(table_bottom[1].inner(&Margin {
@@ -275,6 +277,19 @@ impl Screen for MyTeamPanel {
         );
         frame.render_widget(offense_tactic_button, position_button_splits[7]);
 
+        let defense_tactic_button = Button::new(
+            format!(
+                "{}: {}",
+                UiKey::SET_DEFENSE_TACTIC.to_string(),
+                team.game_defense_tactic
+            ),
+            UiCallbackPreset::SetTeamDefenseTactic {
+                tactic: team.game_defense_tactic.next(),
+            },
+            Rc::clone(&self.callback_registry),
+        );
+        frame.render_widget(defense_tactic_button, position_button_splits[8]);
+
         let button_splits = Layout::default()
             .direction(Direction::Horizontal)
             .constraints([
@@ -391,6 +406,7 @@ impl Screen for MyTeamPanel {
             .constraints([
                 Constraint::Length(SPACESHIP_IMAGE_HEIGHT as u16 / 2 + 2),
                 Constraint::Length(3),
+                Constraint::Length(3),
                 Constraint::Min(0),
             ])
             .split(bottom_split[0]);
@@ -434,6 +450,32 @@ impl Screen for MyTeamPanel {
             }),
         );
 
+        let can_buy_fuel = team.can_buy_fuel();
+        let mut fuel_button = Button::new(
+            format!(
+                "{}: Buy fuel ({} {})",
+                UiKey::BUY_FUEL.to_string(),
+                team.max_fuel_purchase() * FUEL_COST_PER_TONNE,
+                CURRENCY_SYMBOL
+            ),
+            UiCallbackPreset::BuyFuel,
+            Rc::clone(&self.callback_registry),
+        );
+        if can_buy_fuel.is_err() {
+            fuel_button.disable(Some(format!(
+                "{}: {}",
+                UiKey::BUY_FUEL.to_string(),
+                can_buy_fuel.unwrap_err().to_string()
+            )));
+        }
+        frame.render_widget(
+            fuel_button,
+            travel_button_split[2].inner(&Margin {
+                vertical: 0,
+                horizontal: 1,
+            }),
+        );
+
         let mut lines = vec![];
         if team.current_game.is_some() {
             if let Some(game) = world.games.get(&team.current_game.unwrap()) {
@@ -450,16 +492,38 @@ impl Screen for MyTeamPanel {
             }
         }
 
-        for game in world.past_games.values() {
-            lines.push(Line::from(format!(
-                " {:>12} {:>3}-{:<3} {:<}",
-                game.home_team_name, game.home_score, game.away_score, game.away_team_name,
-            )));
-        }
         frame.render_widget(
-            Paragraph::new(lines).block(default_block().title("Recent Games".to_string())),
+            default_block().title("Recent Games (click to replay)".to_string()),
             bottom_split[1],
         );
+        let games_area = bottom_split[1].inner(&Margin {
+            vertical: 1,
+            horizontal: 1,
+        });
+        let current_game_lines = lines.len() as u16;
+        frame.render_widget(Paragraph::new(lines), games_area);
+
+        let past_games = world
+            .past_games
+            .values()
+            .sorted_by(|a, b| b.starting_at.cmp(&a.starting_at))
+            .take(games_area.height.saturating_sub(current_game_lines) as usize);
+        for (idx, game) in past_games.enumerate() {
+            let button = Button::paragraph(
+                format!(
+                    " {:>12} {:>3}-{:<3} {:<}",
+                    game.home_team_name, game.home_score, game.away_score, game.away_team_name,
+                ),
+                UiCallbackPreset::ReplayGame { game_id: game.id },
+                Rc::clone(&self.callback_registry),
+            );
+            let row = Rect {
+                y: games_area.y + current_game_lines + idx as u16,
+                height: 1,
+                ..games_area
+            };
+            frame.render_widget(button, row);
+        }
 
         frame.render_widget(
             default_block().title("Future stuff".to_string()),
@@ -493,6 +557,10 @@ impl Screen for MyTeamPanel {
                 return Some(UiCallbackPreset::SetNextTeamOffenseTactic);
             }
 
+            UiKey::SET_DEFENSE_TACTIC => {
+                return Some(UiCallbackPreset::SetNextTeamDefenseTactic);
+            }
+
             UiKey::HIRE_FIRE => {
                 return Some(UiCallbackPreset::ReleasePlayer { player_id });
             }
@@ -524,6 +592,10 @@ impl Screen for MyTeamPanel {
                 });
             }
 
+            UiKey::BUY_FUEL => {
+                return Some(UiCallbackPreset::BuyFuel);
+            }
+
             crossterm::event::KeyCode::Char('1') => {
                 return Some(UiCallbackPreset::SwapPlayerPositions {
                     player_id,
@@ -9,7 +9,7 @@ use super::{
 use crate::types::{AppResult, SystemTimeTick, Tick};
 use crate::world::constants::SOL_ID;
 use crate::{
-    store::{file_data, world_exists, PERSISTED_WORLD_FILENAME},
+    store::{current_profile, file_data, world_exists, PERSISTED_WORLD_FILENAME},
     world::world::World,
 };
 use core::fmt::Debug;
@@ -38,6 +38,8 @@ pub struct SplashScreen {
     selection_text: Vec<String>,
     tick: usize,
     can_load_world: bool,
+    // Backup offered when the world save cannot be loaded.
+    backup: Option<String>,
     audio_player_is_playing: bool,
     callback_registry: Rc<RefCell<CallbackRegistry>>,
     gif_map: Rc<RefCell<GifMap>>,
@@ -80,53 +82,75 @@ impl SplashScreen {
         callback_registry: Rc<RefCell<CallbackRegistry>>,
         gif_map: Rc<RefCell<GifMap>>,
     ) -> Self {
-        let mut selection_text = vec![];
-        let can_load_world: bool;
-        let mut continue_text = "Continue".to_string();
-        if world_exists() {
-            if let Ok(continue_data) = file_data(PERSISTED_WORLD_FILENAME) {
-                if let Ok(last_modified) = continue_data.modified() {
-                    continue_text = format!(
-                        "Continue: {}",
-                        Tick::from_system_time(last_modified).formatted_as_date()
-                    );
-                }
-            }
-            can_load_world = true;
-        } else {
-            can_load_world = false;
-        }
-        selection_text.push(continue_text);
-        selection_text.push("New".to_string());
-        selection_text.push("Music: On ".to_string());
-        selection_text.push("Quit".to_string());
+        let selection_text = vec![
+            "Continue".to_string(),
+            "New".to_string(),
+            "Profile".to_string(),
+            "Music: On ".to_string(),
+            "Quit".to_string(),
+        ];
 
         let quote = QUOTES.choose(&mut rand::thread_rng());
-        let index = if can_load_world { 0 } else { 1 };
         let title = big_text(&TITLE);
 
-        Self {
-            index,
+        let mut splash_screen = Self {
+            index: 0,
             title,
             quote: quote.as_deref().unwrap(),
             selection_text,
             tick: 0,
-            can_load_world,
+            can_load_world: false,
+            backup: None,
             audio_player_is_playing: true,
             callback_registry,
             gif_map,
+        };
+        splash_screen.refresh_profile();
+        splash_screen
+    }
+
+    /// Updates the continue option with the save of the current profile.
+    pub fn refresh_profile(&mut self) {
+        let mut continue_text = "Continue".to_string();
+        self.can_load_world = world_exists();
+        if self.can_load_world {
+            if let Ok(continue_data) = file_data(PERSISTED_WORLD_FILENAME) {
+                if let Ok(last_modified) = continue_data.modified() {
+                    continue_text = format!(
+                        "Continue: {}",
+                        Tick::from_system_time(last_modified).formatted_as_date()
+                    );
+                }
+            }
         }
+        self.selection_text[0] = continue_text;
+        self.selection_text[2] = format!("Profile: {}", current_profile());
+        self.backup = None;
+        self.index = if self.can_load_world { 0 } else { 1 };
     }
 
     fn get_ui_preset_at_index(&self, index: usize) -> UiCallbackPreset {
         match index {
-            0 => UiCallbackPreset::ContinueGame,
+            0 => match self.backup.as_ref() {
+                Some(filename) => UiCallbackPreset::RecoverBackup {
+                    filename: filename.clone(),
+                },
+                None => UiCallbackPreset::ContinueGame,
+            },
             1 => UiCallbackPreset::NewGame,
-            2 => UiCallbackPreset::ToggleAudio,
+            2 => UiCallbackPreset::NextProfile,
+            3 => UiCallbackPreset::ToggleAudio,
             _ => UiCallbackPreset::QuitGame,
         }
     }
 
+    /// Replaces the continue option with the recovery of a backup.
+    pub fn set_backup(&mut self, timestamp: Tick, filename: String) {
+        self.selection_text[0] = format!("Recover backup: {}", timestamp.formatted_as_date());
+        self.backup = Some(filename);
+        self.can_load_world = true;
+    }
+
     pub fn set_audio_player_is_playing(&mut self, is_playing: bool) {
         self.audio_player_is_playing = is_playing;
     }
@@ -139,7 +163,7 @@ impl Screen for SplashScreen {
 
     fn update(&mut self, _world: &World) -> AppResult<()> {
         self.tick += 1;
-        self.selection_text[2] = if self.audio_player_is_playing {
+        self.selection_text[3] = if self.audio_player_is_playing {
             "Music: On ".to_string()
         } else {
             "Music: Off".to_string()
@@ -261,20 +285,24 @@ impl Screen for SplashScreen {
             KeyCode::Up => self.next_index(),
             KeyCode::Down => self.previous_index(),
             KeyCode::Enter => match self.index {
-                // continue
+                // continue, or recover a backup
                 0 => {
-                    return Some(UiCallbackPreset::ContinueGame);
+                    return Some(self.get_ui_preset_at_index(0));
                 }
                 // new
                 1 => {
                     return Some(UiCallbackPreset::NewGame);
                 }
-                //options
+                // profile
                 2 => {
+                    return Some(UiCallbackPreset::NextProfile);
+                }
+                //options
+                3 => {
                     return Some(UiCallbackPreset::ToggleAudio);
                 }
                 //quit
-                3 => {
+                4 => {
                     return Some(UiCallbackPreset::QuitGame);
                 }
                 _ => {}
@@ -7,7 +7,7 @@ use super::{
     utils::input_from_key_event,
     widgets::default_block,
 };
-use crate::network::types::Challenge;
+use crate::network::types::{Challenge, Leaderboard};
 use crate::types::{AppResult, SystemTimeTick, TeamId, Tick};
 use crate::ui::constants::{PrintableKeyCode, UiKey};
 use crate::world::world::World;
@@ -17,7 +17,7 @@ use libp2p::PeerId;
 use ratatui::layout::Margin;
 use ratatui::style::{Color, Style};
 use ratatui::text::Span;
-use ratatui::widgets::{List, ListItem};
+use ratatui::widgets::{Cell, List, ListItem, Row, Table};
 use ratatui::{
     layout::{Constraint, Direction, Layout},
     prelude::Rect,
@@ -35,6 +35,7 @@ use tui_textarea::{CursorMove, TextArea};
 pub enum EventTopic {
     Log,
     Challenges,
+    Leaderboard,
     #[default]
     Chat,
 }
@@ -48,6 +49,7 @@ pub struct SwarmPanel {
     connected_peers: Vec<PeerId>,
     peer_to_team_id: HashMap<PeerId, TeamId>,
     peer_to_challenge: HashMap<PeerId, Challenge>,
+    leaderboard: Option<Leaderboard>,
     callback_registry: Rc<RefCell<CallbackRegistry>>,
 }
 
@@ -108,6 +110,10 @@ impl SwarmPanel {
         self.peer_to_challenge.clear();
     }
 
+    pub fn set_leaderboard(&mut self, leaderboard: Leaderboard) {
+        self.leaderboard = Some(leaderboard);
+    }
+
     fn build_left_panel(&mut self, frame: &mut Frame, world: &World, area: Rect) {
         let split = Layout::default()
             .direction(Direction::Vertical)
@@ -115,6 +121,7 @@ impl SwarmPanel {
                 Constraint::Length(3),
                 Constraint::Length(3),
                 Constraint::Length(3),
+                Constraint::Length(3),
                 Constraint::Min(1),
                 Constraint::Length(3),
             ])
@@ -136,6 +143,14 @@ impl SwarmPanel {
             Rc::clone(&self.callback_registry),
         );
 
+        let mut leaderboard_button = Button::new(
+            "Leaderboard".to_string(),
+            UiCallbackPreset::SetSwarmPanelTopic {
+                topic: EventTopic::Leaderboard,
+            },
+            Rc::clone(&self.callback_registry),
+        );
+
         let mut log_button = Button::new(
             "Log".to_string(),
             UiCallbackPreset::SetSwarmPanelTopic {
@@ -150,6 +165,9 @@ impl SwarmPanel {
             EventTopic::Challenges => {
                 challenges_button.disable(None);
             }
+            EventTopic::Leaderboard => {
+                leaderboard_button.disable(None);
+            }
             EventTopic::Chat => {
                 chat_button.disable(None);
             }
@@ -157,7 +175,8 @@ impl SwarmPanel {
 
         frame.render_widget(chat_button, split[0]);
         frame.render_widget(challenges_button, split[1]);
-        frame.render_widget(log_button, split[2]);
+        frame.render_widget(leaderboard_button, split[2]);
+        frame.render_widget(log_button, split[3]);
 
         let mut items: Vec<ListItem> = vec![];
 
@@ -176,7 +195,7 @@ impl SwarmPanel {
             }
         }
         let list = List::new(items);
-        frame.render_widget(list.block(default_block().title("Peers")), split[3]);
+        frame.render_widget(list.block(default_block().title("Peers")), split[4]);
 
         let dial_button = Button::new(
             "Ping".to_string(),
@@ -185,7 +204,7 @@ impl SwarmPanel {
             },
             Rc::clone(&self.callback_registry),
         );
-        frame.render_widget(dial_button, split[4]);
+        frame.render_widget(dial_button, split[5]);
     }
 
     fn build_challenge_list(&mut self, frame: &mut Frame, area: Rect) {
@@ -250,6 +269,67 @@ impl SwarmPanel {
         // frame.render_widget(list, area);
     }
 
+    fn build_leaderboard(&self, world: &World, frame: &mut Frame, area: Rect) {
+        let Some(leaderboard) = self.leaderboard.as_ref() else {
+            frame.render_widget(
+                Paragraph::new(" No leaderboard received from the seed yet.")
+                    .block(default_block().title("Leaderboard")),
+                area,
+            );
+            return;
+        };
+
+        let header = Row::new(
+            [" #", "Team", "P", "W", "L", "T", "PF", "PA"]
+                .iter()
+                .map(|h| Cell::from(*h).style(UiStyle::HEADER)),
+        );
+        let rows = leaderboard
+            .entries
+            .iter()
+            .enumerate()
+            .map(|(idx, entry)| {
+                let style = if entry.team_id == world.own_team_id {
+                    UiStyle::OWN_TEAM
+                } else {
+                    UiStyle::NETWORK
+                };
+                Row::new(vec![
+                    Cell::from(format!("{:>2}", idx + 1)),
+                    Cell::from(entry.team_name.clone()).style(style),
+                    Cell::from(format!("{:>3}", entry.played)),
+                    Cell::from(format!("{:>3}", entry.wins)),
+                    Cell::from(format!("{:>3}", entry.losses)),
+                    Cell::from(format!("{:>3}", entry.ties)),
+                    Cell::from(format!("{:>5}", entry.points_for)),
+                    Cell::from(format!("{:>5}", entry.points_against)),
+                ])
+            })
+            .collect::<Vec<Row>>();
+
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Length(3),
+                Constraint::Length(20),
+                Constraint::Length(4),
+                Constraint::Length(4),
+                Constraint::Length(4),
+                Constraint::Length(4),
+                Constraint::Length(6),
+                Constraint::Length(6),
+            ],
+        )
+        .header(header)
+        .block(default_block().title(format!(
+            "Leaderboard - {} teams, {} games - updated {}",
+            leaderboard.teams_count,
+            leaderboard.games_count,
+            leaderboard.updated_at.formatted_as_time()
+        )));
+        frame.render_widget(table, area);
+    }
+
     fn build_right_panel(&mut self, frame: &mut Frame, world: &World, area: Rect) {
         let split = Layout::default()
             .direction(Direction::Vertical)
@@ -269,6 +349,10 @@ impl SwarmPanel {
             );
             return;
         }
+        if self.current_topic == EventTopic::Leaderboard {
+            self.build_leaderboard(world, frame, split[0]);
+            return;
+        }
         let mut items = vec![];
         for event in self.events.get(&self.current_topic).unwrap().iter().rev() {
             match event.peer_id {
@@ -352,7 +436,8 @@ impl Screen for SwarmPanel {
                 match self.current_topic {
                     EventTopic::Log => self.set_current_topic(EventTopic::Chat),
                     EventTopic::Chat => self.set_current_topic(EventTopic::Challenges),
-                    EventTopic::Challenges => self.set_current_topic(EventTopic::Log),
+                    EventTopic::Challenges => self.set_current_topic(EventTopic::Leaderboard),
+                    EventTopic::Leaderboard => self.set_current_topic(EventTopic::Log),
                 };
             }
             KeyCode::Enter => {
@@ -456,7 +456,10 @@ impl TeamListPanel {
                 }
             }
 
-            let can_challenge = own_team.can_challenge_team(team);
+            let can_challenge = own_team
+                .can_challenge_team(team)
+                .and_then(|_| world.can_field_team(own_team))
+                .and_then(|_| world.can_field_team(team));
 
             let mut button = Button::new(
                 format!("{}: Challenge", UiKey::CHALLENGE_TEAM.to_string()),
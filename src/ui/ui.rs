@@ -2,6 +2,7 @@ use super::button::Button;
 use super::constants::{PrintableKeyCode, UiKey, UiStyle, UiText};
 use super::galaxy_panel::GalaxyPanel;
 use super::gif_map::GifMap;
+use super::league_panel::LeaguePanel;
 use super::splash_screen::SplashScreen;
 use super::traits::SplitPanel;
 use super::ui_callback::{CallbackRegistry, UiCallbackPreset};
@@ -48,6 +49,7 @@ pub enum UiTab {
     Player,
     Galaxy,
     Game,
+    League,
     Swarm,
 }
 
@@ -70,6 +72,7 @@ pub struct Ui {
     pub player_panel: PlayerListPanel,
     pub team_panel: TeamListPanel,
     pub game_panel: GamePanel,
+    pub league_panel: LeaguePanel,
     pub swarm_panel: SwarmPanel,
     pub my_team_panel: MyTeamPanel,
     pub galaxy_panel: GalaxyPanel,
@@ -92,6 +95,7 @@ impl Ui {
         let player_panel = PlayerListPanel::new(Rc::clone(&callback_registry), Rc::clone(&gif_map));
         let team_panel = TeamListPanel::new(Rc::clone(&callback_registry), Rc::clone(&gif_map));
         let game_panel = GamePanel::new(Rc::clone(&callback_registry), Rc::clone(&gif_map));
+        let league_panel = LeaguePanel::new(Rc::clone(&callback_registry));
         let swarm_panel = SwarmPanel::new(Rc::clone(&callback_registry));
         let my_team_panel = MyTeamPanel::new(Rc::clone(&callback_registry), Rc::clone(&gif_map));
         let new_team_screen =
@@ -105,6 +109,7 @@ impl Ui {
         ui_tabs.push(UiTab::Player);
         ui_tabs.push(UiTab::Galaxy);
         ui_tabs.push(UiTab::Game);
+        ui_tabs.push(UiTab::League);
 
         if !disable_network {
             ui_tabs.push(UiTab::Swarm);
@@ -129,6 +134,7 @@ impl Ui {
             player_panel,
             team_panel,
             game_panel,
+            league_panel,
             swarm_panel,
             my_team_panel,
             galaxy_panel,
@@ -180,6 +186,7 @@ impl Ui {
                 UiTab::Player => &self.player_panel,
                 UiTab::Galaxy => &self.galaxy_panel,
                 UiTab::Game => &self.game_panel,
+                UiTab::League => &self.league_panel,
                 UiTab::Swarm => &self.swarm_panel,
             },
         }
@@ -195,6 +202,7 @@ impl Ui {
                 UiTab::Player => Some(&mut self.player_panel),
                 UiTab::Galaxy => Some(&mut self.galaxy_panel),
                 UiTab::Game => Some(&mut self.game_panel),
+                UiTab::League => Some(&mut self.league_panel),
                 UiTab::Swarm => Some(&mut self.swarm_panel),
             },
         }
@@ -210,6 +218,7 @@ impl Ui {
                 UiTab::Player => &mut self.player_panel,
                 UiTab::Galaxy => &mut self.galaxy_panel,
                 UiTab::Game => &mut self.game_panel,
+                UiTab::League => &mut self.league_panel,
                 UiTab::Swarm => &mut self.swarm_panel,
             },
         }
@@ -292,6 +301,7 @@ impl Ui {
                 self.team_panel.update(world)?;
                 self.player_panel.update(world)?;
                 self.game_panel.update(world)?;
+                self.league_panel.update(world)?;
                 self.galaxy_panel.update(world)?;
             }
         }
@@ -9,15 +9,19 @@ use super::{
 };
 use crate::{
     app::App,
-    engine::{tactic::OffenseTactic, types::TeamInGame},
+    engine::{
+        tactic::{DefenseTactic, OffenseTactic},
+        types::TeamInGame,
+    },
     image::color_map::{ColorMap, ColorPreset},
-    network::{constants::DEFAULT_PORT, types::Challenge},
+    network::{config::parse_address, types::Challenge},
+    store::{current_profile, load_game, profiles, set_profile},
     types::{
         AppCallback, AppResult, GameId, IdSystem, PlanetId, PlayerId, SystemTimeTick, TeamId, Tick,
         SECONDS,
     },
     world::{
-        jersey::{Jersey, JerseyStyle},
+        jersey::JerseyStyle,
         role::CrewRole,
         spaceship::Spaceship,
         team::Team,
@@ -76,6 +80,11 @@ pub enum UiCallbackPreset {
         tactic: OffenseTactic,
     },
     SetNextTeamOffenseTactic,
+    SetTeamDefenseTactic {
+        tactic: DefenseTactic,
+    },
+    SetNextTeamDefenseTactic,
+    ToggleLeagueParticipation,
     NextUiTab,
     PreviousUiTab,
     SetUiTab {
@@ -86,6 +95,10 @@ pub enum UiCallbackPreset {
     CloseUiPopup,
     NewGame,
     ContinueGame,
+    RecoverBackup {
+        filename: String,
+    },
+    NextProfile,
     QuitGame,
     ToggleAudio,
     SetSwarmPanelTopic {
@@ -131,6 +144,10 @@ pub enum UiCallbackPreset {
     TravelToPlanet {
         planet_id: PlanetId,
     },
+    ReplayGame {
+        game_id: GameId,
+    },
+    BuyFuel,
     ZoomInToPlanet {
         planet_id: PlanetId,
     },
@@ -343,6 +360,8 @@ impl UiCallbackPreset {
             let team = app.world.get_team_or_err(team_id)?;
 
             own_team.can_challenge_team(team)?;
+            app.world.can_field_team(own_team)?;
+            app.world.can_field_team(team)?;
 
             if let Some(peer_id) = team.peer_id {
                 // if !app
@@ -362,17 +381,13 @@ impl UiCallbackPreset {
             }
             let (home_team_in_game, away_team_in_game) = match rand::thread_rng().gen_range(0..=1) {
                 0 => (
-                    TeamInGame::from_team_id(own_team_id, &app.world.teams, &app.world.players)
-                        .ok_or(format!("Own team {:?} not found", own_team_id))?,
-                    TeamInGame::from_team_id(team_id, &app.world.teams, &app.world.players)
-                        .ok_or(format!("Team {:?} not found", team_id))?,
+                    TeamInGame::from_team_id(own_team_id, &app.world.teams, &app.world.players)?,
+                    TeamInGame::from_team_id(team_id, &app.world.teams, &app.world.players)?,
                 ),
 
                 _ => (
-                    TeamInGame::from_team_id(team_id, &app.world.teams, &app.world.players)
-                        .ok_or(format!("Team {:?} not found", team_id))?,
-                    TeamInGame::from_team_id(own_team_id, &app.world.teams, &app.world.players)
-                        .ok_or(format!("Own team {:?} not found", own_team_id))?,
+                    TeamInGame::from_team_id(team_id, &app.world.teams, &app.world.players)?,
+                    TeamInGame::from_team_id(own_team_id, &app.world.teams, &app.world.players)?,
                 ),
             };
 
@@ -560,6 +575,7 @@ impl UiCallbackPreset {
                 .travel_time_to_planet(own_team.id, target_planet.id)?;
             own_team.can_travel_to_planet(&target_planet, travel_time)?;
 
+            own_team.fuel -= own_team.spaceship.fuel_consumed(travel_time);
             own_team.current_location = TeamLocation::Travelling {
                 from: current_planet.id,
                 to: planet_id,
@@ -570,10 +586,7 @@ impl UiCallbackPreset {
             current_planet.teams.retain(|&x| x != own_team.id);
             app.world.planets.insert(current_planet.id, current_planet);
 
-            let pirate_jersey = Jersey {
-                style: JerseyStyle::Pirate,
-                color: own_team.jersey.color.clone(),
-            };
+            let pirate_jersey = own_team.current_jersey();
 
             for player in own_team.player_ids.iter() {
                 let mut player = app.world.get_player_or_err(*player)?.clone();
@@ -590,26 +603,67 @@ impl UiCallbackPreset {
         })
     }
 
+    fn replay_game(game_id: GameId) -> AppCallback {
+        Box::new(move |app: &mut App| {
+            let game = load_game(game_id)?;
+            app.ui.game_panel.start_replay(&game);
+            app.ui.switch_to(UiTab::Game);
+            Ok(None)
+        })
+    }
+
+    fn buy_fuel() -> AppCallback {
+        Box::new(move |app: &mut App| {
+            let amount = app.world.buy_fuel_for_team(app.world.own_team_id)?;
+            Ok(Some(format!("Bought {} t of fuel", amount)))
+        })
+    }
+
     fn dial(address: String) -> AppCallback {
         Box::new(move |app: &mut App| {
-            let multiaddr = match address.clone() {
-                x if x == "seed".to_string() => {
-                    app.network_handler.as_ref().unwrap().seed_address.clone()
-                }
-                _ => format!("/ip4/{address}/tcp/{DEFAULT_PORT}")
-                    .as_str()
-                    .parse()?,
-            };
-            app.network_handler
-                .as_mut()
-                .unwrap()
-                .dial(multiaddr)
-                .map_err(|e| e.to_string())?;
+            let network_handler = app.network_handler.as_mut().unwrap();
+            if address == "seed" {
+                network_handler.dial_bootstrap_peers()?;
+            } else {
+                network_handler
+                    .dial(parse_address(&address)?)
+                    .map_err(|e| e.to_string())?;
+            }
             app.world.dirty_network = true;
             Ok(None)
         })
     }
 
+    fn continue_game(backup: Option<String>) -> AppCallback {
+        Box::new(move |app: &mut App| {
+            match backup.as_ref() {
+                Some(filename) => app.recover_world_backup(filename)?,
+                None => app.load_world()?,
+            }
+            if app.world.has_own_team() {
+                app.ui.set_state(UiState::Main);
+            } else {
+                app.ui.set_state(UiState::NewTeam);
+            }
+            Ok(None)
+        })
+    }
+
+    fn next_profile() -> AppCallback {
+        Box::new(move |app: &mut App| {
+            let profiles = profiles()?;
+            let current = current_profile();
+            let next = profiles
+                .iter()
+                .position(|profile| *profile == current)
+                .map(|idx| (idx + 1) % profiles.len())
+                .unwrap_or_default();
+            set_profile(&profiles[next])?;
+            app.ui.splash_screen.refresh_profile();
+            Ok(None)
+        })
+    }
+
     fn sync() -> AppCallback {
         Box::new(move |app: &mut App| {
             app.world.dirty_network = true;
@@ -682,6 +736,32 @@ impl UiCallbackPreset {
                 app.world.dirty_network = true;
                 Ok(None)
             }
+            UiCallbackPreset::SetTeamDefenseTactic { tactic } => {
+                let own_team = app.world.get_own_team()?;
+                let mut team = own_team.clone();
+                team.game_defense_tactic = tactic.clone();
+                app.world.teams.insert(team.id, team);
+                app.world.dirty = true;
+                app.world.dirty_ui = true;
+                app.world.dirty_network = true;
+                Ok(None)
+            }
+            UiCallbackPreset::SetNextTeamDefenseTactic => {
+                let own_team = app.world.get_own_team()?;
+                let mut team = own_team.clone();
+                team.game_defense_tactic = team.game_defense_tactic.next();
+                app.world.teams.insert(team.id, team);
+                app.world.dirty = true;
+                app.world.dirty_ui = true;
+                app.world.dirty_network = true;
+                Ok(None)
+            }
+            UiCallbackPreset::ToggleLeagueParticipation => {
+                app.world.own_team_in_leagues = !app.world.own_team_in_leagues;
+                app.world.dirty = true;
+                app.world.dirty_ui = true;
+                Ok(None)
+            }
             UiCallbackPreset::ChallengeTeam { team_id } => Self::challenge_team(*team_id)(app),
             UiCallbackPreset::AcceptChallenge { challenge } => {
                 app.network_handler
@@ -720,15 +800,11 @@ impl UiCallbackPreset {
                 app.new_world();
                 Ok(None)
             }
-            UiCallbackPreset::ContinueGame => {
-                app.load_world();
-                if app.world.has_own_team() {
-                    app.ui.set_state(UiState::Main);
-                } else {
-                    app.ui.set_state(UiState::NewTeam);
-                }
-                Ok(None)
+            UiCallbackPreset::ContinueGame => Self::continue_game(None)(app),
+            UiCallbackPreset::RecoverBackup { filename } => {
+                Self::continue_game(Some(filename.clone()))(app)
             }
+            UiCallbackPreset::NextProfile => Self::next_profile()(app),
             UiCallbackPreset::QuitGame => {
                 app.quit()?;
                 Ok(None)
@@ -802,6 +878,8 @@ impl UiCallbackPreset {
             UiCallbackPreset::TravelToPlanet { planet_id } => {
                 Self::travel_to_planet(*planet_id)(app)
             }
+            UiCallbackPreset::ReplayGame { game_id } => Self::replay_game(*game_id)(app),
+            UiCallbackPreset::BuyFuel => Self::buy_fuel()(app),
             UiCallbackPreset::ZoomInToPlanet { planet_id } => {
                 Self::zoom_in_to_planet(*planet_id)(app)
             }
@@ -135,7 +135,7 @@ pub fn render_spaceship_description(
             "Consumption: {:.2} t/h",
             team.spaceship.fuel_consumption() * HOURS as f32
         )),
-        Line::from(format!("Tank: {} t", team.spaceship.tank())),
+        Line::from(format!("Fuel: {}/{} t", team.fuel, team.spaceship.tank())),
         Line::from(format!(
             "Max distance: {:.0} AU",
             team.spaceship.max_distance() / AU as f32
@@ -248,6 +248,17 @@ pub fn render_player_description(
         Line::from(vec![
             Span::raw("Energy ".to_string()),
             Span::styled(format!("{}", energy_string), energy_style),
+            Span::raw(format!(" Morale {:.0}", player.morale)),
+            if player.is_dead() {
+                Span::styled(" Dead", UiStyle::ERROR)
+            } else if let Some(injury) = player.injury {
+                Span::styled(
+                    format!(" {} injury {:.0}%", injury.as_str(), injury.severity),
+                    UiStyle::ERROR,
+                )
+            } else {
+                Span::raw("")
+            },
         ]),
         Line::from(format!(
             "{} yo, {} cm, {} kg, {}",
@@ -8,6 +8,12 @@ pub const EXPERIENCE_PER_SKILL_MULTIPLIER: f32 = 0.00001;
 pub const REPUTATION_PER_EXPERIENCE: f32 = 0.00005;
 pub const REPUTATION_DECREASE_PER_LONG_TICK: f32 = 0.1;
 pub const AGE_INCREASE_PER_LONG_TICK: f32 = 0.025;
+pub const MAX_MORALE: f32 = 100.0;
+pub const DEFAULT_MORALE: f32 = 50.0;
+pub const MORALE_DECAY_PER_LONG_TICK: f32 = 2.0;
+pub const MORALE_GAME_RESULT_BONUS: f32 = 5.0;
+pub const MORALE_MINUTES_THRESHOLD: f32 = 10.0;
+pub const MORALE_MAX_PLAYING_TIME_BONUS: f32 = 3.0;
 
 pub const INCOME_PER_ATTENDEE_HOME: u32 = 12;
 pub const INCOME_PER_ATTENDEE_AWAY: u32 = 12;
@@ -15,9 +21,14 @@ pub const INCOME_PER_ATTENDEE_AWAY: u32 = 12;
 pub const INITIAL_TEAM_BALANCE: u32 = 150_000;
 pub const CURRENCY_SYMBOL: &str = "sat";
 pub const COST_PER_VALUE: u32 = 34;
+pub const FUEL_COST_PER_TONNE: u32 = 2;
 
 pub const AUTO_GENERATE_GAMES_NUMBER: usize = 3;
 
+pub const LEAGUE_MIN_TEAMS: usize = 4;
+pub const LEAGUE_PLAYOFF_TEAMS: usize = 4;
+pub const LEAGUE_FORFEIT_SCORE: u16 = 20;
+
 const DEBUG_TIME_MULTIPLIER: Tick = 1;
 pub const BASE_DISTANCES: [u128; 3] = [1 * LIGHT_YEAR, 1 * AU, 300_000 * KILOMETERS];
 pub const BASE_TANK_CAPACITY: u32 = 50;
@@ -37,6 +48,7 @@ impl TickInterval {
 }
 
 pub const BASE_GAME_START_DELAY: Tick = 10 * SECONDS;
+pub const ENDED_NETWORK_GAME_EXPIRY: Tick = 5 * MINUTES;
 
 static GALAXY_ROOT_STR: &str = "71a43700-0000-0000-0000-000000000000";
 static DEFAULT_PLANET_STR: &str = "71a43700-0000-0000-0002-000000000000";
@@ -46,3 +58,9 @@ pub const GALAXY_ROOT_ID: Lazy<PlanetId> =
 pub const DEFAULT_PLANET_ID: Lazy<PlanetId> =
     Lazy::new(|| PlanetId::try_parse(DEFAULT_PLANET_STR).unwrap());
 pub const SOL_ID: Lazy<PlanetId> = Lazy::new(|| PlanetId::try_parse(SOL_STR).unwrap());
+
+pub const RECOVERY_PHASE_FACTORS: [f32; 3] = [1.5, 1.0, 0.5];
+pub const MAX_INJURY_SEVERITY: f32 = 100.0;
+pub const INJURY_SEVERITY_PER_KNOCKOUT: f32 = 40.0;
+pub const EXTREME_TIREDNESS_FOR_INJURY: f32 = 90.0;
+pub const INJURY_RECOVERY_PER_MEDIUM_TICK: f32 = 0.02;
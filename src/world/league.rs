@@ -0,0 +1,336 @@
+use super::constants::{TickInterval, LEAGUE_PLAYOFF_TEAMS};
+use crate::types::{GameId, PlanetId, TeamId, Tick};
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+use strum_macros::Display;
+
+#[derive(Debug, Clone, Copy, Default, Display, Serialize, Deserialize, PartialEq)]
+pub enum LeaguePhase {
+    #[default]
+    RegularSeason,
+    Playoffs,
+    Finished,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Fixture {
+    pub round: usize,
+    pub home_team_id: TeamId,
+    pub away_team_id: TeamId,
+    pub game_id: Option<GameId>,
+    pub score: Option<(u16, u16)>,
+    pub is_playoff: bool,
+}
+
+impl Fixture {
+    fn new(round: usize, home_team_id: TeamId, away_team_id: TeamId, is_playoff: bool) -> Self {
+        Self {
+            round,
+            home_team_id,
+            away_team_id,
+            game_id: None,
+            score: None,
+            is_playoff,
+        }
+    }
+
+    pub fn is_played(&self) -> bool {
+        self.score.is_some()
+    }
+
+    pub fn is_pending(&self) -> bool {
+        self.game_id.is_none() && self.score.is_none()
+    }
+
+    pub fn involves(&self, team_id: TeamId) -> bool {
+        self.home_team_id == team_id || self.away_team_id == team_id
+    }
+
+    /// The home team, which is the higher seed in the playoffs, wins ties.
+    pub fn winner(&self) -> Option<TeamId> {
+        let (home_score, away_score) = self.score?;
+        if away_score > home_score {
+            Some(self.away_team_id)
+        } else {
+            Some(self.home_team_id)
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct Standing {
+    pub team_id: TeamId,
+    pub played: u16,
+    pub wins: u16,
+    pub losses: u16,
+    pub ties: u16,
+    pub points_for: u32,
+    pub points_against: u32,
+}
+
+impl Standing {
+    pub fn points(&self) -> u16 {
+        2 * self.wins + self.ties
+    }
+
+    pub fn point_difference(&self) -> i64 {
+        self.points_for as i64 - self.points_against as i64
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct League {
+    pub region: PlanetId,
+    pub name: String,
+    pub season: u16,
+    pub started_at: Tick,
+    pub team_ids: Vec<TeamId>,
+    pub fixtures: Vec<Fixture>,
+    pub phase: LeaguePhase,
+    pub champion: Option<TeamId>,
+}
+
+impl League {
+    pub fn new(
+        region: PlanetId,
+        name: String,
+        season: u16,
+        team_ids: Vec<TeamId>,
+        started_at: Tick,
+    ) -> Self {
+        let fixtures = Self::round_robin(&team_ids);
+        Self {
+            region,
+            name,
+            season,
+            started_at,
+            team_ids,
+            fixtures,
+            ..Default::default()
+        }
+    }
+
+    /// Schedules every team against every other team once, using the circle method.
+    /// With an odd number of teams, one team rests each round.
+    fn round_robin(team_ids: &[TeamId]) -> Vec<Fixture> {
+        let mut slots = team_ids.iter().map(|&id| Some(id)).collect::<Vec<_>>();
+        if slots.len() % 2 == 1 {
+            slots.push(None);
+        }
+        let number_of_slots = slots.len();
+        let mut fixtures = vec![];
+        for round in 0..number_of_slots.saturating_sub(1) {
+            for idx in 0..number_of_slots / 2 {
+                if let (Some(a), Some(b)) = (slots[idx], slots[number_of_slots - 1 - idx]) {
+                    // Alternate home and away so that no team always plays at home.
+                    let (home, away) = if (round + idx) % 2 == 0 { (a, b) } else { (b, a) };
+                    fixtures.push(Fixture::new(round, home, away, false));
+                }
+            }
+            // Keep the first slot fixed and rotate the others.
+            slots[1..].rotate_right(1);
+        }
+        fixtures
+    }
+
+    pub fn round_start(&self, round: usize) -> Tick {
+        self.started_at + round as Tick * TickInterval::LONG
+    }
+
+    pub fn last_round(&self) -> usize {
+        self.fixtures.iter().map(|f| f.round).max().unwrap_or_default()
+    }
+
+    pub fn current_round(&self) -> usize {
+        self.fixtures
+            .iter()
+            .filter(|f| !f.is_played())
+            .map(|f| f.round)
+            .min()
+            .unwrap_or(self.last_round())
+    }
+
+    pub fn next_season_at(&self) -> Tick {
+        self.round_start(self.last_round() + 1)
+    }
+
+    /// Returns the regular season standings, best team first.
+    pub fn standings(&self) -> Vec<Standing> {
+        let mut standings = self
+            .team_ids
+            .iter()
+            .map(|&team_id| Standing {
+                team_id,
+                ..Default::default()
+            })
+            .collect::<Vec<Standing>>();
+
+        for fixture in self.fixtures.iter().filter(|f| !f.is_playoff) {
+            let Some((home_score, away_score)) = fixture.score else {
+                continue;
+            };
+            for standing in standings.iter_mut() {
+                let (own_score, other_score) = if standing.team_id == fixture.home_team_id {
+                    (home_score, away_score)
+                } else if standing.team_id == fixture.away_team_id {
+                    (away_score, home_score)
+                } else {
+                    continue;
+                };
+                standing.played += 1;
+                standing.points_for += own_score as u32;
+                standing.points_against += other_score as u32;
+                match own_score.cmp(&other_score) {
+                    std::cmp::Ordering::Greater => standing.wins += 1,
+                    std::cmp::Ordering::Less => standing.losses += 1,
+                    std::cmp::Ordering::Equal => standing.ties += 1,
+                }
+            }
+        }
+
+        standings
+            .into_iter()
+            .sorted_by(|a, b| {
+                b.points()
+                    .cmp(&a.points())
+                    .then(b.point_difference().cmp(&a.point_difference()))
+                    .then(b.points_for.cmp(&a.points_for))
+            })
+            .collect()
+    }
+
+    pub fn record_result(&mut self, game_id: GameId, score: (u16, u16)) -> bool {
+        if let Some(fixture) = self
+            .fixtures
+            .iter_mut()
+            .find(|f| f.game_id == Some(game_id) && !f.is_played())
+        {
+            fixture.score = Some(score);
+            return true;
+        }
+        false
+    }
+
+    /// Moves the league to the playoffs once the regular season is over,
+    /// schedules the next playoff round and crowns the champion after the final.
+    pub fn advance(&mut self) {
+        if self.phase == LeaguePhase::Finished
+            || self.fixtures.iter().any(|fixture| !fixture.is_played())
+        {
+            return;
+        }
+
+        let next_round = if self.fixtures.is_empty() {
+            0
+        } else {
+            self.last_round() + 1
+        };
+        let seeds = self
+            .standings()
+            .iter()
+            .map(|standing| standing.team_id)
+            .collect::<Vec<TeamId>>();
+
+        let qualified = match self.phase {
+            LeaguePhase::RegularSeason => {
+                self.phase = LeaguePhase::Playoffs;
+                seeds
+                    .iter()
+                    .take(LEAGUE_PLAYOFF_TEAMS.min(seeds.len()))
+                    .cloned()
+                    .collect::<Vec<TeamId>>()
+            }
+            _ => {
+                let last_round = self.last_round();
+                self.fixtures
+                    .iter()
+                    .filter(|f| f.is_playoff && f.round == last_round)
+                    .filter_map(|f| f.winner())
+                    .sorted_by_key(|id| seeds.iter().position(|seed| seed == id))
+                    .collect()
+            }
+        };
+
+        if qualified.len() < 2 {
+            self.champion = qualified.first().copied();
+            self.phase = LeaguePhase::Finished;
+            return;
+        }
+
+        // Best seed against worst seed, the better seed plays at home.
+        for idx in 0..qualified.len() / 2 {
+            self.fixtures.push(Fixture::new(
+                next_round,
+                qualified[idx],
+                qualified[qualified.len() - 1 - idx],
+                true,
+            ));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{League, LeaguePhase};
+    use crate::types::{GameId, IdSystem, PlanetId, TeamId};
+
+    #[test]
+    fn test_league_season() {
+        let team_ids = (0..5).map(|_| TeamId::new()).collect::<Vec<TeamId>>();
+        let mut league = League::new(PlanetId::new(), "Test".into(), 1, team_ids.clone(), 0);
+
+        // Every team meets every other team exactly once.
+        assert_eq!(league.fixtures.len(), 5 * 4 / 2);
+        for a in team_ids.iter() {
+            for b in team_ids.iter().filter(|&b| b != a) {
+                let meetings = league
+                    .fixtures
+                    .iter()
+                    .filter(|f| f.involves(*a) && f.involves(*b))
+                    .count();
+                assert_eq!(meetings, 1);
+            }
+        }
+
+        // No team plays twice in the same round.
+        for round in 0..=league.last_round() {
+            for team_id in team_ids.iter() {
+                let games = league
+                    .fixtures
+                    .iter()
+                    .filter(|f| f.round == round && f.involves(*team_id))
+                    .count();
+                assert!(games <= 1);
+            }
+        }
+
+        let play_all = |league: &mut League| {
+            for fixture in league.fixtures.iter_mut().filter(|f| !f.is_played()) {
+                let game_id = GameId::new();
+                fixture.game_id = Some(game_id);
+                let home_idx = team_ids.iter().position(|&id| id == fixture.home_team_id);
+                let away_idx = team_ids.iter().position(|&id| id == fixture.away_team_id);
+                // Lower indexes are stronger teams.
+                if home_idx < away_idx {
+                    fixture.score = Some((90, 80));
+                } else {
+                    fixture.score = Some((80, 90));
+                }
+            }
+            league.advance();
+        };
+
+        play_all(&mut league);
+        assert_eq!(league.phase, LeaguePhase::Playoffs);
+        let standings = league.standings();
+        assert_eq!(standings[0].team_id, team_ids[0]);
+        assert_eq!(standings[0].wins, 4);
+        assert_eq!(standings[4].losses, 4);
+
+        play_all(&mut league);
+        assert_eq!(league.phase, LeaguePhase::Playoffs);
+        play_all(&mut league);
+        assert_eq!(league.phase, LeaguePhase::Finished);
+        assert_eq!(league.champion, Some(team_ids[0]));
+    }
+}
@@ -0,0 +1,94 @@
+use super::spaceship::Spaceship;
+use crate::store::Migration;
+use crate::types::AppResult;
+use serde_json::Value;
+
+// Migrations are applied in order, the one at index N upgrades saves from version N to N + 1.
+// New migrations must be appended, since the current version is the number of migrations.
+pub const WORLD_MIGRATIONS: &[Migration] = &[fill_fuel_tanks, accept_full_save];
+pub const GAME_MIGRATIONS: &[Migration] = &[accept_full_save];
+// Exported team files. Version 0 are plain team files, like the ones used by the simulator.
+pub const TEAM_MIGRATIONS: &[Migration] = &[fill_exported_fuel_tank];
+
+// Version 0 -> 1: teams saved before fuel was introduced would be stranded with an empty tank.
+fn fill_fuel_tanks(world: &mut Value) -> AppResult<()> {
+    let Some(teams) = world.get_mut("teams").and_then(|t| t.as_object_mut()) else {
+        return Ok(());
+    };
+    for team in teams.values_mut() {
+        fill_fuel_tank(team)?;
+    }
+    Ok(())
+}
+
+// World 1 -> 2, game 0 -> 1: saves are compacted from now on, so that older versions refuse them.
+// Full saves are still valid, the data left out of compact saves is rebuilt when loading.
+fn accept_full_save(_: &mut Value) -> AppResult<()> {
+    Ok(())
+}
+
+// Version 0 -> 1: same as for the world, for a single exported team.
+fn fill_exported_fuel_tank(network_team: &mut Value) -> AppResult<()> {
+    fill_fuel_tank(network_team.get_mut("team").ok_or("Missing team")?)
+}
+
+fn fill_fuel_tank(team: &mut Value) -> AppResult<()> {
+    let team = team.as_object_mut().ok_or("Invalid team")?;
+    if team.contains_key("fuel") {
+        return Ok(());
+    }
+    let spaceship: Spaceship =
+        serde_json::from_value(team.get("spaceship").cloned().ok_or("Team without spaceship")?)?;
+    team.insert("fuel".into(), spaceship.tank().into());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{GAME_MIGRATIONS, WORLD_MIGRATIONS};
+    use crate::engine::game::Game;
+    use crate::store::migrate;
+    use crate::world::constants::DEFAULT_MORALE;
+    use crate::world::world::World;
+    use serde_json::{json, Value};
+
+    fn fixture(version: usize) -> Value {
+        let data = match version {
+            0 => include_str!("../../tests/fixtures/world_v0.json"),
+            1 => include_str!("../../tests/fixtures/world_v1.json"),
+            2 => include_str!("../../tests/fixtures/world_v2.json"),
+            _ => unreachable!(),
+        };
+        serde_json::from_str(data).unwrap()
+    }
+
+    #[test]
+    fn test_world_migrations() {
+        let full_world: World = migrate(fixture(1), WORLD_MIGRATIONS).unwrap();
+        // There is a fixture for every past version and for the current one.
+        for version in 0..=WORLD_MIGRATIONS.len() {
+            let world = World::from_store(migrate(fixture(version), WORLD_MIGRATIONS).unwrap());
+            assert!(world.has_own_team());
+            let team = world.get_own_team().unwrap();
+            assert_eq!(team.fuel, team.spaceship.tank());
+            for player_id in team.player_ids.iter() {
+                let player = world.get_player_or_err(*player_id).unwrap();
+                assert_eq!(player.morale, DEFAULT_MORALE);
+                assert!(player.injury.is_none());
+                // Compact saves leave out jerseys, which are rebuilt from the team.
+                assert_eq!(player.image, full_world.get_player_or_err(*player_id).unwrap().image);
+            }
+        }
+
+        // Migrations only fill in missing data.
+        let mut v1 = fixture(1);
+        let team_id = v1["data"]["own_team_id"].as_str().unwrap().to_string();
+        v1["data"]["teams"][&team_id]["fuel"] = json!(3);
+        let world: World = migrate(v1, WORLD_MIGRATIONS).unwrap();
+        assert_eq!(world.get_own_team().unwrap().fuel, 3);
+
+        let newer = json!({ "version": WORLD_MIGRATIONS.len() + 1, "data": {} });
+        assert!(migrate::<World>(newer, WORLD_MIGRATIONS).is_err());
+        assert!(migrate::<Game>(json!({ "version": 0, "data": {} }), GAME_MIGRATIONS).is_err());
+    }
+}
@@ -1,5 +1,7 @@
 pub mod constants;
 pub mod jersey;
+pub mod league;
+pub mod migrations;
 pub mod planet;
 pub mod player;
 pub mod position;
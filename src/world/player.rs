@@ -1,11 +1,16 @@
 use super::{
-    constants::{COST_PER_VALUE, EXPERIENCE_PER_SKILL_MULTIPLIER, REPUTATION_PER_EXPERIENCE},
+    constants::{
+        COST_PER_VALUE, DEFAULT_MORALE, EXPERIENCE_PER_SKILL_MULTIPLIER,
+        EXTREME_TIREDNESS_FOR_INJURY, INJURY_SEVERITY_PER_KNOCKOUT, MAX_MORALE,
+        MORALE_GAME_RESULT_BONUS, MORALE_MAX_PLAYING_TIME_BONUS, MORALE_MINUTES_THRESHOLD,
+        REPUTATION_PER_EXPERIENCE,
+    },
     jersey::Jersey,
     planet::Planet,
     position::{GamePosition, PlayingStyle, MAX_POSITION},
     role::CrewRole,
     skill::{GameSkill, Skill, MAX_SKILL},
-    types::{PlayerLocation, Pronoun, TrainingFocus},
+    types::{Injury, PlayerLocation, Pronoun, TrainingFocus},
     utils::PLAYER_DATA,
 };
 use crate::{
@@ -24,6 +29,7 @@ use rand::{seq::SliceRandom, Rng};
 use rand_chacha::ChaCha8Rng;
 use rand_distr::{Distribution, Normal};
 use serde::{de::Visitor, ser::SerializeStruct, Deserialize, Serialize};
+use std::cmp::Ordering;
 
 const HOOK_MAX_BALL_HANDLING: f32 = 4.0;
 const EYE_PATCH_MAX_VISION: f32 = 4.0;
@@ -49,6 +55,8 @@ pub struct Player {
     pub previous_skills: [Skill; 20], // This is for displaying purposes to show the skills that were recently modified
     pub training_focus: Option<TrainingFocus>,
     pub tiredness: f32,
+    pub morale: f32,
+    pub injury: Option<Injury>,
 }
 
 impl Serialize for Player {
@@ -57,7 +65,7 @@ impl Serialize for Player {
         // and serialize them in a vector which is then deserialized
         // into the corresponding fields
         let compact_skills = self.current_skill_array().to_vec();
-        let mut state = serializer.serialize_struct("Player", 14)?;
+        let mut state = serializer.serialize_struct("Player", 16)?;
         state.serialize_field("id", &self.id)?;
         state.serialize_field("peer_id", &self.peer_id)?;
         state.serialize_field("version", &self.version)?;
@@ -72,6 +80,8 @@ impl Serialize for Player {
         state.serialize_field("training_focus", &self.training_focus)?;
         state.serialize_field("tiredness", &self.tiredness)?;
         state.serialize_field("compact_skills", &compact_skills)?;
+        state.serialize_field("morale", &self.morale)?;
+        state.serialize_field("injury", &self.injury)?;
         state.end()
     }
 }
@@ -96,6 +106,8 @@ impl<'de> Deserialize<'de> for Player {
             TrainingFocus,
             Tiredness,
             CompactSkills,
+            Morale,
+            Injury,
         }
 
         impl<'de> Deserialize<'de> for Field {
@@ -128,6 +140,8 @@ impl<'de> Deserialize<'de> for Player {
                             "training_focus" => Ok(Field::TrainingFocus),
                             "tiredness" => Ok(Field::Tiredness),
                             "compact_skills" => Ok(Field::CompactSkills),
+                            "morale" => Ok(Field::Morale),
+                            "injury" => Ok(Field::Injury),
                             _ => Err(serde::de::Error::unknown_field(value, FIELDS)),
                         }
                     }
@@ -192,6 +206,9 @@ impl<'de> Deserialize<'de> for Player {
                 let compact_skills: Vec<Skill> = seq
                     .next_element()?
                     .ok_or_else(|| serde::de::Error::invalid_length(13, &self))?;
+                // Morale was added later, older players default to neutral morale.
+                let morale = seq.next_element()?.unwrap_or(DEFAULT_MORALE);
+                let injury = seq.next_element()?.unwrap_or_default();
 
                 let mut player = Player {
                     id,
@@ -212,6 +229,8 @@ impl<'de> Deserialize<'de> for Player {
                     previous_skills,
                     training_focus,
                     tiredness,
+                    morale,
+                    injury,
                 };
 
                 player.athleticism = Athleticism {
@@ -266,6 +285,8 @@ impl<'de> Deserialize<'de> for Player {
                 let mut training_focus = None;
                 let mut tiredness = None;
                 let mut compact_skills: Option<Vec<Skill>> = None;
+                let mut morale = None;
+                let mut injury = None;
 
                 while let Some(key) = map.next_key()? {
                     match key {
@@ -353,6 +374,18 @@ impl<'de> Deserialize<'de> for Player {
                             }
                             compact_skills = Some(map.next_value()?);
                         }
+                        Field::Morale => {
+                            if morale.is_some() {
+                                return Err(serde::de::Error::duplicate_field("morale"));
+                            }
+                            morale = Some(map.next_value()?);
+                        }
+                        Field::Injury => {
+                            if injury.is_some() {
+                                return Err(serde::de::Error::duplicate_field("injury"));
+                            }
+                            injury = Some(map.next_value()?);
+                        }
                     }
                 }
 
@@ -378,6 +411,8 @@ impl<'de> Deserialize<'de> for Player {
                     tiredness.ok_or_else(|| serde::de::Error::missing_field("tiredness"))?;
                 let compact_skills = compact_skills
                     .ok_or_else(|| serde::de::Error::missing_field("compact_skills"))?;
+                let morale = morale.unwrap_or(DEFAULT_MORALE);
+                let injury = injury.unwrap_or_default();
 
                 let mut player = Player {
                     id,
@@ -398,6 +433,8 @@ impl<'de> Deserialize<'de> for Player {
                     previous_skills,
                     training_focus,
                     tiredness,
+                    morale,
+                    injury,
                 };
 
                 player.athleticism = Athleticism {
@@ -450,6 +487,8 @@ impl<'de> Deserialize<'de> for Player {
             "training_focus",
             "tiredness",
             "compact_skills",
+            "morale",
+            "injury",
         ];
         deserializer.deserialize_struct("Player", FIELDS, PlayerVisitor)
     }
@@ -534,6 +573,8 @@ impl Player {
             previous_skills: [Skill::default(); 20],
             training_focus: None,
             tiredness: 0.0,
+            morale: DEFAULT_MORALE,
+            injury: None,
         };
 
         player
@@ -742,10 +783,18 @@ impl Player {
 
     pub fn apply_end_of_game_logic(&mut self, stats: &GameStats) {
         self.version += 1;
+        if stats.injury_severity > 0.0 {
+            self.add_injury(stats.injury_severity);
+        }
         if stats.is_knocked_out() {
             self.tiredness = MAX_TIREDNESS;
+            self.add_injury(INJURY_SEVERITY_PER_KNOCKOUT);
             return;
         }
+        // Playing while exhausted can cause injuries.
+        if stats.tiredness >= EXTREME_TIREDNESS_FOR_INJURY {
+            self.add_injury(stats.tiredness - EXTREME_TIREDNESS_FOR_INJURY + 10.0);
+        }
         let experience_at_position = stats.experience_at_position;
         self.reputation = (self.reputation
             + REPUTATION_PER_EXPERIENCE / self.reputation
@@ -780,6 +829,36 @@ impl Player {
             self.modify_skill(idx, increment);
         }
     }
+
+    pub fn add_injury(&mut self, severity: f32) {
+        let current_severity = self.injury.map(|injury| injury.severity).unwrap_or(0.0);
+        self.injury = Some(Injury {
+            severity: current_severity + severity,
+        });
+    }
+
+    pub fn is_injured(&self) -> bool {
+        self.injury.is_some()
+    }
+
+    pub fn is_dead(&self) -> bool {
+        self.injury.map(|injury| injury.is_fatal()).unwrap_or(false)
+    }
+
+    pub fn apply_morale_update(&mut self, stats: &GameStats, game_result: Ordering) {
+        let result_bonus = match game_result {
+            Ordering::Greater => MORALE_GAME_RESULT_BONUS,
+            Ordering::Equal => 0.0,
+            Ordering::Less => -MORALE_GAME_RESULT_BONUS,
+        };
+        // Players who barely played get frustrated, players with many minutes are happy.
+        let minutes_played = stats.seconds_played as f32 / 60.0;
+        let playing_time_bonus = ((minutes_played - MORALE_MINUTES_THRESHOLD) / 4.0).clamp(
+            -MORALE_MAX_PLAYING_TIME_BONUS,
+            MORALE_MAX_PLAYING_TIME_BONUS,
+        );
+        self.morale = (self.morale + result_bonus + playing_time_bonus).clamp(0.0, MAX_MORALE);
+    }
 }
 
 impl Rated for Player {
@@ -852,3 +931,45 @@ impl InfoStats {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::Player;
+    use crate::{
+        engine::types::GameStats,
+        types::{IdSystem, PlayerId},
+        world::{
+            constants::{DEFAULT_MORALE, MAX_MORALE},
+            planet::Planet,
+        },
+    };
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+    use std::cmp::Ordering;
+
+    #[test]
+    fn test_player_morale() {
+        let rng = &mut ChaCha8Rng::seed_from_u64(0);
+        let mut player = Player::random(rng, PlayerId::new(), None, &Planet::default(), 0.0);
+        assert_eq!(player.morale, DEFAULT_MORALE);
+
+        let mut stats = GameStats::default();
+        stats.seconds_played = 30 * 60;
+        player.apply_morale_update(&stats, Ordering::Greater);
+        assert!(player.morale > DEFAULT_MORALE);
+
+        let morale = player.morale;
+        player.apply_morale_update(&GameStats::default(), Ordering::Less);
+        assert!(player.morale < morale);
+
+        player.morale = MAX_MORALE;
+        player.apply_morale_update(&stats, Ordering::Greater);
+        assert_eq!(player.morale, MAX_MORALE);
+
+        // Players saved before morale was introduced get the default one.
+        let mut value = serde_json::to_value(&player).unwrap();
+        value.as_object_mut().unwrap().remove("morale");
+        let player: Player = serde_json::from_value(value).unwrap();
+        assert_eq!(player.morale, DEFAULT_MORALE);
+    }
+}
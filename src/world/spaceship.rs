@@ -313,6 +313,11 @@ impl Spaceship {
         // Return the max travel time in milliseconds (Ticks)
         (self.tank() as f32 / self.fuel_consumption()) as Tick
     }
+
+    pub fn fuel_consumed(&self, travel_time: Tick) -> u32 {
+        // Returns the fuel consumed in tonnes over the travel time
+        (self.fuel_consumption() * travel_time as f32).ceil() as u32
+    }
 }
 
 #[derive(Display, Debug, Clone, Copy, PartialEq, Eq, Hash, EnumIter)]
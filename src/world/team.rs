@@ -1,6 +1,6 @@
 use super::{
-    constants::MIN_PLAYERS_PER_TEAM,
-    jersey::Jersey,
+    constants::{FUEL_COST_PER_TONNE, MIN_PLAYERS_PER_TEAM},
+    jersey::{Jersey, JerseyStyle},
     planet::Planet,
     player::Player,
     position::{GamePosition, MAX_POSITION},
@@ -37,6 +37,8 @@ pub struct Team {
     pub balance: u32,
     pub max_jersey_number: u8,
     pub spaceship: Spaceship,
+    #[serde(default)]
+    pub fuel: u32,
     pub home_planet: PlanetId,
     pub current_location: TeamLocation,
     pub peer_id: Option<PeerId>,
@@ -50,6 +52,8 @@ impl Team {
         let jersey = Jersey::random();
         let ship_name = format!("{}shipp", name);
         let ship_color = jersey.color;
+        let spaceship = Spaceship::random(ship_name, ship_color);
+        let fuel = spaceship.tank();
         Self {
             id,
             name,
@@ -58,7 +62,8 @@ impl Team {
             current_location: TeamLocation::OnPlanet {
                 planet_id: home_planet,
             },
-            spaceship: Spaceship::random(ship_name, ship_color),
+            spaceship,
+            fuel,
             game_offense_tactic: OffenseTactic::random(),
             game_defense_tactic: DefenseTactic::random(),
             ..Default::default()
@@ -226,9 +231,34 @@ impl Team {
             return Err("This planet is too far".into());
         }
 
+        if self.spaceship.fuel_consumed(travel_time) > self.fuel {
+            return Err("Not enough fuel".into());
+        }
+
+        Ok(())
+    }
+
+    pub fn can_buy_fuel(&self) -> AppResult<()> {
+        if self.is_travelling() {
+            return Err("Team is travelling".into());
+        }
+
+        if self.fuel >= self.spaceship.tank() {
+            return Err("Tank is full".into());
+        }
+
+        if self.balance < FUEL_COST_PER_TONNE {
+            return Err("Not enough money".into());
+        }
+
         Ok(())
     }
 
+    pub fn max_fuel_purchase(&self) -> u32 {
+        // Returns the amount of fuel in tonnes that the team can buy with its balance
+        (self.spaceship.tank().saturating_sub(self.fuel)).min(self.balance / FUEL_COST_PER_TONNE)
+    }
+
     pub fn can_change_training_focus(&self) -> AppResult<()> {
         if self.current_game.is_some() {
             return Err("Team is currently playing".into());
@@ -240,6 +270,18 @@ impl Team {
         matches!(self.current_location, TeamLocation::Travelling { .. })
     }
 
+    /// Teams wear pirate jerseys in their colors while travelling.
+    pub fn current_jersey(&self) -> Jersey {
+        if self.is_travelling() {
+            Jersey {
+                style: JerseyStyle::Pirate,
+                color: self.jersey.color,
+            }
+        } else {
+            self.jersey.clone()
+        }
+    }
+
     pub fn add_player(&mut self, player: &mut Player) {
         if self.player_ids.contains(&player.id) {
             return;
@@ -327,8 +369,11 @@ impl Team {
 #[cfg(test)]
 mod tests {
     use crate::{
-        types::{IdSystem, TeamId},
-        world::{planet::Planet, utils::TEAM_DATA},
+        types::{IdSystem, TeamId, HOURS},
+        world::{
+            planet::Planet,
+            utils::{PLANET_DATA, TEAM_DATA},
+        },
     };
 
     #[test]
@@ -338,4 +383,28 @@ mod tests {
         let team = super::Team::random(TeamId::new(), Planet::default().id, name);
         println!("{:?}", team);
     }
+
+    #[test]
+    fn test_team_travel_fuel() {
+        let data = TEAM_DATA.as_ref().unwrap();
+        let (name, _) = data.names[0].clone();
+        let mut team = super::Team::random(TeamId::new(), Planet::default().id, name);
+        let planet = PLANET_DATA
+            .as_ref()
+            .unwrap()
+            .iter()
+            .find(|p| p.total_population() > 0)
+            .unwrap();
+
+        assert_eq!(team.fuel, team.spaceship.tank());
+        assert!(team.can_travel_to_planet(planet, HOURS).is_ok());
+        assert!(team.can_buy_fuel().is_err());
+
+        team.fuel = 0;
+        assert!(team.can_travel_to_planet(planet, HOURS).is_err());
+
+        team.balance = 10;
+        assert!(team.can_buy_fuel().is_ok());
+        assert_eq!(team.max_fuel_purchase(), 10 / super::FUEL_COST_PER_TONNE);
+    }
 }
@@ -1,7 +1,8 @@
 use super::{
-    constants::DEFAULT_PLANET_ID,
+    constants::{DEFAULT_PLANET_ID, MAX_INJURY_SEVERITY},
     player::{InfoStats, Player},
     skill::GameSkill,
+    utils::recovery_phase_factor,
 };
 use crate::{
     image::color_map::SkinColorMap,
@@ -133,6 +134,50 @@ impl Population {
     }
 }
 
+#[derive(Debug, Default, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct Injury {
+    pub severity: f32,
+}
+
+impl Injury {
+    // Past 100% the player never recovers.
+    pub fn is_fatal(&self) -> bool {
+        self.severity > MAX_INJURY_SEVERITY
+    }
+
+    pub fn recovery_factor(&self) -> f32 {
+        if self.is_fatal() {
+            return 0.0;
+        }
+        recovery_phase_factor(self.severity)
+    }
+
+    /// Number of healing ticks needed to fully recover, given the healing per tick.
+    pub fn recovery_ticks(&self, healing_per_tick: f32) -> Option<u32> {
+        if self.is_fatal() || healing_per_tick <= 0.0 {
+            return None;
+        }
+        // Each recovery phase heals at a different speed.
+        let ticks = [(0.0, 50.0), (50.0, 75.0), (75.0, MAX_INJURY_SEVERITY)]
+            .iter()
+            .map(|&(low, high)| {
+                let damage_in_phase = (self.severity.min(high) - low).max(0.0);
+                damage_in_phase / (healing_per_tick * recovery_phase_factor(low))
+            })
+            .sum::<f32>();
+        Some(ticks.ceil() as u32)
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self.severity {
+            x if x > MAX_INJURY_SEVERITY => "Dead",
+            x if x >= 75.0 => "Critical",
+            x if x >= 50.0 => "Serious",
+            _ => "Light",
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, Display, Serialize, Deserialize)]
 pub enum PlayerLocation {
     WithTeam,
@@ -299,4 +344,22 @@ mod tests {
         assert_ne!(team_location, team_location2);
         assert_ne!(team_location, team_location3);
     }
+
+    #[test]
+    fn test_injury_recovery() {
+        use super::Injury;
+        let injury = Injury { severity: 40.0 };
+        assert!(!injury.is_fatal());
+        // Light injuries heal in the fast phase only.
+        assert_eq!(injury.recovery_ticks(1.0), Some(27));
+
+        let injury = Injury { severity: 100.0 };
+        assert!(!injury.is_fatal());
+        assert_eq!(injury.recovery_ticks(1.0), Some(109));
+
+        let injury = Injury { severity: 100.5 };
+        assert!(injury.is_fatal());
+        assert_eq!(injury.recovery_factor(), 0.0);
+        assert_eq!(injury.recovery_ticks(1.0), None);
+    }
 }
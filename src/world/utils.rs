@@ -1,4 +1,4 @@
-use super::{planet::Planet, skill::GameSkill};
+use super::{constants::RECOVERY_PHASE_FACTORS, planet::Planet, skill::GameSkill};
 use crate::store::ASSETS_DIR;
 use once_cell::sync::Lazy;
 use serde::Deserialize;
@@ -33,6 +33,15 @@ pub fn skill_linear_interpolation(base_skill: f32, mod_skill: f32, coords: [f32;
     (base_skill * modifier).bound()
 }
 
+/// Recovery is fast for light damage and slows down as it gets closer to 100%.
+pub fn recovery_phase_factor(value: f32) -> f32 {
+    match value {
+        x if x < 50.0 => RECOVERY_PHASE_FACTORS[0],
+        x if x < 75.0 => RECOVERY_PHASE_FACTORS[1],
+        _ => RECOVERY_PHASE_FACTORS[2],
+    }
+}
+
 pub static PLAYER_DATA: Lazy<Option<PlayerData>> = Lazy::new(|| {
     let file = ASSETS_DIR.get_file("data/players_data.json")?;
     let data = file.contents_utf8()?;
@@ -1,5 +1,6 @@
 use super::constants::*;
 use super::jersey::{Jersey, JerseyStyle};
+use super::league::{League, LeaguePhase};
 use super::planet::Planet;
 use super::player::Player;
 use super::position::Position;
@@ -8,18 +9,18 @@ use super::skill::{GameSkill, Rated};
 use super::spaceship::Spaceship;
 use super::team::Team;
 use super::types::TeamLocation;
-use super::utils::{PLANET_DATA, TEAM_DATA};
+use super::utils::{recovery_phase_factor, PLANET_DATA, TEAM_DATA};
 use crate::engine::constants::{MAX_TIREDNESS, RECOVERING_TIREDNESS_PER_SHORT_TICK};
 use crate::engine::game::{Game, GameSummary};
 use crate::engine::types::TeamInGame;
 use crate::image::color_map::ColorMap;
 use crate::network::types::{NetworkGame, NetworkTeam};
-use crate::store::{
-    load_from_json, save_to_json, PERSISTED_GAMES_PREFIX, PERSISTED_WORLD_FILENAME,
-};
+use crate::network::validation::{validate_network_team, validate_team};
+use crate::store::{load_world, save_game};
 use crate::types::*;
 use crate::world::position::MAX_POSITION;
 use crate::world::types::PlayerLocation;
+use itertools::Itertools;
 use libp2p::PeerId;
 use rand::seq::{IteratorRandom, SliceRandom};
 use rand::{Rng, SeedableRng};
@@ -44,6 +45,19 @@ pub struct World {
     pub planets: PlanetMap,
     pub games: GameMap,
     pub past_games: GameSummaryMap,
+    #[serde(default)]
+    pub leagues: LeagueMap,
+    #[serde(default)]
+    pub own_team_in_leagues: bool,
+    #[serde(skip)]
+    pub last_league_generation: HashMap<PlanetId, Tick>,
+    // When the last update of each peer team was accepted, peer data is not persisted.
+    #[serde(skip)]
+    pub network_team_received_at: HashMap<TeamId, Tick>,
+    // Ended games between peers, with the tick they ended at. They are kept
+    // for a while so that their final checksums can still be exchanged.
+    #[serde(skip)]
+    pub ended_network_games: HashMap<GameId, (Game, Tick)>,
 }
 
 impl World {
@@ -111,8 +125,8 @@ impl World {
         }
     }
 
-    pub fn load() -> Result<Self, Box<dyn std::error::Error>> {
-        load_from_json(PERSISTED_WORLD_FILENAME)
+    pub fn load() -> AppResult<Self> {
+        load_world()
     }
 
     pub fn generate_local_world(&mut self, rng: &mut ChaCha8Rng) -> AppResult<()> {
@@ -193,6 +207,7 @@ impl World {
             },
             home_planet,
             current_location,
+            fuel: spaceship.tank(),
             spaceship,
             ..Default::default()
         };
@@ -277,14 +292,7 @@ impl World {
             CrewRole::Mozzo => None,
         };
 
-        let jersey = if team.is_travelling() {
-            Jersey {
-                style: JerseyStyle::Pirate,
-                color: team.jersey.color.clone(),
-            }
-        } else {
-            team.jersey.clone()
-        };
+        let jersey = team.current_jersey();
 
         // Demote previous crew role player to mozzo.
         if current_role_player.is_some() {
@@ -438,6 +446,23 @@ impl World {
         Ok(())
     }
 
+    pub fn buy_fuel_for_team(&mut self, team_id: TeamId) -> AppResult<u32> {
+        let mut team = self.get_team_or_err(team_id)?.clone();
+        team.can_buy_fuel()?;
+
+        let amount = team.max_fuel_purchase();
+        team.balance -= amount * FUEL_COST_PER_TONNE;
+        team.fuel += amount;
+        self.teams.insert(team.id, team);
+        self.dirty = true;
+        if team_id == self.own_team_id {
+            self.dirty_network = true;
+        }
+        self.dirty_ui = true;
+
+        Ok(amount)
+    }
+
     pub fn generate_game(
         &mut self,
         game_id: GameId,
@@ -524,11 +549,17 @@ impl World {
     }
 
     pub fn add_network_team(&mut self, network_team: NetworkTeam) -> AppResult<()> {
-        let NetworkTeam { team, players } = network_team;
-        if team.peer_id.is_none() {
-            return Err("Cannot receive team without peer_id over the network.".into());
+        if let Err(err) = validate_network_team(&network_team, self) {
+            return Err(format!("Rejected team {}: {}", network_team.team.name, err).into());
         }
+        let NetworkTeam { team, players } = network_team;
         let db_team = self.get_team(team.id);
+        // Peer ids are persistent, so a known team can only be updated by the peer that owns it.
+        if let Some(db_team) = db_team {
+            if db_team.peer_id.is_some() && db_team.peer_id != team.peer_id {
+                return Err("Cannot receive team from a different peer_id.".into());
+            }
+        }
         if db_team.is_none() || db_team.unwrap().version < team.version {
             // Remove team from previous planet
             if db_team.is_some() {
@@ -552,11 +583,9 @@ impl World {
                 _ => {}
             }
 
+            self.network_team_received_at.insert(team.id, Tick::now());
             self.teams.insert(team.id, team);
             for player in players {
-                if player.peer_id.is_none() {
-                    return Err("Cannot receive player without peer_id over the network.".into());
-                }
                 let db_player = self.get_player(player.id);
                 if db_player.is_none() || db_player.unwrap().version < player.version {
                     self.players.insert(player.id, player);
@@ -567,6 +596,47 @@ impl World {
         Ok(())
     }
 
+    /// Adds a team from an exported file, as a local team or as the own team.
+    /// A previous own team stays in the world as a local team.
+    pub fn import_team(&mut self, network_team: NetworkTeam, as_own_team: bool) -> AppResult<TeamId> {
+        if let Err(err) = validate_team(&network_team, self) {
+            return Err(format!("Rejected team {}: {}", network_team.team.name, err).into());
+        }
+        let NetworkTeam { mut team, players } = network_team;
+        if self.get_team(team.id).is_some()
+            || players.iter().any(|player| self.get_player(player.id).is_some())
+        {
+            return Err(format!("Team {} is already in this world", team.name).into());
+        }
+
+        // The team is now run locally: it is not bound to a peer and starts idle on a planet.
+        team.peer_id = None;
+        team.current_game = None;
+        let planet_id = match team.current_location {
+            TeamLocation::OnPlanet { planet_id } => planet_id,
+            TeamLocation::Travelling { .. } => team.home_planet,
+        };
+        team.current_location = TeamLocation::OnPlanet { planet_id };
+        let mut planet = self.get_planet_or_err(planet_id)?.clone();
+        planet.teams.push(team.id);
+        self.planets.insert(planet.id, planet);
+
+        for mut player in players {
+            player.peer_id = None;
+            self.players.insert(player.id, player);
+        }
+        let team_id = team.id;
+        self.teams.insert(team_id, team);
+
+        if as_own_team {
+            self.own_team_id = team_id;
+            self.dirty_network = true;
+        }
+        self.dirty = true;
+        self.dirty_ui = true;
+        Ok(team_id)
+    }
+
     pub fn get_team(&self, id: TeamId) -> Option<&Team> {
         self.teams.get(&id)
     }
@@ -616,6 +686,12 @@ impl World {
         self.games.get(&id)
     }
 
+    /// Like `get_game`, but also finds network games that ended recently.
+    pub fn get_network_game(&self, id: GameId) -> Option<&Game> {
+        self.get_game(id)
+            .or_else(|| self.ended_network_games.get(&id).map(|(game, _)| game))
+    }
+
     pub fn get_game_or_err(&self, id: GameId) -> AppResult<&Game> {
         self.get_game(id)
             .ok_or(format!("Game {:?} not found", id).into())
@@ -675,8 +751,13 @@ impl World {
         if current_timestamp >= self.last_tick_short_interval + TickInterval::SHORT {
             if self.games.len() > 0 {
                 self.tick_games(current_timestamp)?;
-                self.cleanup_games()?;
+                self.cleanup_games(current_timestamp)?;
             }
+            self.ended_network_games.retain(|_, (_, ended_at)| {
+                current_timestamp < *ended_at + ENDED_NETWORK_GAME_EXPIRY
+            });
+
+            self.tick_leagues(current_timestamp)?;
 
             if !is_simulating && self.games.len() < AUTO_GENERATE_GAMES_NUMBER {
                 self.generate_random_game()?;
@@ -707,6 +788,7 @@ impl World {
             }
             self.tick_player_aging();
             self.modify_players_reputation();
+            self.modify_players_morale();
             self.modify_teams_reputation()?;
             self.last_tick_long_interval += TickInterval::LONG;
         }
@@ -714,14 +796,24 @@ impl World {
         Ok(messages)
     }
 
-    fn cleanup_games(&mut self) -> AppResult<()> {
+    fn cleanup_games(&mut self, current_timestamp: Tick) -> AppResult<()> {
+        let mut dead_player_ids = vec![];
         for (_, game) in self.games.iter() {
             if game.timer.has_ended() {
+                let score = game.get_score();
+                for league in self.leagues.values_mut() {
+                    league.record_result(game.id, score);
+                }
                 for team in [&game.home_team_in_game, &game.away_team_in_game] {
                     //we do not apply end of game logic to peer teams
                     if team.peer_id.is_some() && team.team_id != self.own_team_id {
                         continue;
                     }
+                    let game_result = if team.team_id == game.home_team_in_game.team_id {
+                        score.0.cmp(&score.1)
+                    } else {
+                        score.1.cmp(&score.0)
+                    };
                     for player in team.players.values() {
                         let mut player = player.clone();
                         let stats = team
@@ -729,6 +821,10 @@ impl World {
                             .get(&player.id)
                             .ok_or(format!("Player {:?} not found in team stats", player.id))?;
                         player.apply_end_of_game_logic(stats);
+                        player.apply_morale_update(stats, game_result);
+                        if player.is_dead() {
+                            dead_player_ids.push(player.id);
+                        }
                         self.players.insert(player.id, player);
                     }
                 }
@@ -739,10 +835,7 @@ impl World {
                 {
                     let game_summary = GameSummary::from_game(&game);
                     self.past_games.insert(game_summary.id, game_summary);
-                    save_to_json(
-                        format!("{}{}.json", PERSISTED_GAMES_PREFIX, game.id).as_str(),
-                        &game,
-                    )?;
+                    save_game(&game)?;
                     // Update network that game has ended.
                     self.dirty_network = true;
                 }
@@ -752,7 +845,6 @@ impl World {
                 let home_team_income = 100 + game.attendance * INCOME_PER_ATTENDEE_HOME;
                 let away_team_income = 100 + game.attendance * INCOME_PER_ATTENDEE_AWAY;
                 // Winner team gets reputation bonus
-                let score = game.get_score();
                 let home_team_reputation = if score.0 > score.1 {
                     0.5
                 } else if score.0 < score.1 {
@@ -786,11 +878,56 @@ impl World {
                     self.teams.insert(away_team.id, away_team.clone());
                 }
 
+                if game.home_team_in_game.peer_id.is_some()
+                    && game.away_team_in_game.peer_id.is_some()
+                {
+                    self.ended_network_games
+                        .insert(game.id, (game.clone(), current_timestamp));
+                }
+
                 self.dirty = true;
                 self.dirty_ui = true;
             }
         }
         self.games.retain(|_, game| !game.timer.has_ended());
+
+        for player_id in dead_player_ids {
+            self.bury_player(player_id)?;
+        }
+        Ok(())
+    }
+
+    fn bury_player(&mut self, player_id: PlayerId) -> AppResult<()> {
+        // Dead players leave their team for good and are removed from the world.
+        let player = self.get_player_or_err(player_id)?.clone();
+        if let Some(team_id) = player.team {
+            let mut team = self.get_team_or_err(team_id)?.clone();
+            team.player_ids.retain(|&id| id != player_id);
+            for role in [
+                &mut team.crew_roles.captain,
+                &mut team.crew_roles.doctor,
+                &mut team.crew_roles.pilot,
+            ] {
+                if *role == Some(player_id) {
+                    *role = None;
+                }
+            }
+            team.crew_roles.mozzo.retain(|&id| id != player_id);
+            team.player_ids = Team::best_position_assignment(
+                team.player_ids
+                    .iter()
+                    .filter_map(|&id| self.get_player(id))
+                    .collect(),
+            );
+            team.version += 1;
+            if team.id == self.own_team_id {
+                self.dirty_network = true;
+            }
+            self.teams.insert(team.id, team);
+        }
+        self.players.remove(&player_id);
+        self.dirty = true;
+        self.dirty_ui = true;
         Ok(())
     }
 
@@ -806,6 +943,90 @@ impl World {
         Ok(())
     }
 
+    fn tick_leagues(&mut self, current_timestamp: Tick) -> AppResult<()> {
+        let regions = self
+            .planets
+            .values()
+            .filter(|planet| planet.satellite_of == Some(*GALAXY_ROOT_ID))
+            .map(|planet| planet.id)
+            .collect::<Vec<PlanetId>>();
+        for region in regions {
+            // Regions without enough teams are only checked again after a long interval.
+            let last_generation = self
+                .last_league_generation
+                .get(&region)
+                .copied()
+                .unwrap_or_default();
+            if self.leagues.contains_key(&region)
+                || current_timestamp < last_generation + TickInterval::LONG
+            {
+                continue;
+            }
+            self.last_league_generation.insert(region, current_timestamp);
+            self.generate_league(region, 1, current_timestamp)?;
+        }
+
+        let regions = self.leagues.keys().cloned().collect::<Vec<PlanetId>>();
+        for region in regions {
+            let mut league = self.leagues[&region].clone();
+            if league.phase == LeaguePhase::Finished {
+                if current_timestamp >= league.next_season_at() {
+                    self.generate_league(region, league.season + 1, league.next_season_at())?;
+                }
+                continue;
+            }
+
+            for fixture in league.fixtures.iter_mut() {
+                // Games that got lost (for example in a failed save) are rescheduled.
+                if let Some(game_id) = fixture.game_id {
+                    if !fixture.is_played() && !self.games.contains_key(&game_id) {
+                        fixture.game_id = None;
+                    }
+                }
+
+                let round_start = league.started_at + fixture.round as Tick * TickInterval::LONG;
+                if !fixture.is_pending() || current_timestamp < round_start {
+                    continue;
+                }
+
+                // Games are played on the home planet of the home team,
+                // the away team has to travel there.
+                let venue = self.get_team_or_err(fixture.home_team_id)?.home_planet;
+                let home_ready = self.can_play_league_game(fixture.home_team_id, venue);
+                let away_ready = self.can_play_league_game(fixture.away_team_id, venue);
+                if home_ready.is_ok() && away_ready.is_ok() {
+                    let home_team_in_game =
+                        TeamInGame::from_team_id(fixture.home_team_id, &self.teams, &self.players)?;
+                    let away_team_in_game =
+                        TeamInGame::from_team_id(fixture.away_team_id, &self.teams, &self.players)?;
+                    let game_id = GameId::new();
+                    self.generate_game(
+                        game_id,
+                        home_team_in_game,
+                        away_team_in_game,
+                        current_timestamp + BASE_GAME_START_DELAY,
+                    )?;
+                    fixture.game_id = Some(game_id);
+                } else if current_timestamp >= round_start + TickInterval::LONG {
+                    // Teams that cannot show up until the next round forfeit the game.
+                    fixture.score = match (home_ready.is_ok(), away_ready.is_ok()) {
+                        (true, false) => Some((LEAGUE_FORFEIT_SCORE, 0)),
+                        (false, true) => Some((0, LEAGUE_FORFEIT_SCORE)),
+                        _ => Some((0, 0)),
+                    };
+                }
+            }
+
+            league.advance();
+            if league != self.leagues[&region] {
+                self.leagues.insert(region, league);
+                self.dirty = true;
+                self.dirty_ui = true;
+            }
+        }
+        Ok(())
+    }
+
     fn tick_travel(&mut self, current_timestamp: Tick) -> AppResult<()> {
         let own_team = self.get_own_team()?;
 
@@ -853,14 +1074,30 @@ impl World {
                 let db_player = self
                     .get_player(*player_id)
                     .ok_or(format!("Player {:?} not found", player_id))?;
-                if db_player.tiredness > 0.0 && db_player.tiredness <= MAX_TIREDNESS {
-                    let mut player = db_player.clone();
+                if db_player.tiredness == 0.0 && !db_player.is_injured() {
+                    continue;
+                }
+                let mut player = db_player.clone();
+                if player.tiredness > 0.0 && player.tiredness <= MAX_TIREDNESS {
                     // Recovery outside of games is slower by a factor TICK_SHORT_INTERVAL/TICK_MEDIUM_INTERVAL
-                    // so that it takes 1 minute * 10 * 100 ~ 18 hours to recover from 100% tiredness.
-                    player.tiredness =
-                        (player.tiredness - bonus * RECOVERING_TIREDNESS_PER_SHORT_TICK).max(0.0);
-                    self.players.insert(player.id, player);
+                    // so that it takes about 1 minute * 10 * 100 ~ 18 hours to recover from 100% tiredness.
+                    // Recovery is faster when lightly tired and slower when exhausted.
+                    player.tiredness = (player.tiredness
+                        - bonus
+                            * recovery_phase_factor(player.tiredness)
+                            * RECOVERING_TIREDNESS_PER_SHORT_TICK)
+                        .max(0.0);
+                }
+                if let Some(mut injury) = player.injury {
+                    injury.severity -=
+                        bonus * injury.recovery_factor() * INJURY_RECOVERY_PER_MEDIUM_TICK;
+                    player.injury = if injury.severity > 0.0 {
+                        Some(injury)
+                    } else {
+                        None
+                    };
                 }
+                self.players.insert(player.id, player);
             }
         }
 
@@ -904,6 +1141,77 @@ impl World {
         Ok(BASE_BONUS + BONUS_PER_SKILL * role_fitness)
     }
 
+    /// Returns the top level planet (a direct satellite of the galaxy root) the planet belongs to.
+    pub fn region_of(&self, planet_id: PlanetId) -> AppResult<PlanetId> {
+        let mut planet = self.get_planet_or_err(planet_id)?;
+        while let Some(parent_id) = planet.satellite_of {
+            if parent_id == *GALAXY_ROOT_ID {
+                break;
+            }
+            planet = self.get_planet_or_err(parent_id)?;
+        }
+        Ok(planet.id)
+    }
+
+    fn generate_league(&mut self, region: PlanetId, season: u16, started_at: Tick) -> AppResult<()> {
+        let team_ids = self
+            .teams
+            .values()
+            .filter(|team| {
+                // The own team only plays in leagues if it signed up for them.
+                team.peer_id.is_none()
+                    && (team.id != self.own_team_id || self.own_team_in_leagues)
+                    && self
+                        .region_of(team.home_planet)
+                        .is_ok_and(|team_region| team_region == region)
+            })
+            .map(|team| team.id)
+            .sorted()
+            .collect::<Vec<TeamId>>();
+
+        if team_ids.len() < LEAGUE_MIN_TEAMS {
+            self.leagues.remove(&region);
+            return Ok(());
+        }
+
+        let name = self.get_planet_or_err(region)?.name.clone();
+        self.leagues.insert(
+            region,
+            League::new(region, name, season, team_ids, started_at),
+        );
+        self.dirty = true;
+        self.dirty_ui = true;
+        Ok(())
+    }
+
+    fn can_play_league_game(&self, team_id: TeamId, venue: PlanetId) -> AppResult<()> {
+        let team = self.get_team_or_err(team_id)?;
+        if team.current_game.is_some() {
+            return Err("Team is already playing".into());
+        }
+        match team.current_location {
+            TeamLocation::Travelling { .. } => return Err("Team is travelling".into()),
+            TeamLocation::OnPlanet { planet_id } if planet_id != venue => {
+                return Err("Team is not on the planet of the game".into())
+            }
+            _ => {}
+        }
+        self.can_field_team(team)
+    }
+
+    pub fn can_field_team(&self, team: &Team) -> AppResult<()> {
+        let healthy_players = team
+            .player_ids
+            .iter()
+            .filter_map(|id| self.get_player(*id))
+            .filter(|player| !player.is_injured())
+            .count();
+        if healthy_players < MIN_PLAYERS_PER_TEAM {
+            return Err("Not enough healthy players".into());
+        }
+        Ok(())
+    }
+
     pub fn team_reputation_bonus(&self, team: &Team) -> AppResult<f32> {
         let role_fitness = if let Some(captain_id) = team.crew_roles.captain {
             let captain = self.get_player_or_err(captain_id)?;
@@ -937,6 +1245,20 @@ impl World {
         }
     }
 
+    fn modify_players_morale(&mut self) {
+        // Morale slowly goes back to neutral.
+        for (_, player) in self.players.iter_mut() {
+            if player.peer_id.is_some() {
+                continue;
+            }
+            if player.morale > DEFAULT_MORALE {
+                player.morale = (player.morale - MORALE_DECAY_PER_LONG_TICK).max(DEFAULT_MORALE);
+            } else {
+                player.morale = (player.morale + MORALE_DECAY_PER_LONG_TICK).min(DEFAULT_MORALE);
+            }
+        }
+    }
+
     fn modify_teams_reputation(&mut self) -> AppResult<()> {
         let mut reputation_update: Vec<(TeamId, f32)> = vec![];
         for (_, team) in self.teams.iter() {
@@ -992,11 +1314,14 @@ impl World {
             return Ok(());
         }
 
-        let home_team_in_game = TeamInGame::from_team_id(teams[0].id, &self.teams, &self.players)
-            .ok_or(format!("Team {:?} not found in world", teams[0].id))?;
+        // Teams without enough healthy players cannot play.
+        if self.can_field_team(teams[0]).is_err() || self.can_field_team(teams[1]).is_err() {
+            return Ok(());
+        }
+
+        let home_team_in_game = TeamInGame::from_team_id(teams[0].id, &self.teams, &self.players)?;
 
-        let away_team_in_game = TeamInGame::from_team_id(teams[1].id, &self.teams, &self.players)
-            .ok_or(format!("Team {:?} not found in world", teams[1].id))?;
+        let away_team_in_game = TeamInGame::from_team_id(teams[1].id, &self.teams, &self.players)?;
 
         let starting_at = Tick::now() + BASE_GAME_START_DELAY * rng.gen_range(1..=6);
 
@@ -1155,19 +1480,68 @@ impl World {
             teams: self.teams.clone(),
             players: self.players.clone(),
             planets: self.planets.clone(),
-            games: self.games.clone(),
+            // Finished games are already recorded and games between peers are sent again by them.
+            games: self
+                .games
+                .values()
+                .filter(|game| {
+                    !game.timer.has_ended()
+                        && [&game.home_team_in_game, &game.away_team_in_game]
+                            .iter()
+                            .any(|team| team.peer_id.is_none() || team.team_id == self.own_team_id)
+                })
+                .map(|game| (game.id, game.compacted()))
+                .collect(),
             past_games: self.past_games.clone(),
+            leagues: self.leagues.clone(),
+            own_team_in_leagues: self.own_team_in_leagues,
             ..Default::default()
         };
         w.filter_peer_data(None);
+        // Jerseys are rebuilt from the teams when loading.
+        for player in w.players.values_mut() {
+            if player.team.is_some() {
+                player.image.remove_outfit();
+            }
+        }
         w
     }
+
+    /// Rebuilds the data that `to_store` leaves out.
+    pub fn from_store(mut world: World) -> World {
+        for team in world.teams.values() {
+            let jersey = team.current_jersey();
+            for player_id in team.player_ids.iter() {
+                // Setting the image directly does not bump the player version.
+                if let Some(player) = world.players.get_mut(player_id) {
+                    player.image.set_jersey(&jersey, &player.info);
+                }
+            }
+        }
+        world.games = world
+            .games
+            .into_values()
+            .map(|game| (game.id, game.expanded()))
+            .collect();
+        world
+    }
 }
 
 #[cfg(test)]
 mod test {
     use super::World;
-    use crate::world::constants::BASE_DISTANCES;
+    use crate::engine::types::TeamInGame;
+    use crate::network::types::NetworkTeam;
+    use crate::store::{load_team_file, save_team_file};
+    use crate::test_utils::test_world_and_game;
+    use crate::types::{SystemTimeTick, Tick};
+    use crate::world::constants::{
+        TickInterval, BASE_DISTANCES, DEFAULT_PLANET_ID, LEAGUE_FORFEIT_SCORE,
+        MAX_INJURY_SEVERITY, MIN_PLAYERS_PER_TEAM, SOL_ID,
+    };
+    use crate::world::league::Fixture;
+    use crate::world::types::TeamLocation;
+    use crate::world::utils::TEAM_DATA;
     use rand::{Rng, SeedableRng};
     use rand_chacha::ChaCha8Rng;
 
@@ -1187,6 +1561,295 @@ mod test {
         assert_eq!(v1, v2);
     }
 
+    #[test]
+    fn test_league_scheduling() {
+        let mut world = World::new(None);
+        let rng = &mut ChaCha8Rng::seed_from_u64(world.seed);
+        let t_data = TEAM_DATA.as_ref().unwrap();
+        for (team_name, ship_name) in t_data.names.iter().take(4) {
+            world
+                .generate_random_team(
+                    rng,
+                    DEFAULT_PLANET_ID.clone(),
+                    team_name.clone(),
+                    ship_name.clone(),
+                )
+                .unwrap();
+        }
+
+        let now = Tick::now();
+        world.tick_leagues(now).unwrap();
+        let region = world.region_of(DEFAULT_PLANET_ID.clone()).unwrap();
+        assert_eq!(region, SOL_ID.clone());
+        let league = world.leagues.get(&region).unwrap();
+        assert_eq!(league.team_ids.len(), 4);
+        assert_eq!(world.games.len(), 2);
+
+        for game in world.games.values_mut() {
+            while !game.timer.has_ended() {
+                game.tick();
+            }
+        }
+        world.cleanup_games(Tick::now()).unwrap();
+        let league = world.leagues.get(&region).unwrap();
+        assert_eq!(
+            league.fixtures.iter().filter(|f| f.is_played()).count(),
+            2
+        );
+
+        // A team whose players are all injured cannot field a roster.
+        let next_round = league.current_round();
+        let injured_team_id = league
+            .fixtures
+            .iter()
+            .find(|f| f.round == next_round)
+            .unwrap()
+            .home_team_id;
+        for player_id in world.get_team_or_err(injured_team_id).unwrap().player_ids.clone() {
+            world.players.get_mut(&player_id).unwrap().add_injury(50.0);
+        }
+
+        // The next round is only scheduled once it is due.
+        world.tick_leagues(now).unwrap();
+        assert_eq!(world.games.len(), 0);
+        // Players hurt in the first round can keep other teams home as well.
+        let ready = |world: &World, fixture: &Fixture| {
+            let venue = DEFAULT_PLANET_ID.clone();
+            world.can_play_league_game(fixture.home_team_id, venue).is_ok()
+                && world.can_play_league_game(fixture.away_team_id, venue).is_ok()
+        };
+        let expected = world.leagues[&region]
+            .fixtures
+            .iter()
+            .filter(|f| f.round == next_round && ready(&world, f))
+            .count();
+        world.tick_leagues(now + TickInterval::LONG).unwrap();
+        assert_eq!(world.games.len(), expected);
+
+        // Teams that still cannot show up by the next round forfeit the game.
+        let fixture = world.leagues[&region]
+            .fixtures
+            .iter()
+            .find(|f| f.round == next_round && f.involves(injured_team_id))
+            .unwrap()
+            .clone();
+        assert!(fixture.is_pending());
+        let opponent_ready = world
+            .can_play_league_game(fixture.away_team_id, DEFAULT_PLANET_ID.clone())
+            .is_ok();
+        world.tick_leagues(now + 2 * TickInterval::LONG).unwrap();
+        let fixture = world.leagues[&region]
+            .fixtures
+            .iter()
+            .find(|f| f.round == next_round && f.involves(injured_team_id))
+            .unwrap();
+        let expected_score = if opponent_ready {
+            (0, LEAGUE_FORFEIT_SCORE)
+        } else {
+            (0, 0)
+        };
+        assert_eq!(fixture.score, Some(expected_score));
+    }
+
+    #[test]
+    fn test_league_sign_up() {
+        let mut world = World::new(None);
+        let rng = &mut ChaCha8Rng::seed_from_u64(world.seed);
+        let t_data = TEAM_DATA.as_ref().unwrap();
+        for (team_name, ship_name) in t_data.names.iter().take(4) {
+            world.own_team_id = world
+                .generate_random_team(
+                    rng,
+                    DEFAULT_PLANET_ID.clone(),
+                    team_name.clone(),
+                    ship_name.clone(),
+                )
+                .unwrap();
+        }
+
+        // Without the own team there are not enough teams for a league.
+        let now = Tick::now();
+        world.tick_leagues(now).unwrap();
+        assert!(world.leagues.is_empty());
+
+        // Leagues are not regenerated on every tick.
+        world.own_team_in_leagues = true;
+        world.tick_leagues(now + 1).unwrap();
+        assert!(world.leagues.is_empty());
+        world.tick_leagues(now + TickInterval::LONG).unwrap();
+        let region = world.region_of(DEFAULT_PLANET_ID.clone()).unwrap();
+        assert!(world.leagues[&region].team_ids.contains(&world.own_team_id));
+    }
+
+    #[test]
+    fn test_league_away_team_must_travel() {
+        let mut world = World::new(None);
+        let rng = &mut ChaCha8Rng::seed_from_u64(world.seed);
+        let t_data = TEAM_DATA.as_ref().unwrap();
+        for (team_name, ship_name) in t_data.names.iter().take(4) {
+            world
+                .generate_random_team(
+                    rng,
+                    DEFAULT_PLANET_ID.clone(),
+                    team_name.clone(),
+                    ship_name.clone(),
+                )
+                .unwrap();
+        }
+
+        // One away team is on another planet when the first round starts.
+        let now = Tick::now();
+        world.last_league_generation.insert(SOL_ID.clone(), now);
+        world.generate_league(SOL_ID.clone(), 1, now).unwrap();
+        let fixture = world.leagues[&SOL_ID.clone()].fixtures[0].clone();
+        let other_planet_id = *world
+            .planets
+            .keys()
+            .find(|planet_id| **planet_id != *DEFAULT_PLANET_ID)
+            .unwrap();
+        let mut away_team = world.get_team_or_err(fixture.away_team_id).unwrap().clone();
+        away_team.current_location = TeamLocation::OnPlanet {
+            planet_id: other_planet_id,
+        };
+        world.teams.insert(away_team.id, away_team);
+
+        world.tick_leagues(now).unwrap();
+        assert_eq!(world.games.len(), 1);
+        assert!(world.leagues[&SOL_ID.clone()].fixtures[0].is_pending());
+
+        // Without travelling, the away team forfeits the game.
+        world.tick_leagues(now + TickInterval::LONG).unwrap();
+        assert_eq!(
+            world.leagues[&SOL_ID.clone()].fixtures[0].score,
+            Some((LEAGUE_FORFEIT_SCORE, 0))
+        );
+    }
+
+    #[test]
+    fn test_league_regions() {
+        let mut world = World::new(None);
+        let rng = &mut ChaCha8Rng::seed_from_u64(world.seed);
+        let t_data = TEAM_DATA.as_ref().unwrap();
+        let mut names = t_data.names.iter();
+        for (team_name, ship_name) in names.by_ref().take(4) {
+            world
+                .generate_random_team(
+                    rng,
+                    DEFAULT_PLANET_ID.clone(),
+                    team_name.clone(),
+                    ship_name.clone(),
+                )
+                .unwrap();
+        }
+        let now = Tick::now();
+        world.tick_leagues(now).unwrap();
+        let sol_league = world.leagues[&SOL_ID.clone()].clone();
+
+        // A region that gets enough teams later on gets its own league.
+        let other_planet_id = world
+            .planets
+            .values()
+            .find(|planet| {
+                planet.satellite_of.is_some()
+                    && world.region_of(planet.id).is_ok_and(|region| region != *SOL_ID)
+            })
+            .unwrap()
+            .id;
+        let other_region = world.region_of(other_planet_id).unwrap();
+        for (team_name, ship_name) in names.take(4) {
+            world
+                .generate_random_team(rng, other_planet_id, team_name.clone(), ship_name.clone())
+                .unwrap();
+        }
+        world.tick_leagues(now + 1).unwrap();
+        assert!(!world.leagues.contains_key(&other_region));
+        world.tick_leagues(now + TickInterval::LONG).unwrap();
+        assert_eq!(world.leagues[&other_region].team_ids.len(), 4);
+        assert_eq!(world.leagues[&SOL_ID.clone()].season, sol_league.season);
+        assert_eq!(world.leagues[&SOL_ID.clone()].team_ids, sol_league.team_ids);
+    }
+
+    #[test]
+    fn test_dead_players_leave_the_team() {
+        let (mut world, mut game) = test_world_and_game();
+        while !game.timer.has_ended() {
+            game.tick();
+        }
+        let team_id = game.home_team_in_game.team_id;
+        let player_id = world.get_team_or_err(team_id).unwrap().player_ids[0];
+        let stats = game.home_team_in_game.stats.get_mut(&player_id).unwrap();
+        stats.knocked_out_by = Some(game.away_team_in_game.initial_positions[0]);
+        stats.injury_severity = MAX_INJURY_SEVERITY;
+        world.games.insert(game.id, game);
+        world.cleanup_games(Tick::now()).unwrap();
+
+        assert!(world.get_player(player_id).is_none());
+        let team = world.get_team_or_err(team_id).unwrap();
+        assert!(!team.player_ids.contains(&player_id));
+        assert!(team.player_ids.iter().all(|id| world.get_player(*id).is_some()));
+    }
+
+    #[test]
+    fn test_injured_team_cannot_play() {
+        let (mut world, game) = test_world_and_game();
+        let team = world
+            .get_team_or_err(game.home_team_in_game.team_id)
+            .unwrap()
+            .clone();
+        assert!(world.can_field_team(&team).is_ok());
+        for player_id in team.player_ids.iter().skip(MIN_PLAYERS_PER_TEAM - 1) {
+            world.players.get_mut(player_id).unwrap().add_injury(50.0);
+        }
+
+        assert!(world.can_field_team(&team).is_err());
+        let err = TeamInGame::from_team_id(team.id, &world.teams, &world.players)
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("not have enough healthy players"));
+    }
+
+    #[test]
+    fn test_import_team() {
+        let mut world = World::new(None);
+        let rng = &mut ChaCha8Rng::seed_from_u64(world.seed);
+        let team_id = world
+            .generate_random_team(rng, DEFAULT_PLANET_ID.clone(), "Testen".into(), "Tosten".into())
+            .unwrap();
+        let network_team = NetworkTeam::from_team_id(&world, &team_id).unwrap();
+
+        let path = std::env::temp_dir().join(format!("rebels_team_{}.json", team_id));
+        save_team_file(&network_team, &path).unwrap();
+        let imported = load_team_file(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(
+            serde_json::to_value(&imported).unwrap(),
+            serde_json::to_value(&network_team).unwrap()
+        );
+
+        let mut other_world = World::new(None);
+        other_world.import_team(imported.clone(), false).unwrap();
+        assert!(!other_world.has_own_team());
+        assert!(other_world
+            .get_planet(DEFAULT_PLANET_ID.clone())
+            .unwrap()
+            .teams
+            .contains(&team_id));
+        assert_eq!(
+            other_world.get_players_by_team(other_world.get_team(team_id).unwrap()).unwrap().len(),
+            network_team.players.len()
+        );
+        // The same team cannot be imported twice.
+        assert!(other_world.import_team(imported.clone(), true).is_err());
+
+        let mut own_world = World::new(None);
+        own_world.import_team(imported.clone(), true).unwrap();
+        assert_eq!(own_world.own_team_id, team_id);
+
+        let mut cheater = imported;
+        cheater.team.fuel = cheater.team.spaceship.tank() + 1;
+        assert!(World::new(None).import_team(cheater, false).is_err());
+    }
+
     #[test]
     fn test_distance_between_planets() {
         let world = World::new(None);